@@ -0,0 +1,191 @@
+use satkit::consts::EARTH_RADIUS;
+use satkit::{ITRFCoord, Instant};
+
+use crate::earth_orientation::qteme2itrf_with_mode;
+use crate::output_frames::teme_to_itrf_state;
+use crate::satellite_state::{calculate_atmospheric_density_kg_per_m3, propagate_teme, pythag_3};
+
+/// Sutton-Graves constant for Earth's atmosphere, in kg^0.5/m.
+const SUTTON_GRAVES_K_EARTH: f64 = 1.7415e-4;
+
+/// Converts SGP4's B* (1/earth radii) into the ballistic coefficient C_d·A/m (m²/kg).
+pub const BSTAR_TO_DRAG_AREA_PER_MASS: f64 = 12.741621;
+
+/// Perigees are bracketed on this grid and then refined by bisection.
+const PERIGEE_SEARCH_STEP_SECONDS: f64 = 60.0;
+/// Drag and heating are integrated over a pass on this grid.
+const PASS_SAMPLE_SECONDS: f64 = 10.0;
+const APOGEE_SAMPLE_SECONDS: f64 = 30.0;
+
+/// Aerodynamic loads during one perigee pass, from the apogee before it to the apogee after.
+#[derive(Debug, Clone)]
+pub struct PerigeePass {
+    pub perigee_time: Instant,
+    pub perigee_altitude_km: f64,
+
+    /// Peak ½ρv², relative to the co-rotating atmosphere.
+    pub peak_dynamic_pressure_pa: f64,
+    /// Peak stagnation-point convective heat flux (Sutton-Graves).
+    pub peak_heat_flux_w_per_m2: f64,
+    /// Heat flux integrated over the pass.
+    pub heat_load_j_per_m2: f64,
+    /// Peak of ½ρC_dAv³.
+    pub peak_drag_power_watts: f64,
+    /// Drag deceleration integrated over the pass.
+    pub delta_v_lost_m_per_s: f64,
+
+    pub apogee_altitude_before_km: f64,
+    pub apogee_altitude_after_km: f64,
+}
+
+impl PerigeePass {
+    pub fn apogee_decay_km(&self) -> f64 {
+        self.apogee_altitude_before_km - self.apogee_altitude_after_km
+    }
+}
+
+fn radial_velocity_m_per_s(satkit_tle: &mut satkit::TLE, time: &Instant) -> anyhow::Result<f64> {
+    let (position_teme, velocity_teme) = propagate_teme(satkit_tle, time)?;
+    Ok((0..3)
+        .map(|axis| position_teme[axis] * velocity_teme[axis])
+        .sum::<f64>()
+        / pythag_3(&position_teme))
+}
+
+/// Times in `[from, to)` at which the satellite passes through a minimum of its radius.
+pub fn find_perigee_times(
+    satkit_tle: &mut satkit::TLE,
+    from: &Instant,
+    to: &Instant,
+) -> anyhow::Result<Vec<Instant>> {
+    const BISECTION_ITERATIONS: usize = 30;
+
+    let total_seconds = (*to - *from).as_seconds();
+    let mut perigee_times = Vec::new();
+    let mut previous_time = *from;
+    let mut previous_radial_velocity = radial_velocity_m_per_s(satkit_tle, from)?;
+    let mut elapsed_seconds = 0.0;
+    while elapsed_seconds < total_seconds {
+        elapsed_seconds = (elapsed_seconds + PERIGEE_SEARCH_STEP_SECONDS).min(total_seconds);
+        let time = *from + satkit::Duration::from_seconds(elapsed_seconds);
+        let radial_velocity = radial_velocity_m_per_s(satkit_tle, &time)?;
+
+        if previous_radial_velocity < 0.0 && radial_velocity >= 0.0 {
+            let (mut low, mut high) = (previous_time, time);
+            for _ in 0..BISECTION_ITERATIONS {
+                let mid = low + satkit::Duration::from_seconds((high - low).as_seconds() / 2.0);
+                if radial_velocity_m_per_s(satkit_tle, &mid)? < 0.0 {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            if high < *to {
+                perigee_times.push(high);
+            }
+        }
+        previous_time = time;
+        previous_radial_velocity = radial_velocity;
+    }
+    Ok(perigee_times)
+}
+
+/// Highest radius between `from` and `to`, as an altitude above the equatorial radius.
+fn max_altitude_km(
+    satkit_tle: &mut satkit::TLE,
+    from: &Instant,
+    to: &Instant,
+) -> anyhow::Result<f64> {
+    let total_seconds = (*to - *from).as_seconds();
+    let mut max_radius_m: f64 = 0.0;
+    let mut elapsed_seconds = 0.0;
+    while elapsed_seconds <= total_seconds {
+        let time = *from + satkit::Duration::from_seconds(elapsed_seconds);
+        let (position_teme, _) = propagate_teme(satkit_tle, &time)?;
+        max_radius_m = max_radius_m.max(pythag_3(&position_teme));
+        elapsed_seconds += APOGEE_SAMPLE_SECONDS;
+    }
+    Ok((max_radius_m - EARTH_RADIUS) / 1000.0)
+}
+
+/// Analyse the pass through the perigee at `perigee_time`.
+///
+/// Loads are integrated over the half of the orbit centred on perigee, where practically all
+/// of the drag is. Drag deceleration uses the ballistic coefficient implied by `bstar`, so the
+/// delta-v lost is consistent with the decay SGP4 propagates; `drag_coefficient_area_m2` only
+/// sets the drag power. The apogees are searched for a quarter of an orbit either side of
+/// the expected ones.
+pub fn analyze_perigee_pass(
+    satkit_tle: &mut satkit::TLE,
+    perigee_time: &Instant,
+    period_minutes: f64,
+    nose_radius_m: f64,
+    drag_coefficient_area_m2: f64,
+    bstar: f64,
+    enable_space_weather: bool,
+) -> anyhow::Result<PerigeePass> {
+    let period_seconds = period_minutes * 60.0;
+    let at = |offset_seconds: f64| *perigee_time + satkit::Duration::from_seconds(offset_seconds);
+    let drag_area_per_mass_m2_per_kg = (BSTAR_TO_DRAG_AREA_PER_MASS * bstar).max(0.0);
+
+    let (perigee_position_teme, _) = propagate_teme(satkit_tle, perigee_time)?;
+    let mut pass = PerigeePass {
+        perigee_time: *perigee_time,
+        perigee_altitude_km: (pythag_3(&perigee_position_teme) - EARTH_RADIUS) / 1000.0,
+        peak_dynamic_pressure_pa: 0.0,
+        peak_heat_flux_w_per_m2: 0.0,
+        heat_load_j_per_m2: 0.0,
+        peak_drag_power_watts: 0.0,
+        delta_v_lost_m_per_s: 0.0,
+        apogee_altitude_before_km: max_altitude_km(
+            satkit_tle,
+            &at(-0.75 * period_seconds),
+            &at(-0.25 * period_seconds),
+        )?,
+        apogee_altitude_after_km: max_altitude_km(
+            satkit_tle,
+            &at(0.25 * period_seconds),
+            &at(0.75 * period_seconds),
+        )?,
+    };
+
+    let half_window_seconds = 0.25 * period_seconds;
+    let mut offset_seconds = -half_window_seconds;
+    while offset_seconds <= half_window_seconds {
+        let time = at(offset_seconds);
+        let (position_teme, velocity_teme) = propagate_teme(satkit_tle, &time)?;
+        let itrf_state = teme_to_itrf_state(
+            &qteme2itrf_with_mode(&time).0,
+            &position_teme,
+            &velocity_teme,
+        );
+        let position_itrf = ITRFCoord::from_slice(&itrf_state.position)?;
+        let elevation_km = (pythag_3(&itrf_state.position) - EARTH_RADIUS) / 1000.0;
+        let speed_m_per_s = pythag_3(&itrf_state.velocity);
+
+        let density_kg_per_m3 = calculate_atmospheric_density_kg_per_m3(
+            elevation_km,
+            Some(position_itrf.latitude_deg()),
+            Some(position_itrf.longitude_deg()),
+            Some(time),
+            enable_space_weather,
+        );
+        let dynamic_pressure_pa = 0.5 * density_kg_per_m3 * speed_m_per_s.powi(2);
+        let heat_flux_w_per_m2 = SUTTON_GRAVES_K_EARTH
+            * (density_kg_per_m3 / nose_radius_m).sqrt()
+            * speed_m_per_s.powi(3);
+        // ½ρC_dAv³, as in `calculate_power_from_atmospheric_drag_watts`.
+        let drag_power_watts = dynamic_pressure_pa * drag_coefficient_area_m2 * speed_m_per_s;
+
+        pass.peak_dynamic_pressure_pa = pass.peak_dynamic_pressure_pa.max(dynamic_pressure_pa);
+        pass.peak_heat_flux_w_per_m2 = pass.peak_heat_flux_w_per_m2.max(heat_flux_w_per_m2);
+        pass.peak_drag_power_watts = pass.peak_drag_power_watts.max(drag_power_watts);
+        pass.heat_load_j_per_m2 += heat_flux_w_per_m2 * PASS_SAMPLE_SECONDS;
+        pass.delta_v_lost_m_per_s +=
+            dynamic_pressure_pa * drag_area_per_mass_m2_per_kg * PASS_SAMPLE_SECONDS;
+
+        offset_seconds += PASS_SAMPLE_SECONDS;
+    }
+
+    Ok(pass)
+}
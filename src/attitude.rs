@@ -0,0 +1,172 @@
+use nalgebra::{Matrix3, Rotation3, UnitQuaternion};
+use satkit::Instant;
+use satkit::consts::OMEGA_EARTH;
+use satkit::frametransform::qteme2gcrf;
+use satkit::lpephem::sun::pos_gcrf;
+use satkit::types::{Quaternion, Vec3};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+/// How the satellite's body frame is oriented.
+///
+/// Body axis conventions used by the pointing modes:
+/// - Nadir pointing: +Z to nadir, +X as close to the velocity as possible (the LVLH frame).
+/// - Sun pointing: +Z to the Sun, +X as close to the velocity as possible.
+/// - Velocity aligned: +X along the velocity relative to the atmosphere, +Z as close to nadir as
+///   possible.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum AttitudeMode {
+    #[default]
+    NadirPointing,
+    SunPointing,
+    /// Fixed orientation in GCRF, given by `AttitudeSettings::inertial_roll_pitch_yaw_deg`.
+    InertialFixed,
+    VelocityAligned,
+    /// Starts at the inertial-fixed orientation and spins about body +Z at
+    /// `AttitudeSettings::tumble_rate_deg_per_s`.
+    Tumbling,
+}
+
+impl AttitudeMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AttitudeMode::NadirPointing => "Nadir pointing",
+            AttitudeMode::SunPointing => "Sun pointing",
+            AttitudeMode::InertialFixed => "Inertial fixed",
+            AttitudeMode::VelocityAligned => "Velocity aligned",
+            AttitudeMode::Tumbling => "Tumbling",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AttitudeSettings {
+    pub mode: AttitudeMode,
+
+    /// Body orientation relative to GCRF as intrinsic roll (X), pitch (Y), yaw (Z) angles, in
+    /// degrees. Used by `InertialFixed` and as the starting orientation for `Tumbling`.
+    pub inertial_roll_pitch_yaw_deg: [f64; 3],
+
+    /// Spin rate about body +Z for `Tumbling`, in degrees per second.
+    pub tumble_rate_deg_per_s: f64,
+}
+
+/// Attitude at one simulation step.
+///
+/// The direction vectors are unit vectors in the body frame.
+#[derive(Debug, Clone)]
+pub struct AttitudeState {
+    pub mode: AttitudeMode,
+
+    /// Rotation taking body-frame vectors into GCRF.
+    pub body_to_gcrf: Quaternion,
+
+    /// Direction from the satellite to the Sun.
+    pub sun_body: [f64; 3],
+    /// Direction from the satellite to the Earth's centre.
+    pub nadir_body: [f64; 3],
+    /// Direction of the velocity relative to the co-rotating atmosphere (the ram direction).
+    pub velocity_body: [f64; 3],
+}
+
+impl AttitudeState {
+    /// Quaternion components (w, x, y, z) of `body_to_gcrf`.
+    pub fn quaternion_wxyz(&self) -> [f64; 4] {
+        let q = self.body_to_gcrf.quaternion();
+        [q.w, q.i, q.j, q.k]
+    }
+}
+
+/// Rotation whose columns are the body axes expressed in the reference frame.
+///
+/// `primary` fixes one body axis exactly; `secondary` only picks the rotation about it. If the
+/// two are (nearly) parallel, any perpendicular direction is used instead.
+fn body_frame_from_axes(
+    primary_axis: usize,
+    primary: &Vec3,
+    secondary_axis: usize,
+    secondary: &Vec3,
+) -> Quaternion {
+    let primary = primary.normalize();
+    let mut secondary = secondary - primary * primary.dot(secondary);
+    if secondary.norm() < 1e-9 {
+        let fallback = if primary.x.abs() < 0.9 {
+            Vec3::x()
+        } else {
+            Vec3::y()
+        };
+        secondary = fallback - primary * primary.dot(&fallback);
+    }
+    let secondary = secondary.normalize();
+
+    // Third axis completes a right-handed triad, whichever two axes were given.
+    let third_axis = 3 - primary_axis - secondary_axis;
+    let third = if (secondary_axis + 3 - primary_axis) % 3 == 1 {
+        primary.cross(&secondary)
+    } else {
+        secondary.cross(&primary)
+    };
+
+    let mut columns = [Vec3::zeros(); 3];
+    columns[primary_axis] = primary;
+    columns[secondary_axis] = secondary;
+    columns[third_axis] = third;
+    UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(Matrix3::from_columns(
+        &columns,
+    )))
+}
+
+fn intrinsic_roll_pitch_yaw(roll_pitch_yaw_deg: &[f64; 3]) -> Quaternion {
+    let [roll, pitch, yaw] = roll_pitch_yaw_deg.map(f64::to_radians);
+    UnitQuaternion::from_axis_angle(&Vec3::z_axis(), yaw)
+        * UnitQuaternion::from_axis_angle(&Vec3::y_axis(), pitch)
+        * UnitQuaternion::from_axis_angle(&Vec3::x_axis(), roll)
+}
+
+/// Compute the attitude at `time` for a satellite at the given TEME state.
+///
+/// `start_time` is the beginning of the run, from which a tumble is measured.
+pub fn compute_attitude(
+    settings: &AttitudeSettings,
+    position_teme_m: &[f64; 3],
+    velocity_teme_m_per_s: &[f64; 3],
+    time: &Instant,
+    start_time: &Instant,
+) -> AttitudeState {
+    let teme_to_gcrf = qteme2gcrf(time);
+    let position_gcrf = teme_to_gcrf * Vec3::from_row_slice(position_teme_m);
+    let velocity_gcrf = teme_to_gcrf * Vec3::from_row_slice(velocity_teme_m_per_s);
+
+    let nadir_gcrf = -position_gcrf;
+    // Atmosphere co-rotates with the Earth; the pole tilt between TEME/GCRF z and the
+    // rotation axis is negligible here.
+    let ram_gcrf = velocity_gcrf - Vec3::new(0.0, 0.0, OMEGA_EARTH).cross(&position_gcrf);
+    let sun_gcrf = pos_gcrf(time) - position_gcrf;
+
+    let body_to_gcrf = match settings.mode {
+        AttitudeMode::NadirPointing => body_frame_from_axes(2, &nadir_gcrf, 0, &velocity_gcrf),
+        AttitudeMode::SunPointing => body_frame_from_axes(2, &sun_gcrf, 0, &velocity_gcrf),
+        AttitudeMode::VelocityAligned => body_frame_from_axes(0, &ram_gcrf, 2, &nadir_gcrf),
+        AttitudeMode::InertialFixed => {
+            intrinsic_roll_pitch_yaw(&settings.inertial_roll_pitch_yaw_deg)
+        }
+        AttitudeMode::Tumbling => {
+            let spin_angle_rad =
+                settings.tumble_rate_deg_per_s.to_radians() * (*time - *start_time).as_seconds();
+            intrinsic_roll_pitch_yaw(&settings.inertial_roll_pitch_yaw_deg)
+                * UnitQuaternion::from_axis_angle(&Vec3::z_axis(), spin_angle_rad)
+        }
+    };
+
+    let to_body = |v: &Vec3| {
+        let body = body_to_gcrf.inverse() * v.normalize();
+        [body.x, body.y, body.z]
+    };
+    AttitudeState {
+        mode: settings.mode,
+        body_to_gcrf,
+        sun_body: to_body(&sun_gcrf),
+        nadir_body: to_body(&nadir_gcrf),
+        velocity_body: to_body(&ram_gcrf),
+    }
+}
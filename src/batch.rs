@@ -0,0 +1,69 @@
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Run `job(context, index)` for every index in `0..count` on up to `threads` threads, and
+/// return the results in index order. A job that panics gives an `Err` for its index instead
+/// of taking down the batch. `completed` counts finished jobs, for progress.
+pub fn run_batch<C, T, F>(
+    context: &C,
+    count: usize,
+    threads: usize,
+    completed: &AtomicUsize,
+    job: F,
+) -> anyhow::Result<Vec<Result<T, String>>>
+where
+    C: Clone + Send,
+    T: Send,
+    F: Fn(&C, usize) -> Result<T, String> + Sync,
+{
+    let next_index = AtomicUsize::new(0);
+    let results = Mutex::new(
+        (0..count)
+            .map(|_| None)
+            .collect::<Vec<Option<Result<T, String>>>>(),
+    );
+    let (next_index, results_ref, job) = (&next_index, &results, &job);
+    std::thread::scope(|scope| {
+        for _ in 0..threads.min(count) {
+            // Ground stations cache their position in a non-`Sync` cell, so every thread works
+            // from its own copy of the context.
+            let context = context.clone();
+            scope.spawn(move || {
+                loop {
+                    let index = next_index.fetch_add(1, Ordering::Relaxed);
+                    if index >= count {
+                        break;
+                    }
+                    let result =
+                        std::panic::catch_unwind(AssertUnwindSafe(|| job(&context, index)))
+                            .unwrap_or_else(|panic| Err(panic_message(panic.as_ref())));
+                    if let Ok(mut results) = results_ref.lock() {
+                        results[index] = Some(result);
+                    }
+                    completed.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    // Callers zip the results with their inputs, so a missing one must not shift the rest.
+    results
+        .into_inner()
+        .map_err(|_| anyhow::anyhow!("Poisoned mutex lock"))?
+        .into_iter()
+        .enumerate()
+        .map(|(index, result)| {
+            result.ok_or_else(|| anyhow::anyhow!("The result of job {index} was lost"))
+        })
+        .collect()
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    let message = panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause");
+    format!("Panicked: {message}")
+}
@@ -0,0 +1,407 @@
+use std::sync::atomic::AtomicUsize;
+
+use satkit::Instant;
+
+use crate::batch::run_batch;
+use crate::collision_probability::{CollisionProbability, conjunction_probability};
+use crate::conjunction::{Conjunction, range_rate, refine_tca};
+use crate::initial_state_model::{InitialSimulationState, TleData};
+use crate::orbital_elements::rtn_basis;
+use crate::propulsion::{ScheduledBurn, ThrustDirection, Thruster};
+use crate::satellite_state::{SimulationRun, propagate_teme, pythag_3};
+use crate::uncertainty::TleAgeErrorGrowth;
+
+/// Name of the burn added to the schedule for each candidate.
+pub const AVOIDANCE_BURN_NAME: &str = "Collision avoidance";
+
+/// Half-width of the window around the original TCA searched for the new one. An along-track
+/// burn shifts the arrival time by roughly 3·Δv·t / v, a few seconds per m/s and day of lead.
+const TCA_SEARCH_SECONDS: f64 = 600.0;
+
+/// Grid of candidate burns: every lead time before TCA, with every delta-v both along and
+/// against the velocity.
+#[derive(Debug, Clone)]
+pub struct AvoidanceSettings {
+    pub lead_times_hours: Vec<f64>,
+    pub delta_v_magnitudes_m_per_s: Vec<f64>,
+    /// Pc at or below which a manoeuvre is acceptable.
+    pub acceptable_pc: f64,
+    pub threads: usize,
+}
+
+impl AvoidanceSettings {
+    /// Lead times and delta-v are spaced evenly from `max / steps` up to `max`.
+    pub fn new(
+        max_lead_time_hours: f64,
+        lead_time_steps: u32,
+        max_delta_v_m_per_s: f64,
+        delta_v_steps: u32,
+        acceptable_pc: f64,
+        threads: usize,
+    ) -> Result<Self, String> {
+        if max_lead_time_hours <= 0.0 {
+            return Err("Maximum lead time must be > 0".into());
+        }
+        if max_delta_v_m_per_s <= 0.0 {
+            return Err("Maximum delta-v must be > 0".into());
+        }
+        if lead_time_steps == 0 || delta_v_steps == 0 {
+            return Err("Steps must be >= 1".into());
+        }
+        if !(0.0..=1.0).contains(&acceptable_pc) {
+            return Err("Acceptable Pc must be between 0 and 1".into());
+        }
+        if threads == 0 {
+            return Err("Threads must be >= 1".into());
+        }
+        let spaced = |max: f64, steps: u32| {
+            (1..=steps)
+                .map(|i| max * i as f64 / steps as f64)
+                .collect::<Vec<_>>()
+        };
+        Ok(Self {
+            lead_times_hours: spaced(max_lead_time_hours, lead_time_steps),
+            delta_v_magnitudes_m_per_s: spaced(max_delta_v_m_per_s, delta_v_steps),
+            acceptable_pc,
+            threads,
+        })
+    }
+}
+
+/// Encounter after a candidate burn.
+#[derive(Debug, Clone)]
+pub struct AvoidanceOutcome {
+    /// The burn added to the schedule (`None` for the encounter without a burn).
+    pub burn: Option<ScheduledBurn>,
+    /// Delta-v and propellant of the burn as simulated.
+    pub delta_v_m_per_s: f64,
+    pub propellant_kg: f64,
+    pub tca: Instant,
+    /// Position of the object relative to the satellite at the new TCA, along the satellite's
+    /// radial/transverse/normal axes.
+    pub miss_rtn_m: [f64; 3],
+    pub probability: CollisionProbability,
+}
+
+#[derive(Debug, Clone)]
+pub struct AvoidanceCandidate {
+    pub lead_time_hours: f64,
+    /// Positive along the velocity, negative against it.
+    pub planned_delta_v_m_per_s: f64,
+    pub outcome: Result<AvoidanceOutcome, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AvoidanceResult {
+    pub acceptable_pc: f64,
+    /// The encounter without a burn, as the simulation propagates it.
+    pub nominal: AvoidanceOutcome,
+    /// Lead time by lead time, each in order of signed delta-v.
+    pub candidates: Vec<AvoidanceCandidate>,
+}
+
+impl AvoidanceResult {
+    /// Candidate with the least delta-v that brings Pc (Foster) to the acceptable level; among
+    /// equal ones, the latest burn.
+    pub fn cheapest_acceptable(&self) -> Option<&AvoidanceCandidate> {
+        self.candidates
+            .iter()
+            .filter(|candidate| {
+                candidate
+                    .outcome
+                    .as_ref()
+                    .is_ok_and(|outcome| outcome.probability.foster <= self.acceptable_pc)
+            })
+            .min_by(|a, b| {
+                let key = |candidate: &AvoidanceCandidate| {
+                    (
+                        candidate.planned_delta_v_m_per_s.abs(),
+                        candidate.lead_time_hours,
+                    )
+                };
+                key(a)
+                    .partial_cmp(&key(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
+}
+
+/// Along-track burn of `delta_v_m_per_s` (signed) centred `lead_time_hours` before `tca`,
+/// sized for the thruster at its wet mass.
+fn candidate_burn(
+    thruster: &Thruster,
+    epoch: &Instant,
+    tca: &Instant,
+    lead_time_hours: f64,
+    delta_v_m_per_s: f64,
+) -> Result<ScheduledBurn, String> {
+    let propellant_kg = thruster.wet_mass_kg
+        * (1.0 - (-delta_v_m_per_s.abs() / thruster.exhaust_velocity_m_per_s()).exp());
+    let duration_minutes = propellant_kg / thruster.mass_flow_kg_per_s() / 60.0;
+    if duration_minutes / 2.0 >= lead_time_hours * 60.0 {
+        return Err("The burn would not finish before TCA".into());
+    }
+    let centre = *tca - satkit::Duration::from_hours(lead_time_hours);
+    ScheduledBurn::new(
+        AVOIDANCE_BURN_NAME.to_string(),
+        (centre - *epoch).as_days() - duration_minutes / 2.0 / 1440.0,
+        duration_minutes,
+        if delta_v_m_per_s >= 0.0 {
+            ThrustDirection::AlongVelocity
+        } else {
+            ThrustDirection::AntiVelocity
+        },
+    )
+    .map_err(|_| "The burn would start before the TLE epoch".to_string())
+}
+
+/// Closest approach of `primary_tle` to the object of `conjunction` near its original TCA, with
+/// the covariances of the original element sets (the burn's execution error is not modelled).
+/// Fails unless the objects are closing at the start of the search window and opening at its
+/// end, i.e. unless the window brackets the closest approach.
+fn encounter(
+    primary_tle: &TleData,
+    conjunction: &Conjunction,
+    primary_epoch: &Instant,
+    growth: &TleAgeErrorGrowth,
+    hard_body_radius_m: f64,
+) -> anyhow::Result<(Instant, [f64; 3], CollisionProbability)> {
+    let mut primary = primary_tle.to_satkit_tle();
+    let mut object = conjunction.object_tle.to_satkit_tle();
+    let window = satkit::Duration::from_seconds(TCA_SEARCH_SECONDS);
+    let (low, high) = (conjunction.tca - window, conjunction.tca + window);
+    let closing_at_low = range_rate(
+        &propagate_teme(&mut primary, &low)?,
+        &propagate_teme(&mut object, &low)?,
+    ) < 0.0;
+    let closing_at_high = range_rate(
+        &propagate_teme(&mut primary, &high)?,
+        &propagate_teme(&mut object, &high)?,
+    ) < 0.0;
+    if !closing_at_low || closing_at_high {
+        return Err(anyhow::anyhow!(
+            "The closest approach moved more than {TCA_SEARCH_SECONDS:.0} s from the original TCA"
+        ));
+    }
+    let tca = refine_tca(&mut primary, &mut object, low, high)?;
+    let (primary_position, primary_velocity) = propagate_teme(&mut primary, &tca)?;
+    let (object_position, object_velocity) = propagate_teme(&mut object, &tca)?;
+    let offset: [f64; 3] = std::array::from_fn(|a| object_position[a] - primary_position[a]);
+    let miss_rtn_m = rtn_basis(&primary_position, &primary_velocity)
+        .map(|unit| unit.iter().zip(&offset).map(|(u, o)| u * o).sum::<f64>());
+    let moved = Conjunction {
+        tca,
+        miss_distance_m: pythag_3(&offset),
+        miss_rtn_m,
+        primary_state_teme: (primary_position, primary_velocity),
+        object_state_teme: (object_position, object_velocity),
+        ..conjunction.clone()
+    };
+    let probability = conjunction_probability(&moved, primary_epoch, growth, hard_body_radius_m)?;
+    Ok((tca, miss_rtn_m, probability))
+}
+
+/// Run `initial` until `stop`, and return the re-fitted element set with the delta-v and
+/// propellant of the avoidance burn.
+fn run_until(
+    initial: InitialSimulationState,
+    stop: &Instant,
+) -> anyhow::Result<(TleData, f64, f64)> {
+    let mut run = SimulationRun::new(initial, false)?;
+    let mut delta_v_m_per_s = 0.0;
+    let mut propellant_kg = 0.0;
+    loop {
+        let telemetry = run.step()?;
+        if telemetry.is_deorbited {
+            return Err(anyhow::anyhow!(
+                "The satellite reentered before the encounter"
+            ));
+        }
+        for segment in telemetry
+            .propulsion
+            .iter()
+            .flat_map(|propulsion| &propulsion.burn_segments)
+            .filter(|segment| segment.name == AVOIDANCE_BURN_NAME)
+        {
+            delta_v_m_per_s += segment.delta_v_m_per_s;
+            propellant_kg += segment.propellant_used_kg;
+        }
+        if telemetry.time >= *stop {
+            break;
+        }
+    }
+    Ok((run.current_tle().clone(), delta_v_m_per_s, propellant_kg))
+}
+
+/// Run `initial` (with the candidate burn in its schedule) until the burn is over, or until
+/// `horizon` if that is later, and return the encounter from the re-fitted element set.
+fn simulate_candidate(
+    initial: InitialSimulationState,
+    burn: ScheduledBurn,
+    horizon: &Instant,
+    conjunction: &Conjunction,
+    growth: &TleAgeErrorGrowth,
+    hard_body_radius_m: f64,
+) -> anyhow::Result<AvoidanceOutcome> {
+    let epoch = initial.tle.epoch;
+    let burn_end = burn.end_time(&epoch);
+    let stop = if burn_end > *horizon {
+        burn_end
+    } else {
+        *horizon
+    };
+    let (tle, delta_v_m_per_s, propellant_kg) = run_until(initial, &stop)?;
+    let (tca, miss_rtn_m, probability) =
+        encounter(&tle, conjunction, &epoch, growth, hard_body_radius_m)?;
+    Ok(AvoidanceOutcome {
+        burn: Some(burn),
+        delta_v_m_per_s,
+        propellant_kg,
+        tca,
+        miss_rtn_m,
+        probability,
+    })
+}
+
+/// Search along-track avoidance burns for `conjunction` of the satellite of `nominal`.
+///
+/// Each candidate is added to the satellite's burn schedule and simulated with its thruster
+/// through `SimulationRun`, so the burn is finite and the orbit re-fitted as in a normal run.
+/// The new closest approach is then found with SGP4 from the element set after the burn, and
+/// its Pc computed with the TLE-age covariances of the original sets. The encounter without a
+/// burn is propagated the same way through the existing schedule up to the latest candidate
+/// burn, so every outcome carries the same scheduled burns; a scheduled burn ending after that
+/// and before the encounter is rejected. The constellation and the run's own uncertainty
+/// propagation are left out. With a TLE history, the search starts from the set it selects at
+/// the earliest candidate burn, as burns cannot be combined with a history. `completed` counts
+/// finished candidates.
+pub fn search_avoidance_maneuvers(
+    nominal: &InitialSimulationState,
+    conjunction: &Conjunction,
+    growth: &TleAgeErrorGrowth,
+    hard_body_radius_m: f64,
+    settings: &AvoidanceSettings,
+    completed: &AtomicUsize,
+) -> anyhow::Result<AvoidanceResult> {
+    let Some(thruster) = &nominal.satellite.thruster else {
+        return Err(anyhow::anyhow!("Avoidance manoeuvres require a thruster"));
+    };
+    let mut base = nominal.clone();
+    base.constellation.clear();
+    base.uncertainty = None;
+    if let Some(history) = base.tle_history.take() {
+        let max_lead_time_hours = settings
+            .lead_times_hours
+            .iter()
+            .copied()
+            .fold(0.0, f64::max);
+        let earliest_burn = conjunction.tca - satkit::Duration::from_hours(max_lead_time_hours);
+        base.tle = history
+            .sets
+            .get(history.select(&earliest_burn))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("The TLE history has no element sets"))?;
+        if base.tle.epoch >= conjunction.tca {
+            return Err(anyhow::anyhow!(
+                "No element set of the TLE history precedes the conjunction"
+            ));
+        }
+    }
+    let epoch = base.tle.epoch;
+
+    let min_lead_time_hours = settings
+        .lead_times_hours
+        .iter()
+        .copied()
+        .fold(f64::INFINITY, f64::min);
+    let horizon = conjunction.tca - satkit::Duration::from_hours(min_lead_time_hours);
+    let search_end = conjunction.tca + satkit::Duration::from_seconds(TCA_SEARCH_SECONDS);
+    if let Some(burn) = base
+        .satellite
+        .burn_schedule
+        .iter()
+        .find(|burn| burn.end_time(&epoch) > horizon && burn.start_time(&epoch) < search_end)
+    {
+        return Err(anyhow::anyhow!(
+            "Scheduled burn '{}' ends less than {min_lead_time_hours:.1} h before TCA; remove it or \
+             increase the lead times",
+            burn.name
+        ));
+    }
+
+    let (nominal_tle, _, _) = run_until(base.clone(), &horizon)?;
+    let (tca, miss_rtn_m, probability) = encounter(
+        &nominal_tle,
+        conjunction,
+        &epoch,
+        growth,
+        hard_body_radius_m,
+    )?;
+    let nominal_outcome = AvoidanceOutcome {
+        burn: None,
+        delta_v_m_per_s: 0.0,
+        propellant_kg: 0.0,
+        tca,
+        miss_rtn_m,
+        probability,
+    };
+
+    let signed_delta_vs = settings
+        .delta_v_magnitudes_m_per_s
+        .iter()
+        .rev()
+        .map(|dv| -dv)
+        .chain(settings.delta_v_magnitudes_m_per_s.iter().copied())
+        .collect::<Vec<_>>();
+    let grid = settings
+        .lead_times_hours
+        .iter()
+        .flat_map(|lead| signed_delta_vs.iter().map(move |dv| (*lead, *dv)))
+        .collect::<Vec<_>>();
+
+    let outcomes = run_batch(
+        &base,
+        grid.len(),
+        settings.threads,
+        completed,
+        |base, index| {
+            let (lead_time_hours, delta_v_m_per_s) = grid[index];
+            let burn = candidate_burn(
+                thruster,
+                &epoch,
+                &conjunction.tca,
+                lead_time_hours,
+                delta_v_m_per_s,
+            )?;
+            let mut initial = base.clone();
+            initial.satellite.burn_schedule.push(burn.clone());
+            simulate_candidate(
+                initial,
+                burn,
+                &horizon,
+                conjunction,
+                growth,
+                hard_body_radius_m,
+            )
+            .map_err(|e| e.to_string())
+        },
+    )?;
+
+    let candidates = grid
+        .into_iter()
+        .zip(outcomes)
+        .map(
+            |((lead_time_hours, planned_delta_v_m_per_s), outcome)| AvoidanceCandidate {
+                lead_time_hours,
+                planned_delta_v_m_per_s,
+                outcome,
+            },
+        )
+        .collect();
+
+    Ok(AvoidanceResult {
+        acceptable_pc: settings.acceptable_pc,
+        nominal: nominal_outcome,
+        candidates,
+    })
+}
@@ -0,0 +1,475 @@
+use satkit::Instant;
+use satkit::consts::OMEGA_EARTH;
+
+use crate::conjunction::Conjunction;
+use crate::orbital_elements::rtn_basis;
+use crate::satellite_state::pythag_3;
+use crate::uncertainty::TleAgeErrorGrowth;
+
+/// Radial steps of the Foster integration over the hard-body circle.
+const FOSTER_RADIAL_STEPS: usize = 64;
+/// Angular steps of the Foster integration over the hard-body circle.
+const FOSTER_ANGULAR_STEPS: usize = 128;
+/// Simpson intervals of the Alfano integration across the hard-body circle.
+const ALFANO_STEPS: usize = 200;
+/// Terms of the Chan series beyond the peak of its Poisson weights.
+const CHAN_EXTRA_TERMS: usize = 60;
+
+/// State and position covariance of one object at the time of closest approach, in a common
+/// inertial frame.
+#[derive(Debug, Clone)]
+pub struct ObjectAtTca {
+    pub position_m: [f64; 3],
+    pub velocity_m_per_s: [f64; 3],
+    /// Position covariance along the object's own radial/transverse/normal axes, in m².
+    pub covariance_rtn_m2: [[f64; 3]; 3],
+}
+
+impl ObjectAtTca {
+    /// Position covariance rotated to the inertial axes.
+    fn covariance_inertial_m2(&self) -> [[f64; 3]; 3] {
+        let basis = rtn_basis(&self.position_m, &self.velocity_m_per_s);
+        std::array::from_fn(|i| {
+            std::array::from_fn(|j| {
+                let mut sum = 0.0;
+                for (a, row) in self.covariance_rtn_m2.iter().enumerate() {
+                    for (b, value) in row.iter().enumerate() {
+                        sum += basis[a][i] * value * basis[b][j];
+                    }
+                }
+                sum
+            })
+        })
+    }
+}
+
+/// 2D probability of collision in the encounter plane, by three methods that should agree to
+/// within their numerical accuracy.
+#[derive(Debug, Clone)]
+pub struct CollisionProbability {
+    pub miss_distance_m: f64,
+    pub relative_speed_m_per_s: f64,
+    /// 1-sigma axes of the combined covariance projected onto the encounter plane.
+    pub sigma_major_m: f64,
+    pub sigma_minor_m: f64,
+    /// Sum of the hard-body radii of both objects.
+    pub hard_body_radius_m: f64,
+    /// Numerical integration of the Gaussian over the hard-body circle.
+    pub foster: f64,
+    /// Chan's series for the integral over a circle of equal area.
+    pub chan: f64,
+    /// Alfano's one-dimensional integral of error functions across the circle.
+    pub alfano: f64,
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn unit(v: &[f64; 3]) -> [f64; 3] {
+    let norm = pythag_3(v);
+    v.map(|c| c / norm)
+}
+
+/// Complementary error function (Numerical Recipes' `erfcc`, relative error below 1.2e-7).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let r = t
+        * (-z * z - 1.265_512_23
+            + t * (1.000_023_68
+                + t * (0.374_091_96
+                    + t * (0.096_784_18
+                        + t * (-0.186_288_06
+                            + t * (0.278_868_07
+                                + t * (-1.135_203_98
+                                    + t * (1.488_515_87
+                                        + t * (-0.822_152_23 + t * 0.170_872_77)))))))))
+            .exp();
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+/// erf(a) - erf(b), taken from the tail on the side of the arguments so that small
+/// differences of values near ±1 keep their precision.
+fn erf_difference(a: f64, b: f64) -> f64 {
+    if a <= 0.0 && b <= 0.0 {
+        erfc(-a) - erfc(-b)
+    } else {
+        erfc(b) - erfc(a)
+    }
+}
+
+/// Foster: the Gaussian centred on the miss vector, integrated in polar coordinates over the
+/// hard-body circle about the origin, with Simpson's rule along the radius.
+fn foster(miss: [f64; 2], sigma: [f64; 2], radius: f64) -> f64 {
+    let simpson_weight = |i: usize, n: usize| match i {
+        0 => 1.0,
+        i if i == n => 1.0,
+        i if i % 2 == 1 => 4.0,
+        _ => 2.0,
+    };
+    let dr = radius / FOSTER_RADIAL_STEPS as f64;
+    let dtheta = 2.0 * std::f64::consts::PI / FOSTER_ANGULAR_STEPS as f64;
+    let mut sum = 0.0;
+    for i in 0..=FOSTER_RADIAL_STEPS {
+        let r = i as f64 * dr;
+        // The angular integrand is periodic, so the plain sum is the most accurate rule.
+        let ring = (0..FOSTER_ANGULAR_STEPS)
+            .map(|j| {
+                let (s, c) = (j as f64 * dtheta).sin_cos();
+                let x = (r * c - miss[0]) / sigma[0];
+                let y = (r * s - miss[1]) / sigma[1];
+                (-0.5 * (x * x + y * y)).exp()
+            })
+            .sum::<f64>()
+            * dtheta;
+        sum += simpson_weight(i, FOSTER_RADIAL_STEPS) * r * ring;
+    }
+    sum * dr / 3.0 / (2.0 * std::f64::consts::PI * sigma[0] * sigma[1])
+}
+
+/// Chan: with u = R²/(σx·σy) and v = x²/σx² + y²/σy²,
+/// Pc = e^(-v/2) Σ (v/2)^m/m! · (1 - e^(-u/2) Σ_{k≤m} (u/2)^k/k!).
+fn chan(miss: [f64; 2], sigma: [f64; 2], radius: f64) -> f64 {
+    let u = radius * radius / (sigma[0] * sigma[1]);
+    let v = (miss[0] / sigma[0]).powi(2) + (miss[1] / sigma[1]).powi(2);
+    let terms = (v / 2.0).ceil() as usize + CHAN_EXTRA_TERMS;
+    let mut poisson_v = (-v / 2.0).exp();
+    let mut poisson_u = (-u / 2.0).exp();
+    let mut cumulative_u = poisson_u;
+    let mut pc = 0.0;
+    for m in 0..terms {
+        if m > 0 {
+            poisson_v *= v / 2.0 / m as f64;
+            poisson_u *= u / 2.0 / m as f64;
+            cumulative_u += poisson_u;
+        }
+        pc += poisson_v * (1.0 - cumulative_u).max(0.0);
+    }
+    pc
+}
+
+/// Alfano: the integral over y done in closed form, leaving
+/// Pc = 1/(√(8π)·σx) ∫ [erf((y + √(R²-x²))/(√2·σy)) - erf((y - √(R²-x²))/(√2·σy))]
+/// · e^(-(x - xm)²/(2σx²)) dx over -R..R, here integrated with Simpson's rule.
+fn alfano(miss: [f64; 2], sigma: [f64; 2], radius: f64) -> f64 {
+    let dx = 2.0 * radius / ALFANO_STEPS as f64;
+    let mut sum = 0.0;
+    for i in 0..=ALFANO_STEPS {
+        let x = -radius + i as f64 * dx;
+        let half_chord = (radius * radius - x * x).max(0.0).sqrt();
+        let scale = std::f64::consts::SQRT_2 * sigma[1];
+        let value = erf_difference(
+            (miss[1] + half_chord) / scale,
+            (miss[1] - half_chord) / scale,
+        ) * (-0.5 * ((x - miss[0]) / sigma[0]).powi(2)).exp();
+        let weight = match i {
+            0 => 1.0,
+            i if i == ALFANO_STEPS => 1.0,
+            i if i % 2 == 1 => 4.0,
+            _ => 2.0,
+        };
+        sum += weight * value;
+    }
+    sum * dx / 3.0 / ((8.0 * std::f64::consts::PI).sqrt() * sigma[0])
+}
+
+/// Probability that `primary` and `secondary` come within `hard_body_radius_m` of each other.
+///
+/// The encounter is taken as short and rectilinear: the relative motion is a straight line
+/// through the encounter plane normal to the relative velocity, and the combined covariance
+/// (the sum of both objects') is projected onto that plane. Slow encounters, where the
+/// covariance or the relative velocity change over the encounter, need a 3D method instead.
+pub fn collision_probability(
+    primary: &ObjectAtTca,
+    secondary: &ObjectAtTca,
+    hard_body_radius_m: f64,
+) -> anyhow::Result<CollisionProbability> {
+    if hard_body_radius_m <= 0.0 {
+        return Err(anyhow::anyhow!("The hard-body radius must be > 0"));
+    }
+    let miss: [f64; 3] = std::array::from_fn(|a| secondary.position_m[a] - primary.position_m[a]);
+    let relative_velocity: [f64; 3] =
+        std::array::from_fn(|a| secondary.velocity_m_per_s[a] - primary.velocity_m_per_s[a]);
+    let relative_speed_m_per_s = pythag_3(&relative_velocity);
+    if relative_speed_m_per_s <= 0.0 {
+        return Err(anyhow::anyhow!("The objects have no relative velocity"));
+    }
+
+    // Encounter frame: z along the relative velocity, x along the miss vector in the plane.
+    let z = unit(&relative_velocity);
+    let along = dot(&miss, &z);
+    let in_plane: [f64; 3] = std::array::from_fn(|a| miss[a] - along * z[a]);
+    let x = if pythag_3(&in_plane) > 0.0 {
+        unit(&in_plane)
+    } else {
+        // Direct hit: any in-plane direction will do.
+        let helper = if z[0].abs() < 0.9 {
+            [1.0, 0.0, 0.0]
+        } else {
+            [0.0, 1.0, 0.0]
+        };
+        let d = dot(&helper, &z);
+        unit(&std::array::from_fn(|a| helper[a] - d * z[a]))
+    };
+    let y = [
+        z[1] * x[2] - z[2] * x[1],
+        z[2] * x[0] - z[0] * x[2],
+        z[0] * x[1] - z[1] * x[0],
+    ];
+
+    let first = primary.covariance_inertial_m2();
+    let second = secondary.covariance_inertial_m2();
+    let combined: [[f64; 3]; 3] =
+        std::array::from_fn(|i| std::array::from_fn(|j| first[i][j] + second[i][j]));
+    let project = |a: &[f64; 3], b: &[f64; 3]| {
+        let mut sum = 0.0;
+        for (i, row) in combined.iter().enumerate() {
+            sum += a[i] * dot(row, b);
+        }
+        sum
+    };
+    let (cxx, cxy, cyy) = (project(&x, &x), project(&x, &y), project(&y, &y));
+
+    // Principal axes of the 2x2 covariance.
+    let mean = (cxx + cyy) / 2.0;
+    let spread = (((cxx - cyy) / 2.0).powi(2) + cxy * cxy).sqrt();
+    let (major_variance, minor_variance) = (mean + spread, mean - spread);
+    if minor_variance <= 0.0 {
+        return Err(anyhow::anyhow!(
+            "The combined covariance is degenerate in the encounter plane"
+        ));
+    }
+    let angle = 0.5 * (2.0 * cxy).atan2(cxx - cyy);
+    let miss_distance_m = pythag_3(&in_plane);
+    let (s, c) = angle.sin_cos();
+    let miss_principal = [miss_distance_m * c, -miss_distance_m * s];
+    let sigma = [major_variance.sqrt(), minor_variance.sqrt()];
+
+    Ok(CollisionProbability {
+        miss_distance_m: pythag_3(&miss),
+        relative_speed_m_per_s,
+        sigma_major_m: sigma[0],
+        sigma_minor_m: sigma[1],
+        hard_body_radius_m,
+        foster: foster(miss_principal, sigma, hard_body_radius_m),
+        chan: chan(miss_principal, sigma, hard_body_radius_m),
+        alfano: alfano(miss_principal, sigma, hard_body_radius_m),
+    })
+}
+
+/// Diagonal RTN covariance of a TLE of the given age under `growth`.
+fn tle_age_covariance_m2(growth: &TleAgeErrorGrowth, age_days: f64) -> [[f64; 3]; 3] {
+    let uncertainty = growth.uncertainty(age_days);
+    [
+        [uncertainty.radial_sigma_m.powi(2), 0.0, 0.0],
+        [0.0, uncertainty.along_track_sigma_m.powi(2), 0.0],
+        [0.0, 0.0, uncertainty.cross_track_sigma_m.powi(2)],
+    ]
+}
+
+/// Probability of collision of a screened conjunction, with both covariances from the age of
+/// each object's element set at the time of closest approach.
+pub fn conjunction_probability(
+    conjunction: &Conjunction,
+    primary_epoch: &Instant,
+    growth: &TleAgeErrorGrowth,
+    hard_body_radius_m: f64,
+) -> anyhow::Result<CollisionProbability> {
+    let object_at_tca =
+        |(position_m, velocity_m_per_s): ([f64; 3], [f64; 3]), epoch: &Instant| ObjectAtTca {
+            position_m,
+            velocity_m_per_s,
+            covariance_rtn_m2: tle_age_covariance_m2(growth, (conjunction.tca - *epoch).as_days()),
+        };
+    collision_probability(
+        &object_at_tca(conjunction.primary_state_teme, primary_epoch),
+        &object_at_tca(conjunction.object_state_teme, &conjunction.object_tle.epoch),
+        hard_body_radius_m,
+    )
+}
+
+/// One object of a conjunction data message.
+#[derive(Debug, Clone)]
+pub struct CdmObject {
+    pub name: String,
+    pub designator: String,
+    pub state: ObjectAtTca,
+}
+
+/// The parts of a CCSDS Conjunction Data Message (KVN) needed to recompute Pc.
+#[derive(Debug, Clone)]
+pub struct ConjunctionDataMessage {
+    pub tca: Instant,
+    pub miss_distance_m: f64,
+    pub collision_probability: Option<f64>,
+    pub collision_probability_method: Option<String>,
+    /// Combined hard-body radius, when the message carries one.
+    pub hard_body_radius_m: Option<f64>,
+    pub objects: [CdmObject; 2],
+}
+
+#[derive(Default)]
+struct CdmObjectFields {
+    name: String,
+    designator: String,
+    ref_frame: String,
+    position_km: [Option<f64>; 3],
+    velocity_km_per_s: [Option<f64>; 3],
+    covariance_m2: [[Option<f64>; 3]; 3],
+}
+
+impl CdmObjectFields {
+    fn into_object(self, label: &str) -> Result<CdmObject, String> {
+        let require = |value: Option<f64>, key: &str| {
+            value.ok_or_else(|| format!("{label} is missing {key}"))
+        };
+        let mut position_m = [0.0; 3];
+        let mut velocity_m_per_s = [0.0; 3];
+        for (a, axis) in ["X", "Y", "Z"].iter().enumerate() {
+            position_m[a] = require(self.position_km[a], axis)? * 1000.0;
+            velocity_m_per_s[a] =
+                require(self.velocity_km_per_s[a], &format!("{axis}_DOT"))? * 1000.0;
+        }
+        match self.ref_frame.as_str() {
+            "GCRF" | "EME2000" | "TEME" | "" => {}
+            // At one instant ITRF differs from an inertial frame by a rotation, which leaves
+            // the encounter geometry alone, and by the velocity of Earth's rotation.
+            "ITRF" => {
+                velocity_m_per_s[0] -= OMEGA_EARTH * position_m[1];
+                velocity_m_per_s[1] += OMEGA_EARTH * position_m[0];
+            }
+            frame => return Err(format!("{label}: unsupported REF_FRAME {frame}")),
+        }
+
+        const AXES: [&str; 3] = ["R", "T", "N"];
+        let mut covariance_rtn_m2 = [[0.0; 3]; 3];
+        for i in 0..3 {
+            for j in 0..=i {
+                let value = require(
+                    self.covariance_m2[i][j],
+                    &format!("C{}_{}", AXES[i], AXES[j]),
+                )?;
+                covariance_rtn_m2[i][j] = value;
+                covariance_rtn_m2[j][i] = value;
+            }
+        }
+        Ok(CdmObject {
+            name: self.name,
+            designator: self.designator,
+            state: ObjectAtTca {
+                position_m,
+                velocity_m_per_s,
+                covariance_rtn_m2,
+            },
+        })
+    }
+}
+
+/// Parse a CCSDS CDM in key-value notation.
+///
+/// Only the relative metadata, the state vectors and the position block of the RTN covariance
+/// are read; units in brackets are ignored since the standard fixes them (km, km/s, m²).
+/// States in ITRF are converted to an inertial velocity; other frames must be inertial.
+pub fn parse_cdm(text: &str) -> Result<ConjunctionDataMessage, String> {
+    let mut tca = None;
+    let mut miss_distance_m = None;
+    let mut collision_probability = None;
+    let mut collision_probability_method = None;
+    let mut hard_body_radius_m = None;
+    let mut objects: [CdmObjectFields; 2] = Default::default();
+    let mut current: Option<usize> = None;
+
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        let value = value
+            .split('[')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        let number = || {
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid value '{value}' for {key}"))
+        };
+        match key {
+            "TCA" => {
+                tca = Some(
+                    Instant::from_rfc3339(&value)
+                        .map_err(|e| format!("Invalid TCA '{value}': {e}"))?,
+                )
+            }
+            "MISS_DISTANCE" => miss_distance_m = Some(number()?),
+            "COLLISION_PROBABILITY" => collision_probability = Some(number()?),
+            "COLLISION_PROBABILITY_METHOD" => collision_probability_method = Some(value),
+            "HBR" | "HARD_BODY_RADIUS" => hard_body_radius_m = Some(number()?),
+            "OBJECT" => {
+                current = match value.as_str() {
+                    "OBJECT1" => Some(0),
+                    "OBJECT2" => Some(1),
+                    _ => return Err(format!("Unknown OBJECT '{value}'")),
+                }
+            }
+            _ => {
+                let Some(index) = current else {
+                    continue;
+                };
+                let object = &mut objects[index];
+                let rtn_index = |axis: &str| match axis {
+                    "R" => Some(0),
+                    "T" => Some(1),
+                    "N" => Some(2),
+                    _ => None,
+                };
+                match key {
+                    "OBJECT_NAME" => object.name = value,
+                    "OBJECT_DESIGNATOR" => object.designator = value,
+                    "REF_FRAME" => object.ref_frame = value,
+                    "X" => object.position_km[0] = Some(number()?),
+                    "Y" => object.position_km[1] = Some(number()?),
+                    "Z" => object.position_km[2] = Some(number()?),
+                    "X_DOT" => object.velocity_km_per_s[0] = Some(number()?),
+                    "Y_DOT" => object.velocity_km_per_s[1] = Some(number()?),
+                    "Z_DOT" => object.velocity_km_per_s[2] = Some(number()?),
+                    _ => {
+                        // Position covariance terms are C<row>_<column>, e.g. CT_R.
+                        if let Some((row, column)) =
+                            key.strip_prefix('C').and_then(|axes| axes.split_once('_'))
+                            && let (Some(i), Some(j)) = (rtn_index(row), rtn_index(column))
+                        {
+                            object.covariance_m2[i][j] = Some(number()?);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let [first, second] = objects;
+    Ok(ConjunctionDataMessage {
+        tca: tca.ok_or("Missing TCA")?,
+        miss_distance_m: miss_distance_m.ok_or("Missing MISS_DISTANCE")?,
+        collision_probability,
+        collision_probability_method,
+        hard_body_radius_m,
+        objects: [
+            first.into_object("OBJECT1")?,
+            second.into_object("OBJECT2")?,
+        ],
+    })
+}
+
+impl ConjunctionDataMessage {
+    /// Pc recomputed from the message's states and covariances.
+    pub fn recompute(&self, hard_body_radius_m: f64) -> anyhow::Result<CollisionProbability> {
+        collision_probability(
+            &self.objects[0].state,
+            &self.objects[1].state,
+            hard_body_radius_m,
+        )
+    }
+}
@@ -0,0 +1,430 @@
+use std::sync::atomic::AtomicUsize;
+
+use satkit::Instant;
+use satkit::consts::MU_EARTH;
+
+use crate::batch::run_batch;
+use crate::initial_state_model::TleData;
+use crate::orbital_elements::rtn_basis;
+use crate::satellite_state::{propagate_teme, pythag_3};
+
+/// Spacing of the relative-state samples between which closest approaches are bracketed.
+const SAMPLE_SECONDS: f64 = 60.0;
+/// Samples per chunk of the orbit-path filter. The osculating orbits of a chunk are taken at
+/// its middle, so chunks must be short compared to the nodal regression.
+const SAMPLES_PER_CHUNK: usize = 30;
+/// Added to the screening volume in the apogee/perigee filter, for short-periodic variations
+/// of the radius around the mean elements.
+const APSIS_FILTER_MARGIN_M: f64 = 30_000.0;
+/// Added to the screening volume in the orbit-path filter, for the drift of the osculating
+/// orbits over half a chunk (nodal regression and short-periodic terms).
+const PATH_FILTER_MARGIN_M: f64 = 30_000.0;
+/// Points per node window at which the orbit radii are evaluated.
+const PATH_FILTER_WINDOW_SAMPLES: usize = 17;
+/// Bisection steps refining the time of closest approach within its bracket.
+const TCA_BISECTION_STEPS: usize = 24;
+
+/// Ellipsoidal screening volume around the primary, in its radial/transverse/normal frame.
+#[derive(Debug, Clone)]
+pub struct ScreeningVolume {
+    pub radial_m: f64,
+    pub along_track_m: f64,
+    pub cross_track_m: f64,
+}
+
+impl ScreeningVolume {
+    pub fn new(radial_m: f64, along_track_m: f64, cross_track_m: f64) -> Result<Self, String> {
+        if radial_m <= 0.0 || along_track_m <= 0.0 || cross_track_m <= 0.0 {
+            return Err("Screening volume half-axes must be > 0".into());
+        }
+        Ok(Self {
+            radial_m,
+            along_track_m,
+            cross_track_m,
+        })
+    }
+
+    fn contains(&self, offset_rtn_m: &[f64; 3]) -> bool {
+        (offset_rtn_m[0] / self.radial_m).powi(2)
+            + (offset_rtn_m[1] / self.along_track_m).powi(2)
+            + (offset_rtn_m[2] / self.cross_track_m).powi(2)
+            <= 1.0
+    }
+
+    fn largest_half_axis_m(&self) -> f64 {
+        self.radial_m
+            .max(self.along_track_m)
+            .max(self.cross_track_m)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScreeningSettings {
+    pub volume: ScreeningVolume,
+    pub start: Instant,
+    pub duration_days: f64,
+    pub threads: usize,
+}
+
+impl ScreeningSettings {
+    pub fn new(
+        volume: ScreeningVolume,
+        start: Instant,
+        duration_days: f64,
+        threads: usize,
+    ) -> Result<Self, String> {
+        if duration_days <= 0.0 {
+            return Err("Screening duration must be > 0".into());
+        }
+        if threads == 0 {
+            return Err("Threads must be >= 1".into());
+        }
+        Ok(Self {
+            volume,
+            start,
+            duration_days,
+            threads,
+        })
+    }
+}
+
+/// A close approach inside the screening volume.
+#[derive(Debug, Clone)]
+pub struct Conjunction {
+    pub object: String,
+    pub sat_num: i32,
+    /// Time of closest approach.
+    pub tca: Instant,
+    pub miss_distance_m: f64,
+    /// Position of the object relative to the primary, along the primary's
+    /// radial/transverse/normal axes.
+    pub miss_rtn_m: [f64; 3],
+    pub relative_speed_m_per_s: f64,
+    /// TEME position and velocity of the primary and of the object at TCA.
+    pub primary_state_teme: ([f64; 3], [f64; 3]),
+    pub object_state_teme: ([f64; 3], [f64; 3]),
+    /// Element set of the object, whose age sets its covariance.
+    pub object_tle: TleData,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScreeningResult {
+    /// Epoch of the primary's element set.
+    pub primary_epoch: Instant,
+    /// In order of time of closest approach.
+    pub conjunctions: Vec<Conjunction>,
+    pub screened_objects: usize,
+    /// Objects whose altitude band overlaps the primary's.
+    pub after_apsis_filter: usize,
+    /// Object-chunk pairs that the orbit-path filter let through to the close-approach search.
+    pub path_filter_candidates: usize,
+    /// Objects that SGP4 could not propagate over the window (e.g. decayed), with the error.
+    pub failed_objects: Vec<(String, String)>,
+}
+
+/// Osculating orbit: plane normal, eccentricity vector and semi-latus rectum.
+struct OrbitGeometry {
+    normal: [f64; 3],
+    eccentricity: [f64; 3],
+    semi_latus_rectum_m: f64,
+}
+
+impl OrbitGeometry {
+    fn new(position_m: &[f64; 3], velocity_m_per_s: &[f64; 3]) -> Self {
+        let [radial, transverse, normal] = rtn_basis(position_m, velocity_m_per_s);
+        let r = pythag_3(position_m);
+        let v_radial = dot(velocity_m_per_s, &radial);
+        let v_transverse = dot(velocity_m_per_s, &transverse);
+        let h = r * v_transverse;
+        // e = ((v² - μ/r)·r - (r·v)·v) / μ, written in the RTN frame of the state.
+        let e_radial = h * v_transverse / MU_EARTH - 1.0;
+        let e_transverse = -h * v_radial / MU_EARTH;
+        Self {
+            normal,
+            eccentricity: std::array::from_fn(|axis| {
+                e_radial * radial[axis] + e_transverse * transverse[axis]
+            }),
+            semi_latus_rectum_m: h * h / MU_EARTH,
+        }
+    }
+
+    /// Radius of the orbit in the in-plane direction `direction` (a unit vector).
+    fn radius_m(&self, direction: &[f64; 3]) -> f64 {
+        self.semi_latus_rectum_m / (1.0 + dot(&self.eccentricity, direction))
+    }
+
+    fn min_radius_m(&self) -> f64 {
+        self.semi_latus_rectum_m / (1.0 + pythag_3(&self.eccentricity))
+    }
+
+    /// Range of radii within `half_width_rad` of `node` (a unit vector in the plane).
+    fn radius_range_m(&self, node: &[f64; 3], half_width_rad: f64) -> (f64, f64) {
+        let across = cross(&self.normal, node);
+        let mut range = (f64::INFINITY, f64::NEG_INFINITY);
+        for i in 0..PATH_FILTER_WINDOW_SAMPLES {
+            let angle =
+                half_width_rad * (2.0 * i as f64 / (PATH_FILTER_WINDOW_SAMPLES - 1) as f64 - 1.0);
+            let (sin, cos) = angle.sin_cos();
+            let radius_m = self.radius_m(&std::array::from_fn(|a| cos * node[a] + sin * across[a]));
+            range = (range.0.min(radius_m), range.1.max(radius_m));
+        }
+        range
+    }
+
+    /// Half-width of the arc around the mutual nodes within which this orbit comes within
+    /// `pad_m` of the other plane, or `None` when the whole orbit may.
+    fn node_window_rad(&self, pad_m: f64, sin_mutual_inclination: f64) -> Option<f64> {
+        let sin_half_width = pad_m / (self.min_radius_m() * sin_mutual_inclination);
+        (sin_half_width < 1.0).then(|| sin_half_width.asin())
+    }
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Whether the two orbit paths may come within `pad_m` of each other.
+///
+/// A point of one orbit that close to the other lies within `pad_m` of the other's plane, so
+/// near a mutual node, and at nearly the same radius as the nearby part of the other orbit.
+/// Both windows around each node are checked for overlapping radii; coplanar orbits always
+/// pass.
+fn paths_may_intersect(a: &OrbitGeometry, b: &OrbitGeometry, pad_m: f64) -> bool {
+    let line_of_nodes = cross(&a.normal, &b.normal);
+    let sin_mutual_inclination = pythag_3(&line_of_nodes);
+    let (Some(window_a), Some(window_b)) = (
+        a.node_window_rad(pad_m, sin_mutual_inclination),
+        b.node_window_rad(pad_m, sin_mutual_inclination),
+    ) else {
+        return true;
+    };
+    let node = line_of_nodes.map(|c| c / sin_mutual_inclination);
+    [node, node.map(|c| -c)].iter().any(|node| {
+        let (min_a, max_a) = a.radius_range_m(node, window_a);
+        let (min_b, max_b) = b.radius_range_m(node, window_b);
+        min_a <= max_b + pad_m && min_b <= max_a + pad_m
+    })
+}
+
+/// Perigee and apogee radii from the mean elements.
+fn apsis_radii_m(tle: &TleData) -> (f64, f64) {
+    let mean_motion_rad_per_s = tle.mean_motion * 2.0 * std::f64::consts::PI / 86_400.0;
+    let semi_major_axis_m = (MU_EARTH / mean_motion_rad_per_s.powi(2)).cbrt();
+    (
+        semi_major_axis_m * (1.0 - tle.eccen),
+        semi_major_axis_m * (1.0 + tle.eccen),
+    )
+}
+
+type State = ([f64; 3], [f64; 3]);
+
+/// Range rate of `object` relative to `primary`; negative while closing.
+pub fn range_rate(primary: &State, object: &State) -> f64 {
+    let position: [f64; 3] = std::array::from_fn(|a| object.0[a] - primary.0[a]);
+    let velocity: [f64; 3] = std::array::from_fn(|a| object.1[a] - primary.1[a]);
+    dot(&position, &velocity) / pythag_3(&position)
+}
+
+/// Time of closest approach within `[low, high]`, where the objects are closing at `low` and
+/// opening at `high`, by bisection on the range rate.
+pub fn refine_tca(
+    primary: &mut satkit::TLE,
+    object: &mut satkit::TLE,
+    mut low: Instant,
+    mut high: Instant,
+) -> anyhow::Result<Instant> {
+    let midpoint = |low: Instant, high: Instant| {
+        low + satkit::Duration::from_seconds((high - low).as_seconds() / 2.0)
+    };
+    for _ in 0..TCA_BISECTION_STEPS {
+        let mid = midpoint(low, high);
+        let closing = range_rate(
+            &propagate_teme(primary, &mid)?,
+            &propagate_teme(object, &mid)?,
+        ) < 0.0;
+        if closing {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Ok(midpoint(low, high))
+}
+
+/// Conjunctions of one object with the primary, and the number of chunks that passed the
+/// orbit-path filter.
+fn screen_object(
+    primary_tle: &TleData,
+    primary_states: &[State],
+    object_tle: &TleData,
+    settings: &ScreeningSettings,
+) -> anyhow::Result<(Vec<Conjunction>, usize)> {
+    let time_at =
+        |sample: f64| settings.start + satkit::Duration::from_seconds(sample * SAMPLE_SECONDS);
+    let mut primary = primary_tle.to_satkit_tle();
+    let mut object = object_tle.to_satkit_tle();
+    let pad_m = settings.volume.largest_half_axis_m() + PATH_FILTER_MARGIN_M;
+    let intervals = primary_states.len() - 1;
+
+    let mut conjunctions = Vec::new();
+    let mut candidate_chunks = 0;
+    for chunk_start in (0..intervals).step_by(SAMPLES_PER_CHUNK) {
+        let chunk_end = (chunk_start + SAMPLES_PER_CHUNK).min(intervals);
+        let middle = (chunk_start + chunk_end) / 2;
+        let (position, velocity) = &primary_states[middle];
+        let primary_orbit = OrbitGeometry::new(position, velocity);
+        let (position, velocity) = propagate_teme(&mut object, &time_at(middle as f64))?;
+        let object_orbit = OrbitGeometry::new(&position, &velocity);
+        if !paths_may_intersect(&primary_orbit, &object_orbit, pad_m) {
+            continue;
+        }
+        candidate_chunks += 1;
+
+        // The primary has to be near the object's plane at a close approach, which leaves a
+        // few sample intervals around each plane crossing to search.
+        let plane_offset_m = |sample: usize| dot(&primary_states[sample].0, &object_orbit.normal);
+        let mut previous: Option<(usize, State)> = None;
+        for sample in chunk_start..chunk_end {
+            let (offset_before, offset_after) =
+                (plane_offset_m(sample), plane_offset_m(sample + 1));
+            if offset_before.signum() == offset_after.signum()
+                && offset_before.abs().min(offset_after.abs()) > pad_m
+            {
+                continue;
+            }
+            let before = match previous.take() {
+                Some((index, state)) if index == sample => state,
+                _ => propagate_teme(&mut object, &time_at(sample as f64))?,
+            };
+            let after = propagate_teme(&mut object, &time_at((sample + 1) as f64))?;
+            let closing_before = range_rate(&primary_states[sample], &before) < 0.0;
+            let closing_after = range_rate(&primary_states[sample + 1], &after) < 0.0;
+            previous = Some((sample + 1, after));
+            if !closing_before || closing_after {
+                continue;
+            }
+
+            let tca = refine_tca(
+                &mut primary,
+                &mut object,
+                time_at(sample as f64),
+                time_at((sample + 1) as f64),
+            )?;
+            let (primary_position, primary_velocity) = propagate_teme(&mut primary, &tca)?;
+            let (object_position, object_velocity) = propagate_teme(&mut object, &tca)?;
+            let offset: [f64; 3] =
+                std::array::from_fn(|a| object_position[a] - primary_position[a]);
+            let miss_rtn_m =
+                rtn_basis(&primary_position, &primary_velocity).map(|unit| dot(&unit, &offset));
+            if !settings.volume.contains(&miss_rtn_m) {
+                continue;
+            }
+            conjunctions.push(Conjunction {
+                object: object_tle.name.clone(),
+                sat_num: object_tle.sat_num,
+                tca,
+                miss_distance_m: pythag_3(&offset),
+                miss_rtn_m,
+                relative_speed_m_per_s: pythag_3(&std::array::from_fn(|a| {
+                    object_velocity[a] - primary_velocity[a]
+                })),
+                primary_state_teme: (primary_position, primary_velocity),
+                object_state_teme: (object_position, object_velocity),
+                object_tle: object_tle.clone(),
+            });
+        }
+    }
+    Ok((conjunctions, candidate_chunks))
+}
+
+/// Screen `catalog` for close approaches with `primary` over the window of `settings`.
+///
+/// Objects are filtered in stages: their perigee-apogee band against the primary's, then
+/// their orbit paths against the primary's in chunks of half an hour, and within chunks that
+/// pass, the minutes in which the primary crosses the object's plane. Only there are relative
+/// states computed; a closest approach is bracketed where the range rate turns from closing to
+/// opening, refined by bisection and reported when it falls inside the screening volume.
+/// Approaches at the very edges of the window are not reported.
+pub fn screen_conjunctions(
+    primary: &TleData,
+    catalog: &[TleData],
+    settings: &ScreeningSettings,
+    completed: &AtomicUsize,
+) -> anyhow::Result<ScreeningResult> {
+    let (primary_perigee_m, primary_apogee_m) = apsis_radii_m(primary);
+    let apsis_pad_m = settings.volume.largest_half_axis_m() + APSIS_FILTER_MARGIN_M;
+    let objects = catalog
+        .iter()
+        .filter(|object| object.sat_num != primary.sat_num)
+        .collect::<Vec<_>>();
+    let candidates = objects
+        .iter()
+        .filter(|object| {
+            let (perigee_m, apogee_m) = apsis_radii_m(object);
+            perigee_m <= primary_apogee_m + apsis_pad_m
+                && primary_perigee_m <= apogee_m + apsis_pad_m
+        })
+        .copied()
+        .collect::<Vec<_>>();
+    completed.fetch_add(
+        objects.len() - candidates.len(),
+        std::sync::atomic::Ordering::Relaxed,
+    );
+
+    let samples = (settings.duration_days * 86_400.0 / SAMPLE_SECONDS).ceil() as usize;
+    let mut satkit_primary = primary.to_satkit_tle();
+    let primary_states = (0..=samples)
+        .map(|sample| {
+            propagate_teme(
+                &mut satkit_primary,
+                &(settings.start + satkit::Duration::from_seconds(sample as f64 * SAMPLE_SECONDS)),
+            )
+        })
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map_err(|e| anyhow::anyhow!("Failed to propagate the primary: {e}"))?;
+
+    let outcomes = run_batch(
+        &(),
+        candidates.len(),
+        settings.threads,
+        completed,
+        |_, index| {
+            screen_object(primary, &primary_states, candidates[index], settings)
+                .map_err(|e| e.to_string())
+        },
+    )?;
+
+    let mut result = ScreeningResult {
+        primary_epoch: primary.epoch,
+        conjunctions: Vec::new(),
+        screened_objects: objects.len(),
+        after_apsis_filter: candidates.len(),
+        path_filter_candidates: 0,
+        failed_objects: Vec::new(),
+    };
+    for (object, outcome) in candidates.iter().zip(outcomes) {
+        match outcome {
+            Ok((conjunctions, candidate_chunks)) => {
+                result.conjunctions.extend(conjunctions);
+                result.path_filter_candidates += candidate_chunks;
+            }
+            Err(e) => result
+                .failed_objects
+                .push((object.name.clone(), e.to_string())),
+        }
+    }
+    result.conjunctions.sort_by(|a, b| {
+        a.tca
+            .partial_cmp(&b.tca)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    Ok(result)
+}
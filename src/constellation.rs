@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::initial_state_model::TleData;
+use crate::orbital_elements::{apply_delta_v_at_epoch, wrap_degrees_360};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum WalkerPatternKind {
+    /// Ascending nodes spread over the full 360 degrees (typical for inclined constellations).
+    #[default]
+    Delta,
+    /// Ascending nodes spread over 180 degrees (typical for polar constellations).
+    Star,
+}
+
+impl WalkerPatternKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            WalkerPatternKind::Delta => "Walker Delta (360° RAAN spread)",
+            WalkerPatternKind::Star => "Walker Star (180° RAAN spread)",
+        }
+    }
+
+    fn raan_spread_deg(&self) -> f64 {
+        match self {
+            WalkerPatternKind::Delta => 360.0,
+            WalkerPatternKind::Star => 180.0,
+        }
+    }
+}
+
+/// Walker constellation in the usual i:T/P/F notation. The inclination, altitude and
+/// eccentricity are taken from the seed TLE.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalkerPattern {
+    pub kind: WalkerPatternKind,
+    /// T: total number of satellites.
+    pub total_satellites: u32,
+    /// P: number of equally-spaced orbital planes.
+    pub planes: u32,
+    /// F: relative phasing between adjacent planes, in units of 360/T degrees.
+    pub phasing: u32,
+}
+
+impl WalkerPattern {
+    pub fn new(
+        kind: WalkerPatternKind,
+        total_satellites: u32,
+        planes: u32,
+        phasing: u32,
+    ) -> Result<Self, String> {
+        if total_satellites == 0 || planes == 0 {
+            return Err("Walker T and P must be > 0".to_string());
+        }
+        if !total_satellites.is_multiple_of(planes) {
+            return Err("Walker T must be a multiple of P".to_string());
+        }
+        if phasing >= planes {
+            return Err("Walker F must be between 0 and P-1".to_string());
+        }
+
+        Ok(Self {
+            kind,
+            total_satellites,
+            planes,
+            phasing,
+        })
+    }
+
+    pub fn satellites_per_plane(&self) -> u32 {
+        self.total_satellites / self.planes
+    }
+}
+
+/// Generate one element set per Walker slot, derived from `seed`.
+///
+/// Slot (plane 0, satellite 0) coincides with the seed. RAAN and mean anomaly are offset for
+/// every other slot; all other elements (including epoch and B*) are copied from the seed.
+pub fn generate_walker_constellation(seed: &TleData, pattern: &WalkerPattern) -> Vec<TleData> {
+    let sats_per_plane = pattern.satellites_per_plane();
+    let raan_step_deg = pattern.kind.raan_spread_deg() / pattern.planes as f64;
+    let in_plane_step_deg = 360.0 / sats_per_plane as f64;
+    let phase_step_deg = pattern.phasing as f64 * 360.0 / pattern.total_satellites as f64;
+
+    let mut tles = Vec::with_capacity(pattern.total_satellites as usize);
+    for plane in 0..pattern.planes {
+        for slot in 0..sats_per_plane {
+            let mut tle = seed.clone();
+            tle.name = format!("{} P{}-S{}", seed.name, plane + 1, slot + 1);
+            tle.raan = wrap_degrees_360(seed.raan + plane as f64 * raan_step_deg);
+            tle.mean_anomaly = wrap_degrees_360(
+                seed.mean_anomaly + slot as f64 * in_plane_step_deg + plane as f64 * phase_step_deg,
+            );
+            tles.push(tle);
+        }
+    }
+    tles
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum SeparationDirection {
+    #[default]
+    Prograde,
+    Retrograde,
+    RadialOut,
+    RadialIn,
+    OrbitNormal,
+    OrbitAntiNormal,
+}
+
+impl SeparationDirection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SeparationDirection::Prograde => "Prograde (+T)",
+            SeparationDirection::Retrograde => "Retrograde (-T)",
+            SeparationDirection::RadialOut => "Radial out (+R)",
+            SeparationDirection::RadialIn => "Radial in (-R)",
+            SeparationDirection::OrbitNormal => "Orbit normal (+N)",
+            SeparationDirection::OrbitAntiNormal => "Orbit anti-normal (-N)",
+        }
+    }
+
+    /// Unit vector in the RTN frame.
+    fn rtn_unit(&self) -> [f64; 3] {
+        match self {
+            SeparationDirection::Prograde => [0.0, 1.0, 0.0],
+            SeparationDirection::Retrograde => [0.0, -1.0, 0.0],
+            SeparationDirection::RadialOut => [1.0, 0.0, 0.0],
+            SeparationDirection::RadialIn => [-1.0, 0.0, 0.0],
+            SeparationDirection::OrbitNormal => [0.0, 0.0, 1.0],
+            SeparationDirection::OrbitAntiNormal => [0.0, 0.0, -1.0],
+        }
+    }
+}
+
+/// Rideshare deployment: `child_count` spacecraft are ejected from the parent at its TLE epoch,
+/// each with a separation delta-v of `separation_speed_m_per_s`. The ejection directions are
+/// spread evenly around a cone of `cone_half_angle_deg` about `direction`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RideshareDeployment {
+    pub child_count: u32,
+    pub separation_speed_m_per_s: f64,
+    pub direction: SeparationDirection,
+    pub cone_half_angle_deg: f64,
+}
+
+impl RideshareDeployment {
+    pub fn new(
+        child_count: u32,
+        separation_speed_m_per_s: f64,
+        direction: SeparationDirection,
+        cone_half_angle_deg: f64,
+    ) -> Result<Self, String> {
+        if child_count == 0 {
+            return Err("Child count must be > 0".to_string());
+        }
+        if !(separation_speed_m_per_s > 0.0 && separation_speed_m_per_s < 100.0) {
+            return Err("Separation speed must be between 0 and 100 m/s".to_string());
+        }
+        if !(0.0..=90.0).contains(&cone_half_angle_deg) {
+            return Err("Cone half-angle must be between 0 and 90 degrees".to_string());
+        }
+
+        Ok(Self {
+            child_count,
+            separation_speed_m_per_s,
+            direction,
+            cone_half_angle_deg,
+        })
+    }
+
+    /// Separation delta-v (RTN, m/s) applied to each child.
+    pub fn child_delta_vs_rtn(&self) -> Vec<[f64; 3]> {
+        let axis = self.direction.rtn_unit();
+        // Two unit vectors perpendicular to the cone axis.
+        let perp_1 = [axis[2].abs(), axis[0].abs(), axis[1].abs()];
+        let perp_2 = [
+            axis[1] * perp_1[2] - axis[2] * perp_1[1],
+            axis[2] * perp_1[0] - axis[0] * perp_1[2],
+            axis[0] * perp_1[1] - axis[1] * perp_1[0],
+        ];
+
+        let cone_rad = self.cone_half_angle_deg.to_radians();
+        (0..self.child_count)
+            .map(|i| {
+                // A single child gets the nominal direction; otherwise spread around the cone.
+                let (off_axis, around) = if self.child_count == 1 {
+                    (0.0, 0.0)
+                } else {
+                    (
+                        cone_rad,
+                        2.0 * std::f64::consts::PI * i as f64 / self.child_count as f64,
+                    )
+                };
+                let mut dv = [0.0; 3];
+                for (k, dv_k) in dv.iter_mut().enumerate() {
+                    let unit = off_axis.cos() * axis[k]
+                        + off_axis.sin() * (around.cos() * perp_1[k] + around.sin() * perp_2[k]);
+                    *dv_k = unit * self.separation_speed_m_per_s;
+                }
+                dv
+            })
+            .collect()
+    }
+}
+
+/// Generate one element set per rideshare child by applying its separation delta-v to `parent`.
+pub fn generate_rideshare_dispersal(
+    parent: &TleData,
+    deployment: &RideshareDeployment,
+) -> anyhow::Result<Vec<TleData>> {
+    deployment
+        .child_delta_vs_rtn()
+        .into_iter()
+        .enumerate()
+        .map(|(i, delta_v_rtn)| {
+            let mut child = apply_delta_v_at_epoch(parent, delta_v_rtn)?;
+            child.name = format!("{} Child-{}", parent.name, i + 1);
+            Ok(child)
+        })
+        .collect()
+}
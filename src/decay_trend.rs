@@ -0,0 +1,222 @@
+use satkit::Instant;
+use satkit::consts::{EARTH_RADIUS, MU_EARTH};
+use strum_macros::EnumIter;
+
+use crate::aerobraking::BSTAR_TO_DRAG_AREA_PER_MASS;
+use crate::earth_orientation::qteme2itrf_with_mode;
+use crate::initial_state_model::{Satellite, TleData};
+use crate::output_frames::teme_to_itrf_state;
+use crate::satellite_state::{
+    calculate_atmospheric_density_kg_per_m3, calculate_elevation_from_location_km, propagate_teme,
+    pythag_3,
+};
+
+/// Density samples per orbit when integrating the predicted decay.
+const SAMPLES_PER_ORBIT: f64 = 12.0;
+
+/// Mean elements of one set of a TLE history.
+#[derive(Debug, Clone)]
+pub struct ElementTrendPoint {
+    pub epoch: Instant,
+    pub bstar: f64,
+    pub mean_motion_rev_per_day: f64,
+    /// First derivative of mean motion (twice the TLE's field), in rev/day².
+    pub mean_motion_dot_rev_per_day2: f64,
+    pub semi_major_axis_m: f64,
+    pub apogee_altitude_km: f64,
+    pub perigee_altitude_km: f64,
+}
+
+impl ElementTrendPoint {
+    pub fn new(tle: &TleData) -> Self {
+        let mean_motion_rad_per_s = tle.mean_motion * 2.0 * std::f64::consts::PI / 86_400.0;
+        let semi_major_axis_m = (MU_EARTH / mean_motion_rad_per_s.powi(2)).cbrt();
+        Self {
+            epoch: tle.epoch,
+            bstar: tle.bstar,
+            mean_motion_rev_per_day: tle.mean_motion,
+            mean_motion_dot_rev_per_day2: 2.0 * tle.mean_motion_dot,
+            semi_major_axis_m,
+            apogee_altitude_km: (semi_major_axis_m * (1.0 + tle.eccen) - EARTH_RADIUS) / 1000.0,
+            perigee_altitude_km: (semi_major_axis_m * (1.0 - tle.eccen) - EARTH_RADIUS) / 1000.0,
+        }
+    }
+}
+
+/// Quantity shown in the element trend plot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter)]
+pub enum TrendQuantity {
+    #[default]
+    Bstar,
+    MeanMotion,
+    MeanMotionDot,
+    ApogeeAltitudeKm,
+    PerigeeAltitudeKm,
+}
+
+impl TrendQuantity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TrendQuantity::Bstar => "B* (1/earth radii)",
+            TrendQuantity::MeanMotion => "Mean motion (rev/day)",
+            TrendQuantity::MeanMotionDot => "Mean motion derivative (rev/day²)",
+            TrendQuantity::ApogeeAltitudeKm => "Apogee altitude (km)",
+            TrendQuantity::PerigeeAltitudeKm => "Perigee altitude (km)",
+        }
+    }
+
+    pub fn value(&self, point: &ElementTrendPoint) -> f64 {
+        match self {
+            TrendQuantity::Bstar => point.bstar,
+            TrendQuantity::MeanMotion => point.mean_motion_rev_per_day,
+            TrendQuantity::MeanMotionDot => point.mean_motion_dot_rev_per_day2,
+            TrendQuantity::ApogeeAltitudeKm => point.apogee_altitude_km,
+            TrendQuantity::PerigeeAltitudeKm => point.perigee_altitude_km,
+        }
+    }
+}
+
+/// Observed and fitted decay of the mean semi-major axis between two consecutive sets.
+#[derive(Debug, Clone)]
+pub struct DecayInterval {
+    pub start: Instant,
+    pub end: Instant,
+    pub observed_decay_m: f64,
+    pub predicted_decay_m: f64,
+}
+
+/// Effective ballistic coefficient fitted to the decay of a TLE history.
+#[derive(Debug, Clone)]
+pub struct DecayCalibration {
+    /// C_d·A/m that makes the NRLMSISE-00 decay match the observed one, in m²/kg.
+    pub drag_area_per_mass_m2_per_kg: f64,
+    /// Its formal 1-sigma, from the scatter of the intervals (`None` with a single interval).
+    pub sigma_m2_per_kg: Option<f64>,
+    /// Mean C_d·A/m implied by the sets' own B*, for comparison.
+    pub bstar_drag_area_per_mass_m2_per_kg: f64,
+    pub intervals: Vec<DecayInterval>,
+}
+
+impl DecayCalibration {
+    /// B* that corresponds to the fitted C_d·A/m, for lifetime predictions.
+    pub fn calibrated_bstar(&self) -> f64 {
+        self.drag_area_per_mass_m2_per_kg / BSTAR_TO_DRAG_AREA_PER_MASS
+    }
+
+    /// Mass that makes the satellite's own C_d·A match the fitted C_d·A/m.
+    pub fn equivalent_mass_kg(&self, satellite: &Satellite) -> f64 {
+        satellite.drag_coefficient * satellite.drag_area_m2 / self.drag_area_per_mass_m2_per_kg
+    }
+}
+
+/// Decay of the semi-major axis over `[tle.epoch, end]` for a C_d·A/m of 1 m²/kg:
+/// the integral of (a²/μ)·ρ·v³, with the orbit from `tle` and ρ from NRLMSISE-00.
+fn decay_per_unit_drag_area_per_mass(
+    tle: &TleData,
+    end: &Instant,
+    enable_space_weather: bool,
+) -> anyhow::Result<f64> {
+    let point = ElementTrendPoint::new(tle);
+    let duration_seconds = (*end - tle.epoch).as_seconds();
+    let samples = (duration_seconds * tle.mean_motion / 86_400.0 * SAMPLES_PER_ORBIT)
+        .ceil()
+        .max(1.0) as usize;
+    let step_seconds = duration_seconds / samples as f64;
+    let mut satkit_tle = tle.to_satkit_tle();
+    let mut decay_m = 0.0;
+    for i in 0..samples {
+        // Midpoint rule.
+        let time = tle.epoch + satkit::Duration::from_seconds((i as f64 + 0.5) * step_seconds);
+        let (position_teme, velocity_teme) = propagate_teme(&mut satkit_tle, &time)?;
+        let itrf_state = teme_to_itrf_state(
+            &qteme2itrf_with_mode(&time).0,
+            &position_teme,
+            &velocity_teme,
+        );
+        let position_itrf = satkit::ITRFCoord::from_slice(&itrf_state.position)?;
+        let density_kg_per_m3 = calculate_atmospheric_density_kg_per_m3(
+            calculate_elevation_from_location_km(&itrf_state.position.map(|m| m / 1000.0)),
+            Some(position_itrf.latitude_deg()),
+            Some(position_itrf.longitude_deg()),
+            Some(time),
+            enable_space_weather,
+        );
+        decay_m += point.semi_major_axis_m.powi(2) / MU_EARTH
+            * density_kg_per_m3
+            * pythag_3(&itrf_state.velocity).powi(3)
+            * step_seconds;
+    }
+    Ok(decay_m)
+}
+
+/// Fit C_d·A/m to the decay of the mean semi-major axis between consecutive sets of `sets`
+/// (in epoch order), by least squares through the origin.
+///
+/// Manoeuvres between sets show up as negative decay and bias the fit; histories should be
+/// trimmed to drag-only stretches.
+pub fn calibrate_drag(
+    sets: &[TleData],
+    enable_space_weather: bool,
+) -> anyhow::Result<DecayCalibration> {
+    if sets.len() < 2 {
+        return Err(anyhow::anyhow!(
+            "Drag calibration needs at least two element sets"
+        ));
+    }
+    let mut unit_decays_m = Vec::new();
+    let mut observed_decays_m = Vec::new();
+    for pair in sets.windows(2) {
+        unit_decays_m.push(decay_per_unit_drag_area_per_mass(
+            &pair[0],
+            &pair[1].epoch,
+            enable_space_weather,
+        )?);
+        observed_decays_m.push(
+            ElementTrendPoint::new(&pair[0]).semi_major_axis_m
+                - ElementTrendPoint::new(&pair[1]).semi_major_axis_m,
+        );
+    }
+
+    let sum_unit_squared = unit_decays_m.iter().map(|k| k * k).sum::<f64>();
+    if sum_unit_squared <= 0.0 {
+        return Err(anyhow::anyhow!(
+            "The atmosphere is too thin along this orbit to calibrate drag"
+        ));
+    }
+    let drag_area_per_mass_m2_per_kg = unit_decays_m
+        .iter()
+        .zip(&observed_decays_m)
+        .map(|(k, observed)| k * observed)
+        .sum::<f64>()
+        / sum_unit_squared;
+
+    let intervals = sets
+        .windows(2)
+        .zip(unit_decays_m.iter().zip(&observed_decays_m))
+        .map(|(pair, (k, observed))| DecayInterval {
+            start: pair[0].epoch,
+            end: pair[1].epoch,
+            observed_decay_m: *observed,
+            predicted_decay_m: drag_area_per_mass_m2_per_kg * k,
+        })
+        .collect::<Vec<_>>();
+    let sigma_m2_per_kg = (intervals.len() > 1).then(|| {
+        let residual_variance = intervals
+            .iter()
+            .map(|i| (i.observed_decay_m - i.predicted_decay_m).powi(2))
+            .sum::<f64>()
+            / (intervals.len() - 1) as f64;
+        (residual_variance / sum_unit_squared).sqrt()
+    });
+
+    Ok(DecayCalibration {
+        drag_area_per_mass_m2_per_kg,
+        sigma_m2_per_kg,
+        bstar_drag_area_per_mass_m2_per_kg: sets
+            .iter()
+            .map(|tle| BSTAR_TO_DRAG_AREA_PER_MASS * tle.bstar)
+            .sum::<f64>()
+            / sets.len() as f64,
+        intervals,
+    })
+}
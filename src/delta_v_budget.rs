@@ -0,0 +1,192 @@
+use satkit::consts::MU_EARTH;
+use serde::Serialize;
+
+use crate::initial_state_model::Satellite;
+use crate::satellite_state::SimulationStateAtStep;
+use crate::station_keeping::is_reboost_burn;
+
+/// Delta-v and propellant of one named burn, summed over the simulation steps it spanned.
+#[derive(Debug, Clone, Serialize)]
+pub struct ManeuverDeltaV {
+    pub name: String,
+    pub start_utc: String,
+    pub end_utc: String,
+    pub delta_v_m_per_s: f64,
+    pub propellant_kg: f64,
+    /// The tank ran dry during this burn.
+    pub propellant_depleted: bool,
+}
+
+/// Cost of the station-keeping controller's reboosts.
+#[derive(Debug, Clone, Serialize)]
+pub struct StationKeepingCost {
+    pub reboosts: usize,
+    pub delta_v_m_per_s: f64,
+    pub propellant_kg: f64,
+    /// Part of the run during which the controller was switched on.
+    pub active_days: f64,
+    pub delta_v_m_per_s_per_year: f64,
+}
+
+/// What is left in the tank at the end of the run.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeltaVMargin {
+    pub propellant_remaining_kg: f64,
+    pub delta_v_remaining_m_per_s: f64,
+    /// How long the remaining delta-v would hold altitude against drag, at the rate station
+    /// keeping actually spent (or, without any reboost, the drag makeup rate); `None` if no
+    /// drag decay was seen.
+    pub drag_makeup_years_remaining: Option<f64>,
+}
+
+/// Delta-v budget of a simulation run.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeltaVBudget {
+    pub simulated_days: f64,
+
+    /// Burns in the order they started.
+    pub maneuvers: Vec<ManeuverDeltaV>,
+    pub total_maneuver_delta_v_m_per_s: f64,
+    pub total_propellant_kg: f64,
+
+    /// Delta-v that would have been needed to hold the orbit-average semi-major axis against
+    /// drag (each decay made up with a two-impulse transfer between near-circular orbits). The
+    /// decay is taken from start to end of each stretch of steps between burns, as the change
+    /// across a burn is mostly the burn. With station keeping, the delta-v it actually spent is
+    /// in `station_keeping`.
+    pub drag_makeup_delta_v_m_per_s: f64,
+    pub drag_makeup_delta_v_m_per_s_per_year: f64,
+
+    /// `None` without station keeping.
+    pub station_keeping: Option<StationKeepingCost>,
+
+    /// `None` without a thruster.
+    pub margin: Option<DeltaVMargin>,
+}
+
+impl DeltaVBudget {
+    /// Build the budget from a run's telemetry history and the satellite it simulated.
+    pub fn from_history(history: &[SimulationStateAtStep], satellite: &Satellite) -> Self {
+        // Segments of one burn follow each other across steps; a burn's name may be reused.
+        let mut maneuvers: Vec<ManeuverDeltaV> = Vec::new();
+        let mut last_segment_end = None;
+        for segment in history
+            .iter()
+            .filter_map(|t| t.propulsion.as_ref())
+            .flat_map(|p| &p.burn_segments)
+        {
+            let continues = last_segment_end == Some(segment.start)
+                && maneuvers.last().is_some_and(|m| m.name == segment.name);
+            match maneuvers.last_mut() {
+                Some(maneuver) if continues => {
+                    maneuver.end_utc = segment.end.as_iso8601();
+                    maneuver.delta_v_m_per_s += segment.delta_v_m_per_s;
+                    maneuver.propellant_kg += segment.propellant_used_kg;
+                    maneuver.propellant_depleted |= segment.propellant_depleted;
+                }
+                _ => maneuvers.push(ManeuverDeltaV {
+                    name: segment.name.clone(),
+                    start_utc: segment.start.as_iso8601(),
+                    end_utc: segment.end.as_iso8601(),
+                    delta_v_m_per_s: segment.delta_v_m_per_s,
+                    propellant_kg: segment.propellant_used_kg,
+                    propellant_depleted: segment.propellant_depleted,
+                }),
+            }
+            last_segment_end = Some(segment.end);
+        }
+
+        let drag_makeup_delta_v_m_per_s: f64 = history
+            .chunk_by(|_, next| {
+                let burned = next
+                    .propulsion
+                    .as_ref()
+                    .is_some_and(|p| !p.burn_segments.is_empty());
+                !burned && !next.is_deorbited
+            })
+            .map(|stretch| {
+                let before_m = stretch[0].mean_semi_major_axis_m;
+                let after_m = stretch[stretch.len() - 1].mean_semi_major_axis_m;
+                if after_m < before_m {
+                    (MU_EARTH / after_m).sqrt() - (MU_EARTH / before_m).sqrt()
+                } else {
+                    0.0
+                }
+            })
+            .sum();
+
+        let simulated_days = match (history.first(), history.last()) {
+            (Some(first), Some(last)) => (last.time - first.time).as_days(),
+            _ => 0.0,
+        };
+        let drag_makeup_delta_v_m_per_s_per_year = if simulated_days > 0.0 {
+            drag_makeup_delta_v_m_per_s / simulated_days * 365.25
+        } else {
+            0.0
+        };
+
+        let station_keeping = satellite.station_keeping.as_ref().map(|station_keeping| {
+            let reboosts = maneuvers
+                .iter()
+                .filter(|m| is_reboost_burn(&m.name))
+                .collect::<Vec<_>>();
+            let delta_v_m_per_s = reboosts.iter().map(|m| m.delta_v_m_per_s).sum();
+            // Runs start at the TLE epoch.
+            let active_days = (simulated_days
+                .min(station_keeping.start_days_since_epoch + station_keeping.duration_days)
+                - station_keeping.start_days_since_epoch)
+                .max(0.0);
+            StationKeepingCost {
+                reboosts: reboosts.len(),
+                delta_v_m_per_s,
+                propellant_kg: reboosts.iter().map(|m| m.propellant_kg).sum(),
+                active_days,
+                delta_v_m_per_s_per_year: if active_days > 0.0 {
+                    delta_v_m_per_s / active_days * 365.25
+                } else {
+                    0.0
+                },
+            }
+        });
+
+        let drag_delta_v_m_per_s_per_year = station_keeping
+            .as_ref()
+            .filter(|station_keeping| station_keeping.reboosts > 0)
+            .map_or(drag_makeup_delta_v_m_per_s_per_year, |station_keeping| {
+                station_keeping.delta_v_m_per_s_per_year
+            });
+        let last_propulsion = history.last().and_then(|t| t.propulsion.as_ref());
+        let margin = satellite.thruster.as_ref().map(|thruster| {
+            let (mass_kg, propellant_remaining_kg) = last_propulsion.map_or(
+                (
+                    thruster.wet_mass_kg,
+                    thruster.wet_mass_kg - thruster.dry_mass_kg,
+                ),
+                |p| (p.mass_kg, p.propellant_remaining_kg),
+            );
+            let delta_v_remaining_m_per_s =
+                thruster.delta_v_m_per_s(mass_kg, propellant_remaining_kg);
+            DeltaVMargin {
+                propellant_remaining_kg,
+                delta_v_remaining_m_per_s,
+                drag_makeup_years_remaining: (drag_delta_v_m_per_s_per_year > 0.0)
+                    .then(|| delta_v_remaining_m_per_s / drag_delta_v_m_per_s_per_year),
+            }
+        });
+
+        Self {
+            simulated_days,
+            total_maneuver_delta_v_m_per_s: maneuvers.iter().map(|m| m.delta_v_m_per_s).sum(),
+            total_propellant_kg: maneuvers.iter().map(|m| m.propellant_kg).sum(),
+            maneuvers,
+            drag_makeup_delta_v_m_per_s,
+            drag_makeup_delta_v_m_per_s_per_year,
+            station_keeping,
+            margin,
+        }
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum DeploymentTrigger {
+    /// Fires once `DeploymentEvent::threshold` days have passed since the TLE epoch.
+    #[default]
+    DaysSinceEpoch,
+    /// Fires at the first step below `DeploymentEvent::threshold` km altitude.
+    AltitudeBelowKm,
+}
+
+impl DeploymentTrigger {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeploymentTrigger::DaysSinceEpoch => "Days since epoch",
+            DeploymentTrigger::AltitudeBelowKm => "Altitude below (km)",
+        }
+    }
+}
+
+/// A one-off change of the satellite's drag configuration during the run, such as a drag sail
+/// deploying or a panel being jettisoned.
+///
+/// From the step the event fires, drag uses the constant `drag_coefficient` and `drag_area_m2`
+/// given here in place of the satellite's own values and panel model, and the satellite is
+/// `jettisoned_mass_kg` lighter. Only the primary satellite is affected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentEvent {
+    pub name: String,
+    pub trigger: DeploymentTrigger,
+    /// Days or km, depending on `trigger`.
+    pub threshold: f64,
+
+    pub drag_coefficient: f64,
+    pub drag_area_m2: f64,
+    /// Mass released by the event (0 for a deployment that keeps everything attached).
+    #[serde(default)]
+    pub jettisoned_mass_kg: f64,
+}
+
+impl DeploymentEvent {
+    pub fn new(
+        name: String,
+        trigger: DeploymentTrigger,
+        threshold: f64,
+        drag_coefficient: f64,
+        drag_area_m2: f64,
+        jettisoned_mass_kg: f64,
+    ) -> Result<Self, String> {
+        if threshold < 0.0 {
+            return Err("Trigger threshold must be >= 0".into());
+        }
+        if drag_coefficient <= 0.0 {
+            return Err("Drag coefficient after deployment must be > 0".into());
+        }
+        if drag_area_m2 <= 0.0 {
+            return Err("Drag area after deployment must be > 0".into());
+        }
+        if jettisoned_mass_kg < 0.0 {
+            return Err("Jettisoned mass must be >= 0".into());
+        }
+        Ok(Self {
+            name,
+            trigger,
+            threshold,
+            drag_coefficient,
+            drag_area_m2,
+            jettisoned_mass_kg,
+        })
+    }
+
+    pub fn is_triggered(&self, days_since_epoch: f64, elevation_km: f64) -> bool {
+        match self.trigger {
+            DeploymentTrigger::DaysSinceEpoch => days_since_epoch >= self.threshold,
+            DeploymentTrigger::AltitudeBelowKm => elevation_km < self.threshold,
+        }
+    }
+}
+
+/// The same satellite propagated as if none of its deployment events (nor burns) had happened,
+/// for comparing time to reentry.
+#[derive(Debug, Clone)]
+pub struct UndeployedComparison {
+    /// Altitude at this step (NaN once reentered).
+    pub elevation_km: f64,
+    /// When the undeployed satellite reenters (dropped below 100 km). Once the deployed satellite
+    /// has reentered, this is predicted ahead up to the end of the run's `max_days`; `None` if it
+    /// is still in orbit by then.
+    pub reentry_time: Option<satkit::Instant>,
+}
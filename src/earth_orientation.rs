@@ -0,0 +1,218 @@
+use nalgebra::{DMatrix, DVector};
+use once_cell::sync::OnceCell;
+use satkit::frametransform::{gmst, qteme2itrf};
+use satkit::types::{Quaternion, Vec3};
+use satkit::{Instant, TimeScale};
+
+/// How the Earth orientation was obtained for a step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EarthOrientationMode {
+    /// Tabulated IERS EOP values (measured, or the official short-term predictions).
+    Tabulated,
+    /// Beyond the EOP tables: polar motion and UT1-UTC extrapolated from a model fit to the tables.
+    Extrapolated,
+    /// No EOP data at all: GMST rotation only (UT1 = UTC, no polar motion).
+    GmstOnly,
+}
+
+impl EarthOrientationMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EarthOrientationMode::Tabulated => "EOP table",
+            EarthOrientationMode::Extrapolated => "EOP extrapolated",
+            EarthOrientationMode::GmstOnly => "GMST only",
+        }
+    }
+}
+
+const ARCSEC_TO_RAD: f64 = std::f64::consts::PI / 180.0 / 3600.0;
+const ANNUAL_PERIOD_DAYS: f64 = 365.25;
+const CHANDLER_PERIOD_DAYS: f64 = 435.0;
+
+/// Length of the tail of the EOP tables used to fit the prediction model.
+const FIT_SPAN_DAYS: f64 = 3.0 * 365.25;
+
+/// Least-squares fit of the tail of the EOP tables, used to extrapolate past their end.
+///
+/// satkit sets UT1-UTC and polar motion to zero once a time falls outside the downloaded Earth
+/// Orientation Parameter (EOP) tables. This uses the same kind of model IERS Bulletin A uses for
+/// its predictions instead: an annual + Chandler wobble for polar motion, and a linear drift +
+/// seasonal terms for UT1.
+#[derive(Debug, Clone)]
+struct EopPredictionModel {
+    /// Last MJD (UTC) covered by the EOP tables. Time in the model is measured from here.
+    table_end_mjd_utc: f64,
+    /// Coefficients of [1, cos A, sin A, cos C, sin C] (arcsec).
+    polar_x_coeffs: Vec<f64>,
+    polar_y_coeffs: Vec<f64>,
+    /// Coefficients of [1, t, cos A, sin A, cos 2A, sin 2A] (seconds).
+    ///
+    /// UT1-TAI is fit instead of UT1-UTC, as it has no leap-second discontinuities.
+    ut1_minus_tai_coeffs: Vec<f64>,
+}
+
+fn polar_motion_basis(days: f64) -> [f64; 5] {
+    let a = 2.0 * std::f64::consts::PI * days / ANNUAL_PERIOD_DAYS;
+    let c = 2.0 * std::f64::consts::PI * days / CHANDLER_PERIOD_DAYS;
+    [1.0, a.cos(), a.sin(), c.cos(), c.sin()]
+}
+
+fn ut1_basis(days: f64) -> [f64; 6] {
+    let a = 2.0 * std::f64::consts::PI * days / ANNUAL_PERIOD_DAYS;
+    [
+        1.0,
+        days,
+        a.cos(),
+        a.sin(),
+        (2.0 * a).cos(),
+        (2.0 * a).sin(),
+    ]
+}
+
+/// TAI - UTC (leap seconds) in seconds, at the given instant.
+fn tai_minus_utc_seconds(time: &Instant) -> f64 {
+    (time.as_mjd_with_scale(TimeScale::TAI) - time.as_mjd_with_scale(TimeScale::UTC)) * 86_400.0
+}
+
+fn least_squares(rows: &[Vec<f64>], observations: &[f64]) -> Option<Vec<f64>> {
+    let columns = rows.first()?.len();
+    let a = DMatrix::from_fn(rows.len(), columns, |r, c| rows[r][c]);
+    let b = DVector::from_column_slice(observations);
+    let solution = a.svd(true, true).solve(&b, 1e-12).ok()?;
+    Some(solution.iter().copied().collect())
+}
+
+impl EopPredictionModel {
+    /// Fit the model to the last `FIT_SPAN_DAYS` of the loaded EOP tables.
+    ///
+    /// Returns `None` if no EOP tables are loaded.
+    fn fit() -> Option<Self> {
+        // We report the EOP mode in telemetry, so satkit's own warning is redundant.
+        satkit::earth_orientation_params::disable_eop_time_warning();
+        let eop_at = |mjd_utc: f64| satkit::earth_orientation_params::eop_from_mjd_utc(mjd_utc);
+
+        // Find the end of the tables: step forward from J2000 until data runs out, then bisect.
+        let mut known_good = 51544.5;
+        eop_at(known_good)?;
+        let mut unknown = known_good + 64.0;
+        while eop_at(unknown).is_some() {
+            known_good = unknown;
+            unknown += 64.0;
+        }
+        while unknown - known_good > 0.01 {
+            let mid = 0.5 * (known_good + unknown);
+            if eop_at(mid).is_some() {
+                known_good = mid;
+            } else {
+                unknown = mid;
+            }
+        }
+        let table_end_mjd_utc = known_good;
+
+        let mut polar_rows = Vec::new();
+        let mut ut1_rows = Vec::new();
+        let mut xs = Vec::new();
+        let mut ys = Vec::new();
+        let mut ut1_minus_tai = Vec::new();
+        let mut days = -FIT_SPAN_DAYS;
+        while days <= 0.0 {
+            let mjd_utc = table_end_mjd_utc + days;
+            if let Some(eop) = eop_at(mjd_utc) {
+                polar_rows.push(polar_motion_basis(days).to_vec());
+                ut1_rows.push(ut1_basis(days).to_vec());
+                xs.push(eop[1]);
+                ys.push(eop[2]);
+                ut1_minus_tai.push(
+                    eop[0]
+                        - tai_minus_utc_seconds(&Instant::from_mjd_with_scale(
+                            mjd_utc,
+                            TimeScale::UTC,
+                        )),
+                );
+            }
+            days += 1.0;
+        }
+
+        Some(Self {
+            table_end_mjd_utc,
+            polar_x_coeffs: least_squares(&polar_rows, &xs)?,
+            polar_y_coeffs: least_squares(&polar_rows, &ys)?,
+            ut1_minus_tai_coeffs: least_squares(&ut1_rows, &ut1_minus_tai)?,
+        })
+    }
+
+    /// Predicted (UT1-UTC seconds, x_p arcsec, y_p arcsec) at `time`.
+    fn predict(&self, time: &Instant) -> (f64, f64, f64) {
+        let days = time.as_mjd_with_scale(TimeScale::UTC) - self.table_end_mjd_utc;
+        let evaluate = |basis: &[f64], coeffs: &[f64]| -> f64 {
+            basis.iter().zip(coeffs).map(|(b, c)| b * c).sum()
+        };
+
+        let polar_basis = polar_motion_basis(days);
+        let xp = evaluate(&polar_basis, &self.polar_x_coeffs);
+        let yp = evaluate(&polar_basis, &self.polar_y_coeffs);
+        let dut1 =
+            evaluate(&ut1_basis(days), &self.ut1_minus_tai_coeffs) + tai_minus_utc_seconds(time);
+        (dut1, xp, yp)
+    }
+}
+
+fn prediction_model() -> Option<&'static EopPredictionModel> {
+    static MODEL: OnceCell<Option<EopPredictionModel>> = OnceCell::new();
+    MODEL.get_or_init(EopPredictionModel::fit).as_ref()
+}
+
+/// Rotation of the coordinate system about an axis (i.e. the vector rotates by -angle).
+fn coordinate_rotation(axis: &nalgebra::Unit<Vec3>, angle_rad: f64) -> Quaternion {
+    Quaternion::from_axis_angle(axis, -angle_rad)
+}
+
+/// GMST (Vallado algorithm 15, as in `satkit::frametransform::gmst`) from an explicit UT1 MJD.
+fn gmst_from_mjd_ut1(mjd_ut1: f64) -> f64 {
+    let tut1 = (mjd_ut1 - 51544.5) / 36525.0;
+    let gmst_seconds = tut1.mul_add(
+        tut1.mul_add(
+            tut1.mul_add(-6.2e-6, 0.093104),
+            876600.0f64.mul_add(3600.0, 8640184.812866),
+        ),
+        67310.54841,
+    );
+    ((gmst_seconds % 86400.0) / 240.0).to_radians()
+}
+
+/// Rotation from TEME to ITRF at `time`, and the Earth orientation mode that was used.
+///
+/// Inside the EOP tables this is exactly `satkit::frametransform::qteme2itrf`. Past their end,
+/// UT1-UTC and polar motion come from the extrapolation model; if no tables are loaded at all,
+/// a plain GMST rotation is used.
+pub fn qteme2itrf_with_mode(time: &Instant) -> (Quaternion, EarthOrientationMode) {
+    let model = prediction_model();
+    if satkit::earth_orientation_params::get(time).is_some() {
+        return (qteme2itrf(time), EarthOrientationMode::Tabulated);
+    }
+
+    match model {
+        Some(model) => {
+            let (dut1, xp_arcsec, yp_arcsec) = model.predict(time);
+            let mjd_ut1 = time.as_mjd_with_scale(TimeScale::UTC) + dut1 / 86_400.0;
+
+            // Polar motion (ITRF -> TIRS), IERS 2010 eq. 5.3 with the TIO locator s'.
+            let t_tt = (time.as_mjd_with_scale(TimeScale::TT) - 51544.5) / 36525.0;
+            let s_prime = -47.0e-6 * ARCSEC_TO_RAD * t_tt;
+            let itrf_to_tirs = coordinate_rotation(&Vec3::z_axis(), -s_prime)
+                * coordinate_rotation(&Vec3::y_axis(), xp_arcsec * ARCSEC_TO_RAD)
+                * coordinate_rotation(&Vec3::x_axis(), yp_arcsec * ARCSEC_TO_RAD);
+
+            (
+                itrf_to_tirs.conjugate()
+                    * coordinate_rotation(&Vec3::z_axis(), gmst_from_mjd_ut1(mjd_ut1)),
+                EarthOrientationMode::Extrapolated,
+            )
+        }
+        // Without EOP data satkit's UT1 falls back to UTC, which is what we want here.
+        None => (
+            coordinate_rotation(&Vec3::z_axis(), gmst(time)),
+            EarthOrientationMode::GmstOnly,
+        ),
+    }
+}
@@ -0,0 +1,470 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use satkit::consts::OMEGA_EARTH;
+use satkit::{Duration, ITRFCoord, Instant};
+use serde::{Deserialize, Serialize};
+
+use crate::earth_orientation::qteme2itrf_with_mode;
+use crate::initial_state_model::{GroundTarget, InitialSimulationState, TargetShape};
+use crate::orbital_elements::wrap_degrees_360;
+use crate::output_frames::teme_to_itrf_state;
+use crate::satellite_state::{
+    calculate_sun_irradiance_received_w_per_m2, propagate_teme, pythag_3, sun_position_itrf_m,
+};
+
+/// Spacing of the samples at which every target is checked for a possible access.
+const COARSE_STEP_SECONDS: f64 = 60.0;
+/// Spacing of the samples within possible accesses, and so the resolution of window edges.
+const FINE_STEP_SECONDS: f64 = 5.0;
+/// Polygon edges are sampled at this spacing when looking for the point closest to nadir.
+const EDGE_SAMPLE_SPACING_DEG: f64 = 0.05;
+/// Smallest radius of the Earth: the coarse check takes the targets as low as this, which can
+/// only make it let through more samples.
+const EARTH_POLAR_RADIUS_M: f64 = 6_356_752.3;
+/// Added to the coarse check for the difference between geocentric and geodetic nadir.
+const COARSE_MARGIN_DEG: f64 = 1.0;
+
+/// Camera on the satellite.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Imager {
+    /// Largest angle between the line of sight and nadir the satellite can point to.
+    pub max_off_nadir_deg: f64,
+    pub focal_length_mm: f64,
+    pub pixel_pitch_um: f64,
+}
+
+impl Imager {
+    pub fn new(
+        max_off_nadir_deg: f64,
+        focal_length_mm: f64,
+        pixel_pitch_um: f64,
+    ) -> Result<Self, String> {
+        if !(0.0..90.0).contains(&max_off_nadir_deg) {
+            return Err("Maximum off-nadir angle must be between 0 and 90 degrees".into());
+        }
+        if focal_length_mm <= 0.0 {
+            return Err("Focal length must be > 0".into());
+        }
+        if pixel_pitch_um <= 0.0 {
+            return Err("Pixel pitch must be > 0".into());
+        }
+        Ok(Self {
+            max_off_nadir_deg,
+            focal_length_mm,
+            pixel_pitch_um,
+        })
+    }
+
+    /// Ground sample distance at a slant range, seen from the given elevation: the geometric
+    /// mean of the pixel footprint across the line of sight and along it, where the ground is
+    /// tilted and the footprint stretched by 1 / sin(elevation).
+    pub fn ground_sample_distance_m(&self, slant_range_m: f64, elevation_deg: f64) -> f64 {
+        let across_m = slant_range_m * self.pixel_pitch_um * 1e-6 / (self.focal_length_mm * 1e-3);
+        across_m / elevation_deg.to_radians().sin().sqrt()
+    }
+}
+
+/// Geometry of an image taken at one instant.
+#[derive(Debug, Clone)]
+pub struct ImagingGeometry {
+    pub time: Instant,
+    /// Point of the target imaged: the one closest to nadir.
+    pub aim_latitude_deg: f64,
+    pub aim_longitude_deg: f64,
+    pub off_nadir_deg: f64,
+    /// Look angles of the satellite from the aim point (azimuth from north through east).
+    pub azimuth_deg: f64,
+    pub elevation_deg: f64,
+    pub slant_range_m: f64,
+    pub ground_sample_distance_m: f64,
+    pub sun_azimuth_deg: f64,
+    pub sun_elevation_deg: f64,
+    /// Angle at the aim point between the Sun and the satellite; small when the Sun is behind
+    /// the camera.
+    pub phase_angle_deg: f64,
+}
+
+/// Interval over which every constraint of a target is met.
+#[derive(Debug, Clone)]
+pub struct ImagingOpportunity {
+    pub target: String,
+    /// First and last samples of the window.
+    pub start: Instant,
+    pub end: Instant,
+    /// Sample of the window with the smallest off-nadir angle.
+    pub best: ImagingGeometry,
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Angle between two vectors, in radians.
+fn angle_between_rad(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    (dot(a, b) / (pythag_3(a) * pythag_3(b)))
+        .clamp(-1.0, 1.0)
+        .acos()
+}
+
+/// Point on the WGS84 ellipsoid, with its local east/north/up axes.
+#[derive(Debug, Clone)]
+struct SurfacePoint {
+    latitude_deg: f64,
+    longitude_deg: f64,
+    position_m: [f64; 3],
+    east: [f64; 3],
+    north: [f64; 3],
+    up: [f64; 3],
+}
+
+impl SurfacePoint {
+    fn new(latitude_deg: f64, longitude_deg: f64) -> Self {
+        let ecef = nav_types::ECEF::from(nav_types::WGS84::from_degrees_and_meters(
+            latitude_deg,
+            longitude_deg,
+            0.0,
+        ));
+        let (sin_lat, cos_lat) = latitude_deg.to_radians().sin_cos();
+        let (sin_lon, cos_lon) = longitude_deg.to_radians().sin_cos();
+        Self {
+            latitude_deg,
+            longitude_deg,
+            position_m: [ecef.x(), ecef.y(), ecef.z()],
+            east: [-sin_lon, cos_lon, 0.0],
+            north: [-sin_lat * cos_lon, -sin_lat * sin_lon, cos_lat],
+            up: [cos_lat * cos_lon, cos_lat * sin_lon, sin_lat],
+        }
+    }
+
+    /// Azimuth and elevation (degrees) and range (m) of an ITRF position seen from this point.
+    fn look_angles(&self, position_itrf_m: &[f64; 3]) -> (f64, f64, f64) {
+        let line_of_sight: [f64; 3] =
+            std::array::from_fn(|a| position_itrf_m[a] - self.position_m[a]);
+        let range_m = pythag_3(&line_of_sight);
+        let azimuth_deg = wrap_degrees_360(
+            dot(&line_of_sight, &self.east)
+                .atan2(dot(&line_of_sight, &self.north))
+                .to_degrees(),
+        );
+        let elevation_deg = (dot(&line_of_sight, &self.up) / range_m)
+            .clamp(-1.0, 1.0)
+            .asin()
+            .to_degrees();
+        (azimuth_deg, elevation_deg, range_m)
+    }
+
+    /// Angle between nadir at the satellite and the line of sight to this point, in degrees.
+    fn off_nadir_deg(&self, satellite_itrf_m: &[f64; 3]) -> f64 {
+        let nadir = satellite_itrf_m.map(|x| -x);
+        let line_of_sight: [f64; 3] =
+            std::array::from_fn(|a| self.position_m[a] - satellite_itrf_m[a]);
+        angle_between_rad(&nadir, &line_of_sight).to_degrees()
+    }
+}
+
+/// Whether a point lies inside a latitude/longitude polygon (ray casting along the parallel).
+fn polygon_contains(vertices_deg: &[[f64; 2]], latitude_deg: f64, longitude_deg: f64) -> bool {
+    let mut inside = false;
+    for (a, b) in vertices_deg.iter().zip(vertices_deg.iter().cycle().skip(1)) {
+        if (a[0] > latitude_deg) != (b[0] > latitude_deg) {
+            let crossing_longitude_deg =
+                a[1] + (latitude_deg - a[0]) / (b[0] - a[0]) * (b[1] - a[1]);
+            if longitude_deg < crossing_longitude_deg {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Points of a target that can be aimed at: the point itself, or a polygon's boundary sampled
+/// along its edges (plus the sub-satellite point, whenever it falls inside).
+struct TargetGeometry<'a> {
+    target: &'a GroundTarget,
+    boundary: Vec<SurfacePoint>,
+}
+
+impl<'a> TargetGeometry<'a> {
+    fn new(target: &'a GroundTarget) -> Self {
+        let boundary = match &target.shape {
+            TargetShape::Point {
+                latitude_deg,
+                longitude_deg,
+            } => vec![SurfacePoint::new(*latitude_deg, *longitude_deg)],
+            TargetShape::Polygon { vertices_deg } => vertices_deg
+                .iter()
+                .zip(vertices_deg.iter().cycle().skip(1))
+                .flat_map(|(a, b)| {
+                    let span_deg = (b[0] - a[0]).abs().max((b[1] - a[1]).abs());
+                    let samples = ((span_deg / EDGE_SAMPLE_SPACING_DEG).ceil() as usize).max(1);
+                    (0..samples).map(move |k| {
+                        let fraction = k as f64 / samples as f64;
+                        SurfacePoint::new(
+                            a[0] + fraction * (b[0] - a[0]),
+                            a[1] + fraction * (b[1] - a[1]),
+                        )
+                    })
+                })
+                .collect(),
+        };
+        Self { target, boundary }
+    }
+
+    /// Geodetic sub-satellite point, if it lies inside the target polygon.
+    fn sub_satellite_point_inside(
+        &self,
+        satellite_itrf_m: &[f64; 3],
+    ) -> anyhow::Result<Option<(f64, f64)>> {
+        let TargetShape::Polygon { vertices_deg } = &self.target.shape else {
+            return Ok(None);
+        };
+        let coord = ITRFCoord::from_slice(satellite_itrf_m)?;
+        let (latitude_deg, longitude_deg) = (coord.latitude_deg(), coord.longitude_deg());
+        Ok(polygon_contains(vertices_deg, latitude_deg, longitude_deg)
+            .then_some((latitude_deg, longitude_deg)))
+    }
+
+    /// Smallest Earth central angle between the satellite and the target, in radians.
+    fn central_angle_rad(&self, satellite_itrf_m: &[f64; 3]) -> anyhow::Result<f64> {
+        if self.sub_satellite_point_inside(satellite_itrf_m)?.is_some() {
+            return Ok(0.0);
+        }
+        Ok(self
+            .boundary
+            .iter()
+            .map(|point| angle_between_rad(&point.position_m, satellite_itrf_m))
+            .fold(f64::INFINITY, f64::min))
+    }
+
+    /// Point of the target closest to nadir, and its off-nadir angle.
+    fn aim_point(&self, satellite_itrf_m: &[f64; 3]) -> anyhow::Result<(SurfacePoint, f64)> {
+        let point = match self.sub_satellite_point_inside(satellite_itrf_m)? {
+            Some((latitude_deg, longitude_deg)) => SurfacePoint::new(latitude_deg, longitude_deg),
+            None => self
+                .boundary
+                .iter()
+                .min_by(|a, b| {
+                    a.off_nadir_deg(satellite_itrf_m)
+                        .total_cmp(&b.off_nadir_deg(satellite_itrf_m))
+                })
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Target {} has no points", self.target.name))?,
+        };
+        let off_nadir_deg = point.off_nadir_deg(satellite_itrf_m);
+        Ok((point, off_nadir_deg))
+    }
+
+    /// Geometry at `time` if every constraint of the target is met.
+    fn evaluate(
+        &self,
+        imager: &Imager,
+        satellite_itrf_m: &[f64; 3],
+        time: &Instant,
+    ) -> anyhow::Result<Option<ImagingGeometry>> {
+        let (aim, off_nadir_deg) = self.aim_point(satellite_itrf_m)?;
+        let (azimuth_deg, elevation_deg, slant_range_m) = aim.look_angles(satellite_itrf_m);
+        if off_nadir_deg > imager.max_off_nadir_deg || elevation_deg <= 0.0 {
+            return Ok(None);
+        }
+        let sun = sun_position_itrf_m(time);
+        let sun_itrf_m = [sun[0], sun[1], sun[2]];
+        let (sun_azimuth_deg, sun_elevation_deg, _) = aim.look_angles(&sun_itrf_m);
+        if sun_elevation_deg < self.target.min_sun_elevation_deg {
+            return Ok(None);
+        }
+        if self.target.require_sunlit_satellite
+            && calculate_sun_irradiance_received_w_per_m2(satellite_itrf_m, time) <= 0.0
+        {
+            return Ok(None);
+        }
+        let to_sun: [f64; 3] = std::array::from_fn(|a| sun_itrf_m[a] - aim.position_m[a]);
+        let to_satellite: [f64; 3] =
+            std::array::from_fn(|a| satellite_itrf_m[a] - aim.position_m[a]);
+        Ok(Some(ImagingGeometry {
+            time: *time,
+            aim_latitude_deg: aim.latitude_deg,
+            aim_longitude_deg: aim.longitude_deg,
+            off_nadir_deg,
+            azimuth_deg,
+            elevation_deg,
+            slant_range_m,
+            ground_sample_distance_m: imager.ground_sample_distance_m(slant_range_m, elevation_deg),
+            sun_azimuth_deg,
+            sun_elevation_deg,
+            phase_angle_deg: angle_between_rad(&to_sun, &to_satellite).to_degrees(),
+        }))
+    }
+}
+
+/// Largest Earth central angle between the sub-satellite point and a point on the ground seen
+/// within `max_off_nadir_deg`, from a distance of `radius_m` from the Earth's centre.
+fn max_central_angle_rad(radius_m: f64, max_off_nadir_deg: f64) -> f64 {
+    let off_nadir_rad = max_off_nadir_deg.to_radians();
+    let cos_elevation = radius_m * off_nadir_rad.sin() / EARTH_POLAR_RADIUS_M;
+    if cos_elevation >= 1.0 {
+        // The limit is beyond the horizon.
+        (EARTH_POLAR_RADIUS_M / radius_m).acos()
+    } else {
+        std::f64::consts::FRAC_PI_2 - off_nadir_rad - cos_elevation.acos()
+    }
+}
+
+/// SGP4 states of the satellite, from the element set that the TLE history selects (as in a
+/// run), or from the run's TLE.
+struct Ephemeris<'a> {
+    initial: &'a InitialSimulationState,
+    tle: satkit::TLE,
+    history_tles: Vec<satkit::TLE>,
+}
+
+impl<'a> Ephemeris<'a> {
+    fn new(initial: &'a InitialSimulationState) -> Self {
+        Self {
+            initial,
+            tle: initial.tle.to_satkit_tle(),
+            history_tles: initial
+                .tle_history
+                .iter()
+                .flat_map(|history| &history.sets)
+                .map(|set| set.to_satkit_tle())
+                .collect(),
+        }
+    }
+
+    /// ITRF position (m) and inertial velocity (m/s) at `time`.
+    fn state(&mut self, time: &Instant) -> anyhow::Result<([f64; 3], [f64; 3])> {
+        let tle = match &self.initial.tle_history {
+            Some(history) => &mut self.history_tles[history.select(time)],
+            None => &mut self.tle,
+        };
+        let (position_teme, velocity_teme) = propagate_teme(tle, time)?;
+        let itrf = teme_to_itrf_state(
+            &qteme2itrf_with_mode(time).0,
+            &position_teme,
+            &velocity_teme,
+        );
+        Ok((itrf.position, velocity_teme))
+    }
+}
+
+/// Windows of `geometry` between `from` and `to`, sampled every `FINE_STEP_SECONDS`.
+fn scan_for_windows(
+    ephemeris: &mut Ephemeris,
+    geometry: &TargetGeometry,
+    imager: &Imager,
+    from: &Instant,
+    to: &Instant,
+) -> anyhow::Result<Vec<ImagingOpportunity>> {
+    let span_seconds = (*to - *from).as_seconds();
+    let steps = (span_seconds / FINE_STEP_SECONDS).ceil() as usize;
+    let mut windows = Vec::new();
+    let mut open: Option<ImagingOpportunity> = None;
+    for step in 0..=steps {
+        let time =
+            *from + Duration::from_seconds((step as f64 * FINE_STEP_SECONDS).min(span_seconds));
+        let (position_itrf_m, _) = ephemeris.state(&time)?;
+        match geometry.evaluate(imager, &position_itrf_m, &time)? {
+            Some(look) => match &mut open {
+                Some(window) => {
+                    window.end = time;
+                    if look.off_nadir_deg < window.best.off_nadir_deg {
+                        window.best = look;
+                    }
+                }
+                None => {
+                    open = Some(ImagingOpportunity {
+                        target: geometry.target.name.clone(),
+                        start: time,
+                        end: time,
+                        best: look,
+                    });
+                }
+            },
+            None => windows.extend(open.take()),
+        }
+    }
+    windows.extend(open);
+    Ok(windows)
+}
+
+/// Imaging opportunities over every ground target during the run of `initial`.
+///
+/// An image can be taken while the target is within the imager's off-nadir limit and above the
+/// horizon, the Sun is at least the target's minimum elevation above it, and (if the target
+/// asks for it) the satellite is out of Earth's shadow. For a polygon, the point of the target
+/// closest to nadir is aimed at. The satellite is propagated with SGP4 from the run's element
+/// sets, so burns are not included; the search stops early if the satellite decays.
+///
+/// Every target is first checked once a minute against the widest ground range the imager can
+/// reach, padded by the ground track's motion over a minute; only around the samples that pass
+/// are windows searched for, every 5 seconds. Windows at the edges of the run are cut there.
+/// `completed` counts finished targets.
+pub fn find_imaging_opportunities(
+    initial: &InitialSimulationState,
+    completed: &AtomicUsize,
+) -> anyhow::Result<Vec<ImagingOpportunity>> {
+    let Some(imager) = &initial.satellite.imager else {
+        return Err(anyhow::anyhow!("Imaging opportunities require an imager"));
+    };
+    if initial.ground_targets.is_empty() {
+        return Err(anyhow::anyhow!("No ground targets to image"));
+    }
+    let mut ephemeris = Ephemeris::new(initial);
+    let duration_seconds = initial.simulation_settings.max_days * 86_400.0;
+    let mut coarse = Vec::new();
+    let mut offset_seconds = 0.0;
+    while offset_seconds <= duration_seconds {
+        let time = initial.tle.epoch + Duration::from_seconds(offset_seconds);
+        let Ok(state) = ephemeris.state(&time) else {
+            break;
+        };
+        coarse.push((time, state));
+        offset_seconds += COARSE_STEP_SECONDS;
+    }
+
+    let mut opportunities = Vec::new();
+    for target in &initial.ground_targets {
+        let geometry = TargetGeometry::new(target);
+        let reachable = coarse
+            .iter()
+            .map(|(_, (position_itrf_m, velocity_teme))| {
+                let radius_m = pythag_3(position_itrf_m);
+                let reach_rad = max_central_angle_rad(radius_m, imager.max_off_nadir_deg)
+                    + COARSE_STEP_SECONDS * (pythag_3(velocity_teme) / radius_m + OMEGA_EARTH)
+                    + COARSE_MARGIN_DEG.to_radians();
+                Ok(geometry.central_angle_rad(position_itrf_m)? <= reach_rad)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut index = 0;
+        while index < reachable.len() {
+            if !reachable[index] {
+                index += 1;
+                continue;
+            }
+            let first = index;
+            while index < reachable.len() && reachable[index] {
+                index += 1;
+            }
+            // Samples either side of the run could not see the target, so no window crosses
+            // them.
+            let from = coarse[first.saturating_sub(1)].0;
+            let to = coarse[index.min(reachable.len() - 1)].0;
+            opportunities.extend(scan_for_windows(
+                &mut ephemeris,
+                &geometry,
+                imager,
+                &from,
+                &to,
+            )?);
+        }
+        completed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    opportunities.sort_by(|a, b| {
+        a.start
+            .partial_cmp(&b.start)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    Ok(opportunities)
+}
@@ -1,6 +1,16 @@
 use once_cell::unsync::OnceCell;
 use serde::{Deserialize, Serialize};
 
+use crate::attitude::AttitudeSettings;
+use crate::deployment::DeploymentEvent;
+use crate::imaging::Imager;
+use crate::propulsion::{ScheduledBurn, Thruster};
+use crate::solar_power::SolarArray;
+use crate::spacecraft_geometry::Panel;
+use crate::station_keeping::StationKeeping;
+use crate::tle_history::TleHistory;
+use crate::uncertainty::UncertaintyModel;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroundStation {
     pub name: String,
@@ -57,6 +67,76 @@ impl GroundStation {
     }
 }
 
+/// Area to be imaged: a single point, or a polygon of (latitude, longitude) vertices in order
+/// around its boundary. Polygons must not cross the antimeridian.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TargetShape {
+    Point {
+        latitude_deg: f64,
+        longitude_deg: f64,
+    },
+    Polygon {
+        vertices_deg: Vec<[f64; 2]>,
+    },
+}
+
+impl TargetShape {
+    /// Vertices of the polygon, or the point as a single vertex.
+    pub fn vertices_deg(&self) -> Vec<[f64; 2]> {
+        match self {
+            TargetShape::Point {
+                latitude_deg,
+                longitude_deg,
+            } => vec![[*latitude_deg, *longitude_deg]],
+            TargetShape::Polygon { vertices_deg } => vertices_deg.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroundTarget {
+    pub name: String,
+    pub shape: TargetShape,
+    /// Lowest Sun elevation at the imaged point (0 for daylight only).
+    pub min_sun_elevation_deg: f64,
+    /// Only image while the satellite itself is out of Earth's shadow.
+    pub require_sunlit_satellite: bool,
+}
+
+impl GroundTarget {
+    pub fn new(
+        name: String,
+        shape: TargetShape,
+        min_sun_elevation_deg: f64,
+        require_sunlit_satellite: bool,
+    ) -> Result<Self, String> {
+        let vertices_deg = shape.vertices_deg();
+        if let TargetShape::Polygon { .. } = shape
+            && vertices_deg.len() < 3
+        {
+            return Err("A target polygon needs at least 3 vertices".to_string());
+        }
+        for [latitude_deg, longitude_deg] in vertices_deg {
+            if !(-90.0..=90.0).contains(&latitude_deg) {
+                return Err("Latitude must be between -90 and 90 degrees".to_string());
+            }
+            if !(-180.0..=180.0).contains(&longitude_deg) {
+                return Err("Longitude must be between -180 and 180 degrees".to_string());
+            }
+        }
+        if !(-90.0..=90.0).contains(&min_sun_elevation_deg) {
+            return Err("Minimum Sun elevation must be between -90 and 90 degrees".to_string());
+        }
+
+        Ok(Self {
+            name,
+            shape,
+            min_sun_elevation_deg,
+            require_sunlit_satellite,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Satellite {
     pub name: String,
@@ -65,7 +145,45 @@ pub struct Satellite {
     pub drag_coefficient: f64,
 
     /// @brief Average cross-sectional area of the satellite (A) for atmospheric drag calculations.
+    /// With a panel model, this is the reference area that the TLE's B* corresponds to.
     pub drag_area_m2: f64,
+
+    #[serde(default)]
+    pub attitude: AttitudeSettings,
+
+    /// Flat-panel geometry. When non-empty, drag and SRP areas are projected from the attitude
+    /// each step instead of using `drag_area_m2`.
+    #[serde(default)]
+    pub panels: Vec<Panel>,
+
+    #[serde(default)]
+    pub solar_arrays: Vec<SolarArray>,
+
+    /// Drag configuration changes during the run, checked in order at every step.
+    #[serde(default)]
+    pub deployment_events: Vec<DeploymentEvent>,
+
+    #[serde(default)]
+    pub thruster: Option<Thruster>,
+    /// Finite burns to carry out during the run. Requires a `thruster`.
+    #[serde(default)]
+    pub burn_schedule: Vec<ScheduledBurn>,
+    /// Altitude-maintenance autopilot. Requires a `thruster`.
+    #[serde(default)]
+    pub station_keeping: Option<StationKeeping>,
+
+    /// Nose radius for the Sutton-Graves heating estimate. When set, every perigee pass is
+    /// analysed for aerodynamic loads (see `aerobraking`).
+    #[serde(default)]
+    pub nose_radius_m: Option<f64>,
+
+    /// Radius of a sphere enclosing the satellite, for the probability of collision.
+    #[serde(default)]
+    pub hard_body_radius_m: Option<f64>,
+
+    /// Camera, for imaging opportunities over the `InitialSimulationState::ground_targets`.
+    #[serde(default)]
+    pub imager: Option<Imager>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -175,6 +293,36 @@ impl TleData {
 pub struct InitialSimulationState {
     pub tle: TleData,
     pub ground_stations: Vec<GroundStation>,
+    /// Points and areas to image (see `imaging`).
+    #[serde(default)]
+    pub ground_targets: Vec<GroundTarget>,
     pub satellite: Satellite,
     pub simulation_settings: SimulationSettings,
+
+    /// Additional element sets propagated alongside `tle` (e.g. a generated Walker constellation
+    /// or rideshare dispersal). They share the `satellite` properties of the primary.
+    #[serde(default)]
+    pub constellation: Vec<TleData>,
+
+    /// Position uncertainty to carry through the run, if any.
+    #[serde(default)]
+    pub uncertainty: Option<UncertaintyModel>,
+
+    /// Later (or earlier) element sets of the primary. When present, every step propagates the
+    /// set that the history selects instead of `tle`, which still marks the start of the run.
+    #[serde(default)]
+    pub tle_history: Option<TleHistory>,
+}
+
+impl InitialSimulationState {
+    /// Scale B* of every satellite, which all share the same properties.
+    pub fn scale_bstar(&mut self, factor: f64) {
+        self.tle.bstar *= factor;
+        for member in &mut self.constellation {
+            member.bstar *= factor;
+        }
+        for set in self.tle_history.iter_mut().flat_map(|h| &mut h.sets) {
+            set.bstar *= factor;
+        }
+    }
 }
@@ -1,5 +1,31 @@
+mod aerobraking;
+mod attitude;
+mod batch;
+mod collision_avoidance;
+mod collision_probability;
+mod conjunction;
+mod constellation;
+mod decay_trend;
+mod delta_v_budget;
+mod deployment;
+mod earth_orientation;
+mod imaging;
 mod initial_state_model;
+mod maneuver_planner;
+mod monte_carlo;
+mod orbit_determination;
+mod orbital_elements;
+mod output_frames;
+mod parameter_sweep;
+mod passes;
+mod propulsion;
 mod satellite_state;
+mod solar_power;
+mod spacecraft_geometry;
+mod station_keeping;
+mod telemetry_export;
+mod tle_history;
+mod uncertainty;
 
 mod ui;
 
@@ -0,0 +1,332 @@
+use satkit::Instant;
+use satkit::consts::{EARTH_RADIUS, MU_EARTH};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::initial_state_model::TleData;
+use crate::orbital_elements::fit_tle_to_state;
+use crate::propulsion::{ScheduledBurn, ThrustDirection, Thruster, fire_thruster};
+use crate::satellite_state::{MAX_SATELLITE_RADIUS_M, propagate_teme};
+
+/// Burns whose remaining impulsive delta-v is below this are not worth firing.
+const DELTA_V_TOLERANCE_M_PER_S: f64 = 0.01;
+
+/// Give up on a low-thrust transfer after this many burns.
+const MAX_LOW_THRUST_BURNS: usize = 2000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum TransferMethod {
+    /// Two burns, one at each apsis.
+    #[default]
+    Hohmann,
+    /// Three burns via an intermediate apogee (cheaper than Hohmann for large radius ratios).
+    BiElliptic,
+    /// The Hohmann burns split over as many apsis passes as the per-burn duration limit needs.
+    LowThrustMultiBurn,
+}
+
+impl TransferMethod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TransferMethod::Hohmann => "Hohmann",
+            TransferMethod::BiElliptic => "Bi-elliptic",
+            TransferMethod::LowThrustMultiBurn => "Multi-burn low thrust",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManeuverRequest {
+    pub target_perigee_altitude_km: f64,
+    pub target_apogee_altitude_km: f64,
+    pub method: TransferMethod,
+    /// Earliest time the first burn may start.
+    pub start_days_since_epoch: f64,
+    /// Intermediate apogee for `BiElliptic`.
+    pub bi_elliptic_apogee_altitude_km: f64,
+    /// Longest single burn for `LowThrustMultiBurn`, so each burn stays close to its apsis.
+    pub max_burn_minutes: f64,
+}
+
+impl ManeuverRequest {
+    pub fn new(
+        target_perigee_altitude_km: f64,
+        target_apogee_altitude_km: f64,
+        method: TransferMethod,
+        start_days_since_epoch: f64,
+        bi_elliptic_apogee_altitude_km: f64,
+        max_burn_minutes: f64,
+    ) -> Result<Self, String> {
+        if target_perigee_altitude_km > target_apogee_altitude_km {
+            return Err("Target perigee must not be above the target apogee".into());
+        }
+        if target_perigee_altitude_km < 0.0 {
+            return Err("Target perigee must not be below the Earth's surface".into());
+        }
+        if start_days_since_epoch < 0.0 {
+            return Err("Start must be >= 0 days".into());
+        }
+        if method == TransferMethod::BiElliptic
+            && bi_elliptic_apogee_altitude_km < target_apogee_altitude_km
+        {
+            return Err("Bi-elliptic apogee must be at least the target apogee".into());
+        }
+        // The planned burns are simulated step by step, which only supports near-Earth orbits.
+        let max_altitude_km = (MAX_SATELLITE_RADIUS_M - EARTH_RADIUS) / 1000.0;
+        let highest_apogee_km = match method {
+            TransferMethod::BiElliptic => bi_elliptic_apogee_altitude_km,
+            TransferMethod::Hohmann | TransferMethod::LowThrustMultiBurn => {
+                target_apogee_altitude_km
+            }
+        };
+        if highest_apogee_km >= max_altitude_km {
+            return Err(format!(
+                "Apogees must stay below {max_altitude_km:.0} km, the limit of the simulation"
+            ));
+        }
+        if method == TransferMethod::LowThrustMultiBurn && max_burn_minutes <= 0.0 {
+            return Err("Max burn duration must be > 0".into());
+        }
+        Ok(Self {
+            target_perigee_altitude_km,
+            target_apogee_altitude_km,
+            method,
+            start_days_since_epoch,
+            bi_elliptic_apogee_altitude_km,
+            max_burn_minutes,
+        })
+    }
+}
+
+/// One burn of a plan, with its simulated effect.
+#[derive(Debug, Clone)]
+pub struct PlannedBurn {
+    pub burn: ScheduledBurn,
+    pub delta_v_m_per_s: f64,
+    pub propellant_kg: f64,
+    /// Mean perigee / apogee altitude right after the burn.
+    pub perigee_altitude_km: f64,
+    pub apogee_altitude_km: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ManeuverPlan {
+    pub request: ManeuverRequest,
+    pub burns: Vec<PlannedBurn>,
+    pub total_delta_v_m_per_s: f64,
+    pub total_propellant_kg: f64,
+    /// Impulsive (ideal) delta-v of the transfer, for comparison with the finite burns.
+    pub impulsive_delta_v_m_per_s: f64,
+    /// Mean perigee / apogee altitude after the last burn.
+    pub achieved_perigee_altitude_km: f64,
+    pub achieved_apogee_altitude_km: f64,
+}
+
+/// One leg of a transfer: burn at the apsis nearest `burn_radius_m` until the opposite apsis
+/// is at `target_opposite_radius_m`.
+struct TransferLeg {
+    burn_radius_m: f64,
+    target_opposite_radius_m: f64,
+}
+
+/// Speed at radius `r` on an orbit whose apsides are `r` and `r_other`.
+fn apsis_speed_m_per_s(r: f64, r_other: f64) -> f64 {
+    (MU_EARTH * (2.0 / r - 2.0 / (r + r_other))).sqrt()
+}
+
+/// Impulsive delta-v of a sequence of legs starting from apsides (`r_a`, `r_b`).
+fn impulsive_delta_v_m_per_s(mut apsides: (f64, f64), legs: &[TransferLeg]) -> f64 {
+    let mut total = 0.0;
+    for leg in legs {
+        let (r, r_other) =
+            if (apsides.0 - leg.burn_radius_m).abs() <= (apsides.1 - leg.burn_radius_m).abs() {
+                apsides
+            } else {
+                (apsides.1, apsides.0)
+            };
+        total += (apsis_speed_m_per_s(r, leg.target_opposite_radius_m)
+            - apsis_speed_m_per_s(r, r_other))
+        .abs();
+        apsides = (r, leg.target_opposite_radius_m);
+    }
+    total
+}
+
+/// Mean perigee and apogee radii of an element set, in m.
+fn mean_apsis_radii_m(tle: &TleData) -> (f64, f64) {
+    let n_rad_per_s = tle.mean_motion * 2.0 * std::f64::consts::PI / 86_400.0;
+    let a = (MU_EARTH / (n_rad_per_s * n_rad_per_s)).cbrt();
+    (a * (1.0 - tle.eccen), a * (1.0 + tle.eccen))
+}
+
+/// Next time after `after` that the element set passes mean anomaly `mean_anomaly_deg`.
+fn next_passage(tle: &TleData, mean_anomaly_deg: f64, after: &Instant) -> Instant {
+    let period = satkit::Duration::from_days(1.0 / tle.mean_motion);
+    let fraction = (mean_anomaly_deg - tle.mean_anomaly).rem_euclid(360.0) / 360.0;
+    let mut time = tle.epoch + satkit::Duration::from_days(fraction / tle.mean_motion);
+    while time < *after {
+        time += period;
+    }
+    time
+}
+
+/// Plan a coplanar transfer to the requested perigee/apogee and simulate it with the thruster.
+///
+/// Each leg's burns are centred on an apsis and sized from vis-viva for the orbit reached so far,
+/// so the finite-burn losses of earlier burns are corrected by later ones. Altitudes are mean
+/// (SGP4) apsides above the equatorial radius. The spacecraft starts at the thruster's wet mass.
+pub fn plan_maneuver(
+    tle: &TleData,
+    thruster: &Thruster,
+    request: &ManeuverRequest,
+) -> anyhow::Result<ManeuverPlan> {
+    let epoch = tle.epoch;
+    let start = epoch + satkit::Duration::from_days(request.start_days_since_epoch);
+    let (position_teme, velocity_teme) = propagate_teme(&mut tle.to_satkit_tle(), &start)?;
+    let mut tle = fit_tle_to_state(tle, start, &position_teme, &velocity_teme)?;
+    let mut mass_kg = thruster.wet_mass_kg;
+
+    let (rp0, ra0) = mean_apsis_radii_m(&tle);
+    let rp1 = EARTH_RADIUS + request.target_perigee_altitude_km * 1000.0;
+    let ra1 = EARTH_RADIUS + request.target_apogee_altitude_km * 1000.0;
+
+    let legs = match request.method {
+        TransferMethod::BiElliptic => {
+            let rb = EARTH_RADIUS + request.bi_elliptic_apogee_altitude_km * 1000.0;
+            vec![
+                TransferLeg {
+                    burn_radius_m: rp0,
+                    target_opposite_radius_m: rb,
+                },
+                TransferLeg {
+                    burn_radius_m: rb,
+                    target_opposite_radius_m: rp1,
+                },
+                TransferLeg {
+                    burn_radius_m: rp1,
+                    target_opposite_radius_m: ra1,
+                },
+            ]
+        }
+        TransferMethod::Hohmann | TransferMethod::LowThrustMultiBurn => {
+            // Either set the apogee first (burning at perigee) or the perigee first (burning at
+            // apogee), whichever is cheaper.
+            let apogee_first = vec![
+                TransferLeg {
+                    burn_radius_m: rp0,
+                    target_opposite_radius_m: ra1,
+                },
+                TransferLeg {
+                    burn_radius_m: ra1,
+                    target_opposite_radius_m: rp1,
+                },
+            ];
+            let perigee_first = vec![
+                TransferLeg {
+                    burn_radius_m: ra0,
+                    target_opposite_radius_m: rp1,
+                },
+                TransferLeg {
+                    burn_radius_m: rp1,
+                    target_opposite_radius_m: ra1,
+                },
+            ];
+            if impulsive_delta_v_m_per_s((rp0, ra0), &apogee_first)
+                <= impulsive_delta_v_m_per_s((rp0, ra0), &perigee_first)
+            {
+                apogee_first
+            } else {
+                perigee_first
+            }
+        }
+    };
+    let impulsive_delta_v_m_per_s = impulsive_delta_v_m_per_s((rp0, ra0), &legs);
+
+    let max_burn_seconds = match request.method {
+        TransferMethod::LowThrustMultiBurn => request.max_burn_minutes * 60.0,
+        TransferMethod::Hohmann | TransferMethod::BiElliptic => f64::INFINITY,
+    };
+
+    let mut burns: Vec<PlannedBurn> = Vec::new();
+    let mut earliest = start;
+    for (leg_index, leg) in legs.iter().enumerate() {
+        loop {
+            let (rp, ra) = mean_apsis_radii_m(&tle);
+            let (r, r_other, mean_anomaly_deg) =
+                if (rp - leg.burn_radius_m).abs() <= (ra - leg.burn_radius_m).abs() {
+                    (rp, ra, 0.0)
+                } else {
+                    (ra, rp, 180.0)
+                };
+            let delta_v_m_per_s = apsis_speed_m_per_s(r, leg.target_opposite_radius_m)
+                - apsis_speed_m_per_s(r, r_other);
+            if delta_v_m_per_s.abs() < DELTA_V_TOLERANCE_M_PER_S {
+                break;
+            }
+            if burns.len() >= MAX_LOW_THRUST_BURNS {
+                return Err(anyhow::anyhow!(
+                    "Transfer did not converge within {MAX_LOW_THRUST_BURNS} burns"
+                ));
+            }
+
+            let propellant_kg = mass_kg
+                * (1.0 - (-delta_v_m_per_s.abs() / thruster.exhaust_velocity_m_per_s()).exp());
+            let duration_seconds =
+                (propellant_kg / thruster.mass_flow_kg_per_s()).min(max_burn_seconds);
+            let apsis_time = next_passage(
+                &tle,
+                mean_anomaly_deg,
+                &(earliest + satkit::Duration::from_seconds(duration_seconds / 2.0)),
+            );
+            let burn_start = apsis_time - satkit::Duration::from_seconds(duration_seconds / 2.0);
+            let burn = ScheduledBurn::new(
+                format!("Leg {} burn {}", leg_index + 1, burns.len() + 1),
+                (burn_start - epoch).as_days(),
+                duration_seconds / 60.0,
+                if delta_v_m_per_s > 0.0 {
+                    ThrustDirection::AlongVelocity
+                } else {
+                    ThrustDirection::AntiVelocity
+                },
+            )
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+            let burn_end = burn_start + satkit::Duration::from_seconds(duration_seconds);
+            let (new_tle, segment) =
+                fire_thruster(thruster, &burn, &tle, &mut mass_kg, &burn_start, &burn_end)?;
+            if segment.propellant_depleted {
+                return Err(anyhow::anyhow!(
+                    "Propellant exhausted during \"{}\" after {:.3} m/s in total",
+                    burn.name,
+                    burns.iter().map(|b| b.delta_v_m_per_s).sum::<f64>() + segment.delta_v_m_per_s
+                ));
+            }
+            tle = new_tle;
+            earliest = burn_end;
+
+            let (rp, ra) = mean_apsis_radii_m(&tle);
+            burns.push(PlannedBurn {
+                burn,
+                delta_v_m_per_s: segment.delta_v_m_per_s,
+                propellant_kg: segment.propellant_used_kg,
+                perigee_altitude_km: (rp - EARTH_RADIUS) / 1000.0,
+                apogee_altitude_km: (ra - EARTH_RADIUS) / 1000.0,
+            });
+
+            if request.method != TransferMethod::LowThrustMultiBurn {
+                break;
+            }
+        }
+    }
+
+    let (rp, ra) = mean_apsis_radii_m(&tle);
+    Ok(ManeuverPlan {
+        request: request.clone(),
+        total_delta_v_m_per_s: burns.iter().map(|b| b.delta_v_m_per_s).sum(),
+        total_propellant_kg: burns.iter().map(|b| b.propellant_kg).sum(),
+        burns,
+        impulsive_delta_v_m_per_s,
+        achieved_perigee_altitude_km: (rp - EARTH_RADIUS) / 1000.0,
+        achieved_apogee_altitude_km: (ra - EARTH_RADIUS) / 1000.0,
+    })
+}
@@ -0,0 +1,400 @@
+use std::sync::atomic::AtomicUsize;
+
+use satkit::Instant;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::batch::run_batch;
+use crate::initial_state_model::InitialSimulationState;
+use crate::satellite_state::SimulationRun;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum DispersedParameter {
+    /// Relative error of B* (of every element set, which all share the satellite's properties).
+    #[default]
+    Bstar,
+    /// Relative error of C_d. B* is scaled with it, as the TLE's B* goes with the nominal C_d·A.
+    DragCoefficient,
+    /// Relative error of the drag area (B* is scaled with it too). Not available with a panel
+    /// model, whose drag area comes from the panels.
+    DragAreaM2,
+    /// Relative error of the atmospheric density from space weather. SGP4's drag goes with
+    /// B*·ρ, so it is applied to B* of every satellite.
+    AtmosphericDensity,
+    /// Relative execution error of every burn's delta-v (applied to its duration).
+    BurnMagnitude,
+    /// Execution error of every burn's start time, in minutes.
+    BurnStartMinutes,
+}
+
+impl DispersedParameter {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DispersedParameter::Bstar => "B* (fraction)",
+            DispersedParameter::DragCoefficient => "Drag coefficient (fraction)",
+            DispersedParameter::DragAreaM2 => "Drag area (fraction)",
+            DispersedParameter::AtmosphericDensity => "Atmospheric density (fraction)",
+            DispersedParameter::BurnMagnitude => "Burn magnitude (fraction)",
+            DispersedParameter::BurnStartMinutes => "Burn start (minutes)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum DistributionKind {
+    /// Zero-mean normal with a standard deviation of `Dispersion::spread`.
+    #[default]
+    Normal,
+    /// Uniform within ±`Dispersion::spread`.
+    Uniform,
+}
+
+impl DistributionKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DistributionKind::Normal => "Normal (spread = 1σ)",
+            DistributionKind::Uniform => "Uniform (spread = half-width)",
+        }
+    }
+}
+
+/// Uncertainty of one input. Each sample draws one error per dispersion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dispersion {
+    pub parameter: DispersedParameter,
+    pub distribution: DistributionKind,
+    pub spread: f64,
+}
+
+impl Dispersion {
+    pub fn new(
+        parameter: DispersedParameter,
+        distribution: DistributionKind,
+        spread: f64,
+    ) -> Result<Self, String> {
+        if spread < 0.0 {
+            return Err("Dispersion spread must be >= 0".into());
+        }
+        Ok(Self {
+            parameter,
+            distribution,
+            spread,
+        })
+    }
+
+    fn sample(&self, rng: &mut fastrand::Rng) -> f64 {
+        match self.distribution {
+            DistributionKind::Normal => self.spread * standard_normal(rng),
+            DistributionKind::Uniform => self.spread * (2.0 * rng.f64() - 1.0),
+        }
+    }
+}
+
+/// Standard normal draw (Box-Muller).
+fn standard_normal(rng: &mut fastrand::Rng) -> f64 {
+    let u1 = 1.0 - rng.f64();
+    let u2 = rng.f64();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonteCarloSettings {
+    pub samples: usize,
+    /// Sample `i` is drawn from an RNG seeded with `seed + i`, so results do not depend on the
+    /// number of threads.
+    pub seed: u64,
+    pub threads: usize,
+    pub dispersions: Vec<Dispersion>,
+}
+
+impl MonteCarloSettings {
+    pub fn new(
+        samples: usize,
+        seed: u64,
+        threads: usize,
+        dispersions: Vec<Dispersion>,
+    ) -> Result<Self, String> {
+        if samples == 0 {
+            return Err("Monte Carlo needs at least one sample".into());
+        }
+        if threads == 0 {
+            return Err("Monte Carlo needs at least one thread".into());
+        }
+        Ok(Self {
+            samples,
+            seed,
+            threads,
+            dispersions,
+        })
+    }
+}
+
+/// Copy of `nominal` with one draw of every dispersion applied.
+fn perturbed_initial_state(
+    nominal: &InitialSimulationState,
+    dispersions: &[Dispersion],
+    rng: &mut fastrand::Rng,
+) -> InitialSimulationState {
+    let mut initial = nominal.clone();
+    for dispersion in dispersions {
+        let error = dispersion.sample(rng);
+        let factor = (1.0 + error).max(0.0);
+        match dispersion.parameter {
+            DispersedParameter::Bstar => initial.scale_bstar(factor),
+            DispersedParameter::DragCoefficient => {
+                initial.satellite.drag_coefficient *= factor;
+                initial.scale_bstar(factor);
+            }
+            DispersedParameter::DragAreaM2 => {
+                initial.satellite.drag_area_m2 *= factor;
+                initial.scale_bstar(factor);
+            }
+            DispersedParameter::AtmosphericDensity => initial.scale_bstar(factor),
+            DispersedParameter::BurnMagnitude => {
+                for burn in &mut initial.satellite.burn_schedule {
+                    burn.duration_minutes *= factor;
+                }
+            }
+            DispersedParameter::BurnStartMinutes => {
+                for burn in &mut initial.satellite.burn_schedule {
+                    burn.start_days_since_epoch =
+                        (burn.start_days_since_epoch + error / 1440.0).max(0.0);
+                }
+            }
+        }
+    }
+    initial
+}
+
+/// What is kept of one run: enough to aggregate (the run is not interactive, so it keeps no
+/// telemetry history of its own).
+#[derive(Debug, Clone)]
+struct SampleTrace {
+    elevation_km: Vec<f64>,
+    reentry_time: Option<Instant>,
+    /// Acquisition-of-signal times per ground station, interpolated between steps.
+    aos_times: Vec<Vec<Instant>>,
+}
+
+/// Run to the end of `max_days` or to reentry.
+fn run_sample(initial: InitialSimulationState) -> anyhow::Result<SampleTrace> {
+    let max_hours = initial.simulation_settings.max_days * 24.0;
+    let min_elevations_deg = initial
+        .ground_stations
+        .iter()
+        .map(|station| station.min_elevation_deg)
+        .collect::<Vec<_>>();
+    let mut run = SimulationRun::new(initial, false)?;
+
+    let mut trace = SampleTrace {
+        elevation_km: Vec::new(),
+        reentry_time: None,
+        aos_times: vec![Vec::new(); min_elevations_deg.len()],
+    };
+    let mut previous: Option<(Instant, Vec<f64>)> = None;
+    while run.hours_since_epoch() < max_hours {
+        let telemetry = run.step()?;
+        trace.elevation_km.push(telemetry.elevation_km);
+
+        if let Some((previous_time, previous_angles_deg)) = &previous {
+            for (station, min_elevation_deg) in min_elevations_deg.iter().enumerate() {
+                let (before, after) = (
+                    previous_angles_deg[station] - min_elevation_deg,
+                    telemetry.elevation_angles_degrees[station] - min_elevation_deg,
+                );
+                if before <= 0.0 && after > 0.0 {
+                    let fraction = -before / (after - before);
+                    let step_seconds = (telemetry.time - *previous_time).as_seconds();
+                    trace.aos_times[station].push(
+                        *previous_time + satkit::Duration::from_seconds(fraction * step_seconds),
+                    );
+                }
+            }
+        }
+        previous = Some((telemetry.time, telemetry.elevation_angles_degrees.clone()));
+
+        if telemetry.is_deorbited {
+            trace.reentry_time = Some(telemetry.time);
+            break;
+        }
+    }
+    Ok(trace)
+}
+
+/// Linear-interpolated percentile (`p` in [0, 100]) of sorted values.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f64)
+}
+
+#[derive(Debug, Clone)]
+pub struct SampleOutcome {
+    pub reentry_days_since_epoch: Option<f64>,
+    /// Why the run failed, if it did; failed samples are left out of the statistics.
+    pub error: Option<String>,
+}
+
+/// 5th / 50th / 95th percentiles.
+#[derive(Debug, Clone, Copy)]
+pub struct PercentileBand {
+    pub p05: f64,
+    pub p50: f64,
+    pub p95: f64,
+}
+
+impl PercentileBand {
+    fn from_values(mut values: Vec<f64>) -> Self {
+        values.sort_by(f64::total_cmp);
+        Self {
+            p05: percentile(&values, 5.0),
+            p50: percentile(&values, 50.0),
+            p95: percentile(&values, 95.0),
+        }
+    }
+}
+
+/// Spread of the acquisition time of one nominal pass over the samples.
+#[derive(Debug, Clone)]
+pub struct PassTimingSpread {
+    pub station: String,
+    pub nominal_aos: Instant,
+    /// Samples with an acquisition within half an orbit of the nominal one.
+    pub samples: usize,
+    pub std_dev_seconds: f64,
+    pub max_abs_offset_seconds: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct MonteCarloResult {
+    pub samples: Vec<SampleOutcome>,
+    pub reentered_samples: usize,
+    /// Reentry time of the samples that reentered, in days since the TLE epoch.
+    pub reentry_days: Option<PercentileBand>,
+    /// Altitude at every step (samples that have reentered count as 0 km).
+    pub altitude_bands_km: Vec<(f64, PercentileBand)>,
+    pub pass_timing: Vec<PassTimingSpread>,
+}
+
+/// Run `settings.samples` dispersed copies of `nominal` on `settings.threads` threads, plus the
+/// nominal run itself for reference. `completed` counts finished samples, for progress.
+pub fn run_monte_carlo(
+    nominal: &InitialSimulationState,
+    settings: &MonteCarloSettings,
+    completed: &AtomicUsize,
+) -> anyhow::Result<MonteCarloResult> {
+    if !nominal.satellite.panels.is_empty()
+        && settings
+            .dispersions
+            .iter()
+            .any(|d| d.parameter == DispersedParameter::DragAreaM2)
+    {
+        return Err(anyhow::anyhow!(
+            "The drag area cannot be dispersed with a panel model, which sets the drag area \
+             from the panels"
+        ));
+    }
+    let nominal_trace = run_sample(nominal.clone())?;
+
+    let results = run_batch(
+        nominal,
+        settings.samples,
+        settings.threads,
+        completed,
+        |nominal, index| {
+            let mut rng = fastrand::Rng::with_seed(settings.seed.wrapping_add(index as u64));
+            let initial = perturbed_initial_state(nominal, &settings.dispersions, &mut rng);
+            run_sample(initial).map_err(|e| e.to_string())
+        },
+    )?;
+
+    let mut samples = Vec::with_capacity(results.len());
+    let mut traces = Vec::with_capacity(results.len());
+    for result in results {
+        match result {
+            Ok(trace) => {
+                samples.push(SampleOutcome {
+                    reentry_days_since_epoch: trace
+                        .reentry_time
+                        .map(|time| (time - nominal.tle.epoch).as_days()),
+                    error: None,
+                });
+                traces.push(trace);
+            }
+            Err(e) => samples.push(SampleOutcome {
+                reentry_days_since_epoch: None,
+                error: Some(e),
+            }),
+        }
+    }
+
+    let reentry_days = samples
+        .iter()
+        .filter_map(|sample| sample.reentry_days_since_epoch)
+        .collect::<Vec<_>>();
+    let reentered_samples = reentry_days.len();
+
+    let step_days = nominal.simulation_settings.step_interval_hours / 24.0;
+    let step_count = traces
+        .iter()
+        .map(|trace| trace.elevation_km.len())
+        .max()
+        .unwrap_or(0);
+    let altitude_bands_km = (0..step_count)
+        .map(|step| {
+            let values = traces
+                .iter()
+                .map(|trace| trace.elevation_km.get(step).copied().unwrap_or(0.0))
+                .collect();
+            (step as f64 * step_days, PercentileBand::from_values(values))
+        })
+        .collect();
+
+    let half_period_seconds = 43_200.0 / nominal.tle.mean_motion;
+    let mut pass_timing = Vec::new();
+    for (station_index, station) in nominal.ground_stations.iter().enumerate() {
+        for nominal_aos in &nominal_trace.aos_times[station_index] {
+            let offsets_seconds = traces
+                .iter()
+                .filter_map(|trace| {
+                    trace.aos_times[station_index]
+                        .iter()
+                        .map(|aos| (*aos - *nominal_aos).as_seconds())
+                        .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+                        .filter(|offset| offset.abs() < half_period_seconds)
+                })
+                .collect::<Vec<_>>();
+            if offsets_seconds.is_empty() {
+                continue;
+            }
+            let count = offsets_seconds.len() as f64;
+            let mean = offsets_seconds.iter().sum::<f64>() / count;
+            let variance = offsets_seconds
+                .iter()
+                .map(|offset| (offset - mean).powi(2))
+                .sum::<f64>()
+                / count;
+            pass_timing.push(PassTimingSpread {
+                station: station.name.clone(),
+                nominal_aos: *nominal_aos,
+                samples: offsets_seconds.len(),
+                std_dev_seconds: variance.sqrt(),
+                max_abs_offset_seconds: offsets_seconds
+                    .iter()
+                    .map(|offset| offset.abs())
+                    .fold(0.0, f64::max),
+            });
+        }
+    }
+
+    Ok(MonteCarloResult {
+        samples,
+        reentered_samples,
+        reentry_days: (!reentry_days.is_empty()).then(|| PercentileBand::from_values(reentry_days)),
+        altitude_bands_km,
+        pass_timing,
+    })
+}
@@ -0,0 +1,663 @@
+use nalgebra::{DMatrix, DVector};
+use satkit::Instant;
+use satkit::frametransform::qteme2gcrf;
+use satkit::types::Vec3;
+
+use crate::earth_orientation::qteme2itrf_with_mode;
+use crate::initial_state_model::{GroundStation, TleData};
+use crate::orbital_elements::{fit_tle_to_state, rtn_basis, tle_from_mean_element_params};
+use crate::output_frames::{itrf_to_teme_state, teme_to_itrf_state};
+use crate::satellite_state::{propagate_teme, pythag_3};
+
+/// Names of the solve-for parameters, in order (see `tle_from_mean_element_params`).
+pub const PARAMETER_LABELS: [&str; 7] = [
+    "Mean motion (rev/day)",
+    "e·cos ω",
+    "e·sin ω",
+    "Inclination (deg)",
+    "RAAN (deg)",
+    "ω + M (deg)",
+    "B* (1/earth radii)",
+];
+
+/// Finite-difference step for the Jacobian, per parameter, relative to the parameter's
+/// magnitude or, for values near zero, to `PARAMETER_STEP_FLOORS`.
+const PARAMETER_RELATIVE_STEPS: [f64; 7] = [1e-8, 1e-4, 1e-4, 1e-8, 1e-8, 1e-8, 1e-2];
+const PARAMETER_STEP_FLOORS: [f64; 7] = [10.0, 1e-3, 1e-3, 100.0, 100.0, 100.0, 1e-5];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateFrame {
+    Itrf,
+    Gcrf,
+    Teme,
+}
+
+#[derive(Debug, Clone)]
+pub enum Measurement {
+    State {
+        frame: StateFrame,
+        position_m: [f64; 3],
+        velocity_m_per_s: [f64; 3],
+    },
+    Track {
+        station: String,
+        range_m: Option<f64>,
+        azimuth_deg: Option<f64>,
+        elevation_deg: Option<f64>,
+        range_rate_m_per_s: Option<f64>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Observation {
+    pub time: Instant,
+    pub measurement: Measurement,
+}
+
+fn parse_f64(field: &str, name: &str) -> Result<f64, String> {
+    field
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{name}' must be a number, got '{}'", field.trim()))
+}
+
+fn parse_optional(field: Option<&str>, name: &str) -> Result<Option<f64>, String> {
+    match field.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(field) => parse_f64(field, name).map(Some),
+    }
+}
+
+/// Parse an observation file: CSV, one observation per line (blank lines and lines starting
+/// with `#` are skipped), with UTC times in RFC 3339:
+///
+/// ```text
+/// state,<time>,<ITRF|GCRF|TEME>,x_m,y_m,z_m,vx_m_per_s,vy_m_per_s,vz_m_per_s
+/// track,<time>,<station name>,range_m,azimuth_deg,elevation_deg,range_rate_m_per_s
+/// ```
+///
+/// `state` lines are GPS fixes. Any of the `track` measurements may be left blank.
+pub fn parse_observations(text: &str) -> Result<Vec<Observation>, String> {
+    let mut observations = Vec::new();
+    for (line_number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let context = |e: String| format!("Line {}: {e}", line_number + 1);
+        let fields = line.split(',').collect::<Vec<_>>();
+        if fields.len() < 3 {
+            return Err(context(
+                "expected at least a kind, a time and a frame/station".into(),
+            ));
+        }
+        let time = Instant::from_rfc3339(fields[1].trim())
+            .map_err(|e| context(format!("invalid time '{}': {e}", fields[1].trim())))?;
+
+        let measurement = match fields[0].trim() {
+            "state" => {
+                if fields.len() != 9 {
+                    return Err(context("a state needs a frame and six components".into()));
+                }
+                let frame = match fields[2].trim().to_ascii_uppercase().as_str() {
+                    "ITRF" => StateFrame::Itrf,
+                    "GCRF" => StateFrame::Gcrf,
+                    "TEME" => StateFrame::Teme,
+                    other => return Err(context(format!("unknown frame '{other}'"))),
+                };
+                let component = |i: usize| parse_f64(fields[3 + i], "state component");
+                Measurement::State {
+                    frame,
+                    position_m: [
+                        component(0).map_err(context)?,
+                        component(1).map_err(context)?,
+                        component(2).map_err(context)?,
+                    ],
+                    velocity_m_per_s: [
+                        component(3).map_err(context)?,
+                        component(4).map_err(context)?,
+                        component(5).map_err(context)?,
+                    ],
+                }
+            }
+            "track" => {
+                let field = |i: usize| fields.get(i).copied();
+                let measurement = Measurement::Track {
+                    station: fields[2].trim().to_string(),
+                    range_m: parse_optional(field(3), "range").map_err(context)?,
+                    azimuth_deg: parse_optional(field(4), "azimuth").map_err(context)?,
+                    elevation_deg: parse_optional(field(5), "elevation").map_err(context)?,
+                    range_rate_m_per_s: parse_optional(field(6), "range rate").map_err(context)?,
+                };
+                if let Measurement::Track {
+                    range_m: None,
+                    azimuth_deg: None,
+                    elevation_deg: None,
+                    range_rate_m_per_s: None,
+                    ..
+                } = measurement
+                {
+                    return Err(context("a track needs at least one measurement".into()));
+                }
+                measurement
+            }
+            other => return Err(context(format!("unknown observation kind '{other}'"))),
+        };
+        observations.push(Observation { time, measurement });
+    }
+    Ok(observations)
+}
+
+/// Measurement noise (1-sigma), which weights the residuals.
+#[derive(Debug, Clone)]
+pub struct OrbitDeterminationSettings {
+    pub position_sigma_m: f64,
+    pub velocity_sigma_m_per_s: f64,
+    pub range_sigma_m: f64,
+    pub angle_sigma_deg: f64,
+    pub range_rate_sigma_m_per_s: f64,
+    pub solve_for_bstar: bool,
+    pub max_iterations: usize,
+}
+
+impl OrbitDeterminationSettings {
+    pub fn new(
+        position_sigma_m: f64,
+        velocity_sigma_m_per_s: f64,
+        range_sigma_m: f64,
+        angle_sigma_deg: f64,
+        range_rate_sigma_m_per_s: f64,
+        solve_for_bstar: bool,
+        max_iterations: usize,
+    ) -> Result<Self, String> {
+        if [
+            position_sigma_m,
+            velocity_sigma_m_per_s,
+            range_sigma_m,
+            angle_sigma_deg,
+            range_rate_sigma_m_per_s,
+        ]
+        .iter()
+        .any(|sigma| *sigma <= 0.0)
+        {
+            return Err("Measurement sigmas must be > 0".into());
+        }
+        if max_iterations == 0 {
+            return Err("Orbit determination needs at least one iteration".into());
+        }
+        Ok(Self {
+            position_sigma_m,
+            velocity_sigma_m_per_s,
+            range_sigma_m,
+            angle_sigma_deg,
+            range_rate_sigma_m_per_s,
+            solve_for_bstar,
+            max_iterations,
+        })
+    }
+}
+
+/// Post-fit residual (observed minus computed) of one observation.
+#[derive(Debug, Clone)]
+pub enum Residual {
+    State {
+        /// Position residual along the radial/transverse/normal axes of the fitted orbit.
+        position_rtn_m: [f64; 3],
+        velocity_m_per_s: f64,
+    },
+    Track {
+        station: String,
+        range_m: Option<f64>,
+        azimuth_deg: Option<f64>,
+        elevation_deg: Option<f64>,
+        range_rate_m_per_s: Option<f64>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct IterationDiagnostics {
+    /// Root mean square of the residuals divided by their sigmas, after the iteration.
+    pub weighted_rms: f64,
+    /// Levenberg-Marquardt damping used for the accepted step.
+    pub damping: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct OrbitDeterminationResult {
+    pub tle: TleData,
+    pub converged: bool,
+    /// No damping could lower the cost, so the fit stopped without converging.
+    pub stalled: bool,
+    pub iterations: Vec<IterationDiagnostics>,
+    /// Formal 1-sigma of each solved-for parameter, with its label.
+    pub parameter_sigmas: Vec<(&'static str, f64)>,
+    pub residuals: Vec<(Instant, Residual)>,
+}
+
+/// Range, azimuth, elevation and range rate of an ITRF state seen from `station`.
+fn topocentric(
+    station: &GroundStation,
+    position_itrf: &[f64; 3],
+    velocity_itrf: &[f64; 3],
+) -> [f64; 4] {
+    let station_m = station.ecef_xyz_m();
+    let relative = Vec3::from_row_slice(position_itrf) - Vec3::from_row_slice(&station_m);
+    let (lat, lon) = (
+        station.latitude_deg.to_radians(),
+        station.longitude_deg.to_radians(),
+    );
+    let east = Vec3::new(-lon.sin(), lon.cos(), 0.0);
+    let north = Vec3::new(-lat.sin() * lon.cos(), -lat.sin() * lon.sin(), lat.cos());
+    let up = Vec3::new(lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin());
+
+    let range_m = relative.norm();
+    let azimuth_deg = relative
+        .dot(&east)
+        .atan2(relative.dot(&north))
+        .to_degrees()
+        .rem_euclid(360.0);
+    let elevation_deg = (relative.dot(&up) / range_m).asin().to_degrees();
+    let range_rate_m_per_s = relative.dot(&Vec3::from_row_slice(velocity_itrf)) / range_m;
+    [range_m, azimuth_deg, elevation_deg, range_rate_m_per_s]
+}
+
+/// A state observation rotated into TEME.
+fn state_to_teme(
+    time: &Instant,
+    frame: StateFrame,
+    position_m: &[f64; 3],
+    velocity_m_per_s: &[f64; 3],
+) -> ([f64; 3], [f64; 3]) {
+    match frame {
+        StateFrame::Teme => (*position_m, *velocity_m_per_s),
+        StateFrame::Itrf => {
+            let teme =
+                itrf_to_teme_state(&qteme2itrf_with_mode(time).0, position_m, velocity_m_per_s);
+            (teme.position, teme.velocity)
+        }
+        StateFrame::Gcrf => {
+            let gcrf_to_teme = qteme2gcrf(time).conjugate();
+            let rotate = |v: &[f64; 3]| {
+                let v = gcrf_to_teme * Vec3::from_row_slice(v);
+                [v[0], v[1], v[2]]
+            };
+            (rotate(position_m), rotate(velocity_m_per_s))
+        }
+    }
+}
+
+/// `observation` with a state fix rotated into TEME, which is all `evaluate` then needs.
+fn observation_in_teme(observation: &Observation) -> Observation {
+    let measurement = match &observation.measurement {
+        Measurement::State {
+            frame,
+            position_m,
+            velocity_m_per_s,
+        } => {
+            let (position_m, velocity_m_per_s) =
+                state_to_teme(&observation.time, *frame, position_m, velocity_m_per_s);
+            Measurement::State {
+                frame: StateFrame::Teme,
+                position_m,
+                velocity_m_per_s,
+            }
+        }
+        track @ Measurement::Track { .. } => track.clone(),
+    };
+    Observation {
+        time: observation.time,
+        measurement,
+    }
+}
+
+/// Predicted measurement, observed minus computed, and weighted by the sigmas.
+struct Evaluation {
+    weighted: Vec<f64>,
+    residual: Residual,
+}
+
+fn evaluate(
+    observation: &Observation,
+    satkit_tle: &mut satkit::TLE,
+    stations: &[GroundStation],
+    settings: &OrbitDeterminationSettings,
+) -> anyhow::Result<Evaluation> {
+    let time = &observation.time;
+    let (position_teme, velocity_teme) = propagate_teme(satkit_tle, time)?;
+    match &observation.measurement {
+        Measurement::State {
+            frame,
+            position_m,
+            velocity_m_per_s,
+        } => {
+            let (observed_position, observed_velocity) =
+                state_to_teme(time, *frame, position_m, velocity_m_per_s);
+            let position_residual =
+                [0, 1, 2].map(|axis| observed_position[axis] - position_teme[axis]);
+            let velocity_residual =
+                [0, 1, 2].map(|axis| observed_velocity[axis] - velocity_teme[axis]);
+            let basis = rtn_basis(&position_teme, &velocity_teme);
+            let position_rtn_m = basis.map(|unit| {
+                (0..3)
+                    .map(|axis| unit[axis] * position_residual[axis])
+                    .sum::<f64>()
+            });
+            Ok(Evaluation {
+                weighted: position_residual
+                    .iter()
+                    .map(|r| r / settings.position_sigma_m)
+                    .chain(
+                        velocity_residual
+                            .iter()
+                            .map(|r| r / settings.velocity_sigma_m_per_s),
+                    )
+                    .collect(),
+                residual: Residual::State {
+                    position_rtn_m,
+                    velocity_m_per_s: pythag_3(&velocity_residual),
+                },
+            })
+        }
+        Measurement::Track {
+            station,
+            range_m,
+            azimuth_deg,
+            elevation_deg,
+            range_rate_m_per_s,
+        } => {
+            let ground_station = stations
+                .iter()
+                .find(|s| s.name == *station)
+                .ok_or_else(|| anyhow::anyhow!("Unknown ground station '{station}'"))?;
+            let itrf = teme_to_itrf_state(
+                &qteme2itrf_with_mode(time).0,
+                &position_teme,
+                &velocity_teme,
+            );
+            let [range, azimuth, elevation, range_rate] =
+                topocentric(ground_station, &itrf.position, &itrf.velocity);
+            let range_residual = range_m.map(|observed| observed - range);
+            let azimuth_residual =
+                azimuth_deg.map(|observed| (observed - azimuth + 540.0).rem_euclid(360.0) - 180.0);
+            let elevation_residual = elevation_deg.map(|observed| observed - elevation);
+            let range_rate_residual = range_rate_m_per_s.map(|observed| observed - range_rate);
+            Ok(Evaluation {
+                weighted: [
+                    range_residual.map(|r| r / settings.range_sigma_m),
+                    // Azimuth errors shrink to cross-range errors towards the zenith.
+                    azimuth_residual
+                        .map(|r| r * elevation.to_radians().cos() / settings.angle_sigma_deg),
+                    elevation_residual.map(|r| r / settings.angle_sigma_deg),
+                    range_rate_residual.map(|r| r / settings.range_rate_sigma_m_per_s),
+                ]
+                .into_iter()
+                .flatten()
+                .collect(),
+                residual: Residual::Track {
+                    station: station.clone(),
+                    range_m: range_residual,
+                    azimuth_deg: azimuth_residual,
+                    elevation_deg: elevation_residual,
+                    range_rate_m_per_s: range_rate_residual,
+                },
+            })
+        }
+    }
+}
+
+/// Element set for `params` (six mean-element parameters, then B*).
+fn tle_from_params(template: &TleData, params: &[f64; 7]) -> TleData {
+    let mut tle = tle_from_mean_element_params(
+        template,
+        &[
+            params[0], params[1], params[2], params[3], params[4], params[5],
+        ],
+    );
+    tle.bstar = params[6];
+    tle
+}
+
+fn weighted_residuals(
+    observations: &[Observation],
+    stations: &[GroundStation],
+    settings: &OrbitDeterminationSettings,
+    tle: &TleData,
+) -> anyhow::Result<Vec<Evaluation>> {
+    let mut satkit_tle = tle.to_satkit_tle();
+    observations
+        .iter()
+        .map(|observation| evaluate(observation, &mut satkit_tle, stations, settings))
+        .collect()
+}
+
+fn cost(evaluations: &[Evaluation]) -> f64 {
+    evaluations
+        .iter()
+        .flat_map(|e| &e.weighted)
+        .map(|r| r * r)
+        .sum()
+}
+
+/// Element set to start from: `template` or, without one, the state observation closest to
+/// the epoch. Either way it is re-fitted to have its epoch at `epoch`.
+fn initial_guess(
+    observations: &[Observation],
+    template: Option<&TleData>,
+    epoch: Instant,
+) -> anyhow::Result<TleData> {
+    let template = match template {
+        Some(template) => template.clone(),
+        None => {
+            let (time, position_teme, velocity_teme) = observations
+                .iter()
+                .filter_map(|observation| match &observation.measurement {
+                    Measurement::State {
+                        frame,
+                        position_m,
+                        velocity_m_per_s,
+                    } => {
+                        let (position, velocity) =
+                            state_to_teme(&observation.time, *frame, position_m, velocity_m_per_s);
+                        Some((observation.time, position, velocity))
+                    }
+                    Measurement::Track { .. } => None,
+                })
+                .min_by(|a, b| {
+                    (epoch - a.0)
+                        .as_seconds()
+                        .abs()
+                        .total_cmp(&(epoch - b.0).as_seconds().abs())
+                })
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Tracking observations alone need a TLE as a first guess; load one or add state fixes"
+                    )
+                })?;
+            let blank = TleData {
+                name: "FITTED".into(),
+                intl_desig: String::new(),
+                sat_num: 0,
+                desig_year: 0,
+                desig_launch: 0,
+                desig_piece: String::new(),
+                epoch: time,
+                mean_motion_dot: 0.0,
+                mean_motion_dot_dot: 0.0,
+                bstar: 0.0,
+                ephem_type: 0,
+                element_num: 0,
+                inclination: 0.0,
+                raan: 0.0,
+                eccen: 0.0,
+                arg_of_perigee: 0.0,
+                mean_anomaly: 0.0,
+                mean_motion: 0.0,
+                rev_num: 0,
+            };
+            fit_tle_to_state(&blank, time, &position_teme, &velocity_teme)?
+        }
+    };
+    let (position_teme, velocity_teme) = propagate_teme(&mut template.to_satkit_tle(), &epoch)?;
+    fit_tle_to_state(&template, epoch, &position_teme, &velocity_teme)
+}
+
+/// Fit an element set, with its epoch at the last observation, to `observations`.
+///
+/// Weighted least squares, solved by Levenberg-Marquardt with a finite-difference Jacobian.
+/// `template` (e.g. the current TLE) is the first guess and supplies the catalog fields; without
+/// one, the first guess comes from the state fixes. Without `solve_for_bstar`, B* is kept from
+/// the template (zero without one).
+pub fn fit_tle_to_observations(
+    observations: &[Observation],
+    stations: &[GroundStation],
+    template: Option<&TleData>,
+    settings: &OrbitDeterminationSettings,
+) -> anyhow::Result<OrbitDeterminationResult> {
+    const MAX_DAMPING: f64 = 1e10;
+    const CONVERGENCE_RELATIVE_COST: f64 = 1e-8;
+
+    // Every iteration evaluates all observations several times, so rotate the state fixes once.
+    let observations = observations
+        .iter()
+        .map(observation_in_teme)
+        .collect::<Vec<_>>();
+    let observations = observations.as_slice();
+    let epoch = observations
+        .iter()
+        .map(|observation| observation.time)
+        .reduce(|latest, time| if time > latest { time } else { latest })
+        .ok_or_else(|| anyhow::anyhow!("No observations to fit"))?;
+    let mut tle = initial_guess(observations, template, epoch)?;
+    let mut params = [
+        tle.mean_motion,
+        tle.eccen * tle.arg_of_perigee.to_radians().cos(),
+        tle.eccen * tle.arg_of_perigee.to_radians().sin(),
+        tle.inclination,
+        tle.raan,
+        tle.arg_of_perigee + tle.mean_anomaly,
+        tle.bstar,
+    ];
+    let solved = if settings.solve_for_bstar { 7 } else { 6 };
+
+    let mut evaluations = weighted_residuals(observations, stations, settings, &tle)?;
+    let rows = evaluations.iter().map(|e| e.weighted.len()).sum::<usize>();
+    if rows <= solved {
+        return Err(anyhow::anyhow!(
+            "{rows} measurements cannot determine {solved} parameters"
+        ));
+    }
+    let mut current_cost = cost(&evaluations);
+    let mut damping = 1e-3;
+    let mut iterations = Vec::new();
+    let mut converged = false;
+    let mut stalled = false;
+
+    let jacobian_at = |tle: &TleData,
+                       params: &[f64; 7],
+                       residual: &DVector<f64>|
+     -> anyhow::Result<DMatrix<f64>> {
+        let mut jacobian = DMatrix::<f64>::zeros(rows, solved);
+        for column in 0..solved {
+            let step = PARAMETER_RELATIVE_STEPS[column]
+                * params[column].abs().max(PARAMETER_STEP_FLOORS[column]);
+            let mut perturbed = *params;
+            perturbed[column] += step;
+            let perturbed_evaluations = weighted_residuals(
+                observations,
+                stations,
+                settings,
+                &tle_from_params(tle, &perturbed),
+            )?;
+            for (row, value) in perturbed_evaluations
+                .iter()
+                .flat_map(|e| &e.weighted)
+                .enumerate()
+            {
+                // Residuals are observed minus computed, so this is -∂(computed)/∂p.
+                jacobian[(row, column)] = (value - residual[row]) / step;
+            }
+        }
+        Ok(jacobian)
+    };
+    let residual_vector = |evaluations: &[Evaluation]| {
+        DVector::from_iterator(rows, evaluations.iter().flat_map(|e| e.weighted.clone()))
+    };
+
+    for _ in 0..settings.max_iterations {
+        let residual = residual_vector(&evaluations);
+        let jacobian = jacobian_at(&tle, &params, &residual)?;
+        let normal = jacobian.transpose() * &jacobian;
+        let gradient = jacobian.transpose() * &residual;
+
+        // Raise the damping until a step lowers the cost.
+        let mut accepted = None;
+        while damping < MAX_DAMPING {
+            let mut damped = normal.clone();
+            for i in 0..solved {
+                damped[(i, i)] += damping * normal[(i, i)].max(f64::EPSILON);
+            }
+            let Some(step) = damped.lu().solve(&(-&gradient)) else {
+                damping *= 10.0;
+                continue;
+            };
+            let mut candidate = params;
+            for (param, delta) in candidate.iter_mut().zip(step.iter()) {
+                *param += delta;
+            }
+            let candidate_tle = tle_from_params(&tle, &candidate);
+            match weighted_residuals(observations, stations, settings, &candidate_tle) {
+                Ok(candidate_evaluations) if cost(&candidate_evaluations) < current_cost => {
+                    accepted = Some((candidate, candidate_tle, candidate_evaluations));
+                    break;
+                }
+                _ => damping *= 10.0,
+            }
+        }
+        let Some((candidate, candidate_tle, candidate_evaluations)) = accepted else {
+            // No step improves the fit, which may still be far from the minimum.
+            stalled = true;
+            break;
+        };
+
+        let new_cost = cost(&candidate_evaluations);
+        let relative_decrease = (current_cost - new_cost) / current_cost.max(f64::MIN_POSITIVE);
+        iterations.push(IterationDiagnostics {
+            weighted_rms: (new_cost / rows as f64).sqrt(),
+            damping,
+        });
+        params = candidate;
+        tle = candidate_tle;
+        evaluations = candidate_evaluations;
+        current_cost = new_cost;
+        damping = (damping / 10.0).max(1e-9);
+        if relative_decrease < CONVERGENCE_RELATIVE_COST {
+            converged = true;
+            break;
+        }
+    }
+
+    // Covariance at the final parameters, not at the start of the last accepted step.
+    let jacobian = jacobian_at(&tle, &params, &residual_vector(&evaluations))?;
+    let covariance = (jacobian.transpose() * &jacobian).try_inverse();
+    let parameter_sigmas = (0..solved)
+        .map(|i| {
+            (
+                PARAMETER_LABELS[i],
+                covariance.as_ref().map_or(f64::NAN, |c| c[(i, i)].sqrt()),
+            )
+        })
+        .collect();
+
+    Ok(OrbitDeterminationResult {
+        tle,
+        converged,
+        stalled,
+        iterations,
+        parameter_sigmas,
+        residuals: observations
+            .iter()
+            .zip(evaluations)
+            .map(|(observation, evaluation)| (observation.time, evaluation.residual))
+            .collect(),
+    })
+}
@@ -0,0 +1,368 @@
+use satkit::consts::{EARTH_RADIUS, MU_EARTH};
+
+use crate::initial_state_model::TleData;
+use crate::satellite_state::{propagate_teme, pythag_3};
+
+fn dot_3(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross_3(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn unit_3(a: &[f64; 3]) -> [f64; 3] {
+    let norm = pythag_3(a);
+    [a[0] / norm, a[1] / norm, a[2] / norm]
+}
+
+/// Wrap an angle in degrees into [0, 360).
+pub fn wrap_degrees_360(angle_deg: f64) -> f64 {
+    angle_deg.rem_euclid(360.0)
+}
+
+/// Wrap an angle in degrees into [-180, 180).
+pub fn wrap_degrees_180(angle_deg: f64) -> f64 {
+    (angle_deg + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Unit vectors of the satellite-relative radial/transverse/normal (RTN) frame,
+/// expressed in the same inertial frame as the input state.
+///
+/// Returns `[radial, transverse, normal]`.
+pub fn rtn_basis(position_m: &[f64; 3], velocity_m_per_s: &[f64; 3]) -> [[f64; 3]; 3] {
+    let radial = unit_3(position_m);
+    let normal = unit_3(&cross_3(position_m, velocity_m_per_s));
+    let transverse = cross_3(&normal, &radial);
+    [radial, transverse, normal]
+}
+
+/// Osculating two-body orbital elements, computed from an inertial state vector.
+///
+/// Angles that are undefined for circular or equatorial orbits fall back to zero, with the
+/// angle absorbed into the argument of latitude / true longitude, so the struct never holds NaN.
+#[derive(Debug, Clone)]
+pub struct KeplerianElements {
+    pub semi_major_axis_m: f64,
+    pub eccentricity: f64,
+    pub inclination_deg: f64,
+    pub raan_deg: f64,
+    pub arg_of_perigee_deg: f64,
+    pub true_anomaly_deg: f64,
+    pub mean_anomaly_deg: f64,
+
+    /// Argument of perigee + true anomaly. Well-defined even for circular orbits.
+    pub arg_of_latitude_deg: f64,
+
+    /// Eccentricity vector components in the node-aligned frame: (e cos ω, e sin ω).
+    pub eccentricity_vector_node: [f64; 2],
+}
+
+impl KeplerianElements {
+    /// Compute osculating elements from an inertial (TEME/GCRF) position (m) and velocity (m/s).
+    pub fn from_state_vector(position_m: &[f64; 3], velocity_m_per_s: &[f64; 3]) -> Self {
+        const SMALL: f64 = 1e-11;
+
+        let r = pythag_3(position_m);
+        let v = pythag_3(velocity_m_per_s);
+        let h_vec = cross_3(position_m, velocity_m_per_s);
+        let h = pythag_3(&h_vec);
+
+        // Node vector (z × h).
+        let node_vec = [-h_vec[1], h_vec[0], 0.0];
+        let node = pythag_3(&node_vec);
+
+        let r_dot_v = dot_3(position_m, velocity_m_per_s);
+        let e_vec = [
+            ((v * v - MU_EARTH / r) * position_m[0] - r_dot_v * velocity_m_per_s[0]) / MU_EARTH,
+            ((v * v - MU_EARTH / r) * position_m[1] - r_dot_v * velocity_m_per_s[1]) / MU_EARTH,
+            ((v * v - MU_EARTH / r) * position_m[2] - r_dot_v * velocity_m_per_s[2]) / MU_EARTH,
+        ];
+        let eccentricity = pythag_3(&e_vec);
+
+        let specific_energy = v * v / 2.0 - MU_EARTH / r;
+        let semi_major_axis_m = -MU_EARTH / (2.0 * specific_energy);
+
+        let inclination_rad = (h_vec[2] / h).clamp(-1.0, 1.0).acos();
+
+        // Reference direction for angles measured in the orbit plane: the ascending node,
+        // or the inertial x-axis for equatorial orbits.
+        let (raan_rad, node_unit) = if node > SMALL * h {
+            let raan = node_vec[1].atan2(node_vec[0]);
+            (raan, [node_vec[0] / node, node_vec[1] / node, 0.0])
+        } else {
+            (0.0, [1.0, 0.0, 0.0])
+        };
+        let h_unit = [h_vec[0] / h, h_vec[1] / h, h_vec[2] / h];
+        let in_plane_perp = cross_3(&h_unit, &node_unit);
+
+        // Argument of latitude (angle from node to satellite, in the direction of motion).
+        let arg_of_latitude_rad =
+            dot_3(position_m, &in_plane_perp).atan2(dot_3(position_m, &node_unit));
+
+        let (arg_of_perigee_rad, true_anomaly_rad) = if eccentricity > SMALL {
+            let w = dot_3(&e_vec, &in_plane_perp).atan2(dot_3(&e_vec, &node_unit));
+            (w, arg_of_latitude_rad - w)
+        } else {
+            (0.0, arg_of_latitude_rad)
+        };
+
+        let eccentric_anomaly_rad = f64::atan2(
+            (1.0 - eccentricity * eccentricity).max(0.0).sqrt() * true_anomaly_rad.sin(),
+            eccentricity + true_anomaly_rad.cos(),
+        );
+        let mean_anomaly_rad = eccentric_anomaly_rad - eccentricity * eccentric_anomaly_rad.sin();
+
+        Self {
+            semi_major_axis_m,
+            eccentricity,
+            inclination_deg: inclination_rad.to_degrees(),
+            raan_deg: wrap_degrees_360(raan_rad.to_degrees()),
+            arg_of_perigee_deg: wrap_degrees_360(arg_of_perigee_rad.to_degrees()),
+            true_anomaly_deg: wrap_degrees_360(true_anomaly_rad.to_degrees()),
+            mean_anomaly_deg: wrap_degrees_360(mean_anomaly_rad.to_degrees()),
+            arg_of_latitude_deg: wrap_degrees_360(arg_of_latitude_rad.to_degrees()),
+            eccentricity_vector_node: [
+                eccentricity * arg_of_perigee_rad.cos(),
+                eccentricity * arg_of_perigee_rad.sin(),
+            ],
+        }
+    }
+
+    /// Altitude of apogee above the equatorial radius, in km.
+    pub fn apogee_altitude_km(&self) -> f64 {
+        (self.semi_major_axis_m * (1.0 + self.eccentricity) - EARTH_RADIUS) / 1000.0
+    }
+
+    /// Altitude of perigee above the equatorial radius, in km.
+    pub fn perigee_altitude_km(&self) -> f64 {
+        (self.semi_major_axis_m * (1.0 - self.eccentricity) - EARTH_RADIUS) / 1000.0
+    }
+
+    /// Two-body orbital period, in minutes.
+    pub fn period_minutes(&self) -> f64 {
+        2.0 * std::f64::consts::PI * (self.semi_major_axis_m.powi(3) / MU_EARTH).sqrt() / 60.0
+    }
+
+    /// Non-singular equinoctial elements for the same orbit.
+    pub fn to_equinoctial(&self) -> EquinoctialElements {
+        let raan_rad = self.raan_deg.to_radians();
+        let longitude_of_perigee_rad = raan_rad + self.arg_of_perigee_deg.to_radians();
+        let half_inclination_tan = (self.inclination_deg.to_radians() / 2.0).tan();
+
+        EquinoctialElements {
+            h: self.eccentricity * longitude_of_perigee_rad.sin(),
+            k: self.eccentricity * longitude_of_perigee_rad.cos(),
+            p: half_inclination_tan * raan_rad.sin(),
+            q: half_inclination_tan * raan_rad.cos(),
+            mean_longitude_deg: wrap_degrees_360(
+                self.raan_deg + self.arg_of_perigee_deg + self.mean_anomaly_deg,
+            ),
+        }
+    }
+}
+
+/// Semi-major axis averaged over one orbit starting at `start_time`, in m.
+///
+/// The osculating semi-major axis swings by several km over an orbit (mostly from J2), which
+/// hides the slow decay from drag; the orbit average tracks the mean orbit instead.
+pub fn orbit_averaged_semi_major_axis_m(
+    satkit_tle: &mut satkit::TLE,
+    start_time: &satkit::Instant,
+    period_minutes: f64,
+) -> anyhow::Result<f64> {
+    const SAMPLES_PER_ORBIT: usize = 36;
+
+    let mut total_m = 0.0;
+    for i in 0..SAMPLES_PER_ORBIT {
+        let time = *start_time
+            + satkit::Duration::from_seconds(
+                period_minutes * 60.0 * i as f64 / SAMPLES_PER_ORBIT as f64,
+            );
+        let (position_teme, velocity_teme) = propagate_teme(satkit_tle, &time)?;
+        total_m +=
+            KeplerianElements::from_state_vector(&position_teme, &velocity_teme).semi_major_axis_m;
+    }
+    Ok(total_m / SAMPLES_PER_ORBIT as f64)
+}
+
+/// Equinoctial orbital elements (prograde form).
+///
+/// These stay well-defined for circular and equatorial orbits, where ω and Ω (and therefore
+/// the classical anomalies) lose their meaning. The semi-major axis is shared with
+/// [`KeplerianElements`] and not repeated here.
+#[derive(Debug, Clone)]
+pub struct EquinoctialElements {
+    /// e·sin(ω + Ω)
+    pub h: f64,
+    /// e·cos(ω + Ω)
+    pub k: f64,
+    /// tan(i/2)·sin(Ω)
+    pub p: f64,
+    /// tan(i/2)·cos(Ω)
+    pub q: f64,
+    /// Ω + ω + M
+    pub mean_longitude_deg: f64,
+}
+
+/// Apply an impulsive delta-v (in the satellite's RTN frame, m/s) at the TLE epoch, and return
+/// the element set of the resulting orbit.
+///
+/// The change in osculating elements caused by the impulse is added onto the TLE's mean elements.
+/// For small impulses (separation springs, trim burns) the mean-to-osculating offset is nearly
+/// identical before and after, so this is a good approximation. The eccentricity vector and mean
+/// longitude are updated rather than ω and M separately, which keeps near-circular orbits stable.
+pub fn apply_delta_v_at_epoch(
+    tle: &TleData,
+    delta_v_rtn_m_per_s: [f64; 3],
+) -> anyhow::Result<TleData> {
+    let mut satkit_tle = tle.to_satkit_tle();
+    let (position_m, velocity_m_per_s) = propagate_teme(&mut satkit_tle, &tle.epoch)?;
+
+    let [radial, transverse, normal] = rtn_basis(&position_m, &velocity_m_per_s);
+    let mut new_velocity_m_per_s = velocity_m_per_s;
+    for axis in 0..3 {
+        new_velocity_m_per_s[axis] += delta_v_rtn_m_per_s[0] * radial[axis]
+            + delta_v_rtn_m_per_s[1] * transverse[axis]
+            + delta_v_rtn_m_per_s[2] * normal[axis];
+    }
+
+    let before = KeplerianElements::from_state_vector(&position_m, &velocity_m_per_s);
+    let after = KeplerianElements::from_state_vector(&position_m, &new_velocity_m_per_s);
+    if !(after.eccentricity < 1.0 && after.semi_major_axis_m > 0.0) {
+        return Err(anyhow::anyhow!(
+            "Delta-v of {:?} m/s results in an unbound orbit",
+            delta_v_rtn_m_per_s
+        ));
+    }
+
+    let mut new_tle = tle.clone();
+
+    // Mean motion scales with a^(-3/2).
+    new_tle.mean_motion =
+        tle.mean_motion * (before.semi_major_axis_m / after.semi_major_axis_m).powf(1.5);
+
+    new_tle.inclination = tle.inclination + (after.inclination_deg - before.inclination_deg);
+    new_tle.raan = wrap_degrees_360(tle.raan + wrap_degrees_180(after.raan_deg - before.raan_deg));
+
+    let mean_w_rad = tle.arg_of_perigee.to_radians();
+    let new_ex = tle.eccen * mean_w_rad.cos() + after.eccentricity_vector_node[0]
+        - before.eccentricity_vector_node[0];
+    let new_ey = tle.eccen * mean_w_rad.sin() + after.eccentricity_vector_node[1]
+        - before.eccentricity_vector_node[1];
+    new_tle.eccen = new_ex.hypot(new_ey).clamp(0.0, 0.999);
+    new_tle.arg_of_perigee = wrap_degrees_360(new_ey.atan2(new_ex).to_degrees());
+
+    // Mean argument of latitude (ω + M) is what stays continuous across the impulse.
+    let osculating_u_change = wrap_degrees_180(
+        (after.arg_of_perigee_deg + after.mean_anomaly_deg)
+            - (before.arg_of_perigee_deg + before.mean_anomaly_deg),
+    );
+    let new_mean_u = tle.arg_of_perigee + tle.mean_anomaly + osculating_u_change;
+    new_tle.mean_anomaly = wrap_degrees_360(new_mean_u - new_tle.arg_of_perigee);
+
+    Ok(new_tle)
+}
+
+/// Element set from SGP4 mean elements in a form that stays well-conditioned for near-circular
+/// orbits: [mean motion (rev/day), e·cos ω, e·sin ω, inclination (deg), RAAN (deg), ω + M (deg)].
+pub fn tle_from_mean_element_params(template: &TleData, params: &[f64; 6]) -> TleData {
+    let mut tle = template.clone();
+    tle.mean_motion = params[0];
+    tle.eccen = params[1].hypot(params[2]);
+    tle.arg_of_perigee = wrap_degrees_360(params[2].atan2(params[1]).to_degrees());
+    tle.inclination = params[3];
+    tle.raan = wrap_degrees_360(params[4]);
+    tle.mean_anomaly = wrap_degrees_360(params[5] - tle.arg_of_perigee);
+    tle
+}
+
+/// Find the element set, with epoch `epoch`, whose SGP4 state at that epoch is the given TEME
+/// state.
+///
+/// Everything other than the mean orbital elements (B*, name, catalog numbers, ...) is taken from
+/// `template`. This is how the run changes the orbit or the ballistic coefficient part-way
+/// through: SGP4 has no way to apply an impulse or a new B* at an arbitrary time, but an element
+/// set re-fitted at that time can carry the new values from there on.
+///
+/// Solved by Newton iteration with a finite-difference Jacobian, starting from the osculating
+/// elements; it converges in a handful of iterations.
+pub fn fit_tle_to_state(
+    template: &TleData,
+    epoch: satkit::Instant,
+    position_teme_m: &[f64; 3],
+    velocity_teme_m_per_s: &[f64; 3],
+) -> anyhow::Result<TleData> {
+    // Weight velocity errors like position errors over roughly 1/ω of an orbit.
+    const VELOCITY_WEIGHT_S: f64 = 1000.0;
+    const STEP_SIZES: [f64; 6] = [1e-7, 1e-7, 1e-7, 1e-6, 1e-6, 1e-6];
+    const MAX_ITERATIONS: usize = 30;
+
+    let mut template = template.clone();
+    template.epoch = epoch;
+
+    let residual = |params: &[f64; 6]| -> anyhow::Result<[f64; 6]> {
+        let tle = tle_from_mean_element_params(&template, params);
+        let (position, velocity) = propagate_teme(&mut tle.to_satkit_tle(), &epoch)?;
+        Ok([
+            position[0] - position_teme_m[0],
+            position[1] - position_teme_m[1],
+            position[2] - position_teme_m[2],
+            (velocity[0] - velocity_teme_m_per_s[0]) * VELOCITY_WEIGHT_S,
+            (velocity[1] - velocity_teme_m_per_s[1]) * VELOCITY_WEIGHT_S,
+            (velocity[2] - velocity_teme_m_per_s[2]) * VELOCITY_WEIGHT_S,
+        ])
+    };
+
+    let osculating = KeplerianElements::from_state_vector(position_teme_m, velocity_teme_m_per_s);
+    if !(osculating.eccentricity < 1.0 && osculating.semi_major_axis_m > 0.0) {
+        return Err(anyhow::anyhow!(
+            "Cannot fit an element set to an unbound orbit"
+        ));
+    }
+    let mut params = [
+        (MU_EARTH / osculating.semi_major_axis_m.powi(3)).sqrt() * 86_400.0
+            / (2.0 * std::f64::consts::PI),
+        osculating.eccentricity_vector_node[0],
+        osculating.eccentricity_vector_node[1],
+        osculating.inclination_deg,
+        osculating.raan_deg,
+        osculating.arg_of_perigee_deg + osculating.mean_anomaly_deg,
+    ];
+
+    for _ in 0..MAX_ITERATIONS {
+        let r = residual(&params)?;
+        let position_error_m = (r[0] * r[0] + r[1] * r[1] + r[2] * r[2]).sqrt();
+        let velocity_error_m = (r[3] * r[3] + r[4] * r[4] + r[5] * r[5]).sqrt();
+        if position_error_m < 1e-3 && velocity_error_m < 1e-3 {
+            return Ok(tle_from_mean_element_params(&template, &params));
+        }
+
+        let mut jacobian = nalgebra::SMatrix::<f64, 6, 6>::zeros();
+        for (column, step) in STEP_SIZES.iter().enumerate() {
+            let mut perturbed = params;
+            perturbed[column] += step;
+            let r_perturbed = residual(&perturbed)?;
+            for row in 0..6 {
+                jacobian[(row, column)] = (r_perturbed[row] - r[row]) / step;
+            }
+        }
+        let correction = jacobian
+            .lu()
+            .solve(&nalgebra::SVector::<f64, 6>::from_column_slice(&r))
+            .ok_or_else(|| anyhow::anyhow!("Singular Jacobian while fitting element set"))?;
+        for (param, delta) in params.iter_mut().zip(correction.iter()) {
+            *param -= delta;
+        }
+    }
+
+    let r = residual(&params)?;
+    Err(anyhow::anyhow!(
+        "Element set fit did not converge (position error {:.3} m)",
+        (r[0] * r[0] + r[1] * r[1] + r[2] * r[2]).sqrt()
+    ))
+}
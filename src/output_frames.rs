@@ -0,0 +1,227 @@
+use satkit::ITRFCoord;
+use satkit::consts::OMEGA_EARTH;
+use satkit::frametransform::qteme2gcrf;
+use satkit::types::{Quaternion, Vec3};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::orbital_elements::rtn_basis;
+use crate::satellite_state::{ConstellationMemberState, SimulationStateAtStep};
+
+/// Reference frame in which positions and velocities are reported.
+///
+/// Every step is propagated in TEME; all other frames are derived from the TEME state on output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
+pub enum OutputFrame {
+    /// True Equator Mean Equinox: the native SGP4 output frame.
+    Teme,
+    /// Geocentric Celestial Reference Frame (J2000 to within a few tens of mas).
+    Gcrf,
+    /// International Terrestrial Reference Frame (Earth-fixed).
+    #[default]
+    Itrf,
+    /// Geodetic latitude/longitude/height above the WGS84 ellipsoid, with an east/north/up
+    /// velocity.
+    GeodeticLla,
+    /// Radial / transverse / normal axes of the primary satellite.
+    Rtn,
+    /// Local-vertical local-horizontal axes of the primary satellite (CCSDS convention:
+    /// +Z to nadir, +Y along the negative orbit normal, +X completing the triad).
+    Lvlh,
+}
+
+/// A position and velocity expressed in an [`OutputFrame`], in the units of
+/// [`OutputFrame::component_labels`].
+#[derive(Debug, Clone, Copy)]
+pub struct FrameState {
+    pub position: [f64; 3],
+    pub velocity: [f64; 3],
+}
+
+impl OutputFrame {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OutputFrame::Teme => "TEME",
+            OutputFrame::Gcrf => "GCRF (J2000)",
+            OutputFrame::Itrf => "ITRF",
+            OutputFrame::GeodeticLla => "Geodetic LLA",
+            OutputFrame::Rtn => "RTN",
+            OutputFrame::Lvlh => "LVLH",
+        }
+    }
+
+    /// Prefix of this frame's CSV column headers.
+    pub fn key(&self) -> &'static str {
+        match self {
+            OutputFrame::Teme => "teme",
+            OutputFrame::Gcrf => "gcrf",
+            OutputFrame::Itrf => "itrf",
+            OutputFrame::GeodeticLla => "lla",
+            OutputFrame::Rtn => "rtn",
+            OutputFrame::Lvlh => "lvlh",
+        }
+    }
+
+    /// Names (with units) of the three position and three velocity components.
+    pub fn component_labels(&self) -> ([&'static str; 3], [&'static str; 3]) {
+        match self {
+            OutputFrame::GeodeticLla => (
+                ["latitude_deg", "longitude_deg", "height_m"],
+                ["v_east_m_per_s", "v_north_m_per_s", "v_up_m_per_s"],
+            ),
+            OutputFrame::Rtn => (
+                ["radial_m", "transverse_m", "normal_m"],
+                [
+                    "v_radial_m_per_s",
+                    "v_transverse_m_per_s",
+                    "v_normal_m_per_s",
+                ],
+            ),
+            OutputFrame::Teme | OutputFrame::Gcrf | OutputFrame::Itrf | OutputFrame::Lvlh => (
+                ["x_m", "y_m", "z_m"],
+                ["vx_m_per_s", "vy_m_per_s", "vz_m_per_s"],
+            ),
+        }
+    }
+
+    /// The primary satellite's state at a step, in this frame.
+    ///
+    /// RTN and LVLH are centred on the satellite itself, so there the position is that of the
+    /// Earth's centre and the velocity is the Earth's apparent velocity in the rotating axes.
+    pub fn convert(&self, t: &SimulationStateAtStep) -> FrameState {
+        match self {
+            OutputFrame::Rtn | OutputFrame::Lvlh => {
+                self.relative_to_primary(t, &[0.0; 3], &[0.0; 3])
+            }
+            OutputFrame::Itrf => FrameState {
+                position: t.position_itrf,
+                velocity: t.velocity_itrf,
+            },
+            _ => self.convert_inertial_state(t, &t.position_teme, &t.velocity_teme),
+        }
+    }
+
+    /// A constellation member's state at a step, in this frame.
+    ///
+    /// In RTN and LVLH this is the member's position and velocity relative to the primary.
+    pub fn convert_member(
+        &self,
+        t: &SimulationStateAtStep,
+        member: &ConstellationMemberState,
+    ) -> FrameState {
+        match self {
+            OutputFrame::Rtn | OutputFrame::Lvlh => {
+                self.relative_to_primary(t, &member.position_teme, &member.velocity_teme)
+            }
+            _ => self.convert_inertial_state(t, &member.position_teme, &member.velocity_teme),
+        }
+    }
+
+    /// Convert a TEME state at the step's time into one of the Earth-centred frames.
+    fn convert_inertial_state(
+        &self,
+        t: &SimulationStateAtStep,
+        position_teme: &[f64; 3],
+        velocity_teme: &[f64; 3],
+    ) -> FrameState {
+        match self {
+            OutputFrame::Teme => FrameState {
+                position: *position_teme,
+                velocity: *velocity_teme,
+            },
+            OutputFrame::Gcrf => {
+                let q = qteme2gcrf(&t.time);
+                FrameState {
+                    position: to_array(&(q * Vec3::from_row_slice(position_teme))),
+                    velocity: to_array(&(q * Vec3::from_row_slice(velocity_teme))),
+                }
+            }
+            OutputFrame::Itrf => teme_to_itrf_state(&t.teme_to_itrf, position_teme, velocity_teme),
+            OutputFrame::GeodeticLla => {
+                let itrf = teme_to_itrf_state(&t.teme_to_itrf, position_teme, velocity_teme);
+                let coord = ITRFCoord::from_slice(&itrf.position).unwrap();
+                let velocity_enu =
+                    coord.q_enu2itrf().conjugate() * Vec3::from_row_slice(&itrf.velocity);
+                FrameState {
+                    position: [coord.latitude_deg(), coord.longitude_deg(), coord.hae()],
+                    velocity: to_array(&velocity_enu),
+                }
+            }
+            OutputFrame::Rtn | OutputFrame::Lvlh => {
+                unreachable!("satellite-relative frames are handled by relative_to_primary")
+            }
+        }
+    }
+
+    /// Express a TEME state relative to the primary satellite, in its RTN or LVLH axes.
+    ///
+    /// The axes rotate with the orbit at ω = (r × v) / |r|², so the relative velocity has
+    /// ω × Δr removed to make it the velocity seen from the rotating frame.
+    fn relative_to_primary(
+        &self,
+        t: &SimulationStateAtStep,
+        position_teme: &[f64; 3],
+        velocity_teme: &[f64; 3],
+    ) -> FrameState {
+        let r = Vec3::from_row_slice(&t.position_teme);
+        let v = Vec3::from_row_slice(&t.velocity_teme);
+        let omega = r.cross(&v) / r.norm_squared();
+
+        let relative_position = Vec3::from_row_slice(position_teme) - r;
+        let relative_velocity =
+            Vec3::from_row_slice(velocity_teme) - v - omega.cross(&relative_position);
+
+        let [radial, transverse, normal] = rtn_basis(&t.position_teme, &t.velocity_teme);
+        let axes = match self {
+            OutputFrame::Lvlh => [transverse, normal.map(|x| -x), radial.map(|x| -x)],
+            _ => [radial, transverse, normal],
+        };
+        let project = |vector: &Vec3| axes.map(|axis| Vec3::from_row_slice(&axis).dot(vector));
+
+        FrameState {
+            position: project(&relative_position),
+            velocity: project(&relative_velocity),
+        }
+    }
+}
+
+fn to_array(v: &Vec3) -> [f64; 3] {
+    [v[0], v[1], v[2]]
+}
+
+/// Rotate a TEME state into ITRF.
+///
+/// ITRF rotates with the Earth, so the velocity is the rotated inertial velocity minus the
+/// velocity of the frame itself at that point (ω⊕ × r). Polar motion and the slow precession of
+/// the pole are neglected in ω⊕; the error is well below 1 mm/s in LEO.
+pub fn teme_to_itrf_state(
+    teme_to_itrf: &Quaternion,
+    position_teme: &[f64; 3],
+    velocity_teme: &[f64; 3],
+) -> FrameState {
+    let position_itrf = teme_to_itrf * Vec3::from_row_slice(position_teme);
+    let earth_rotation = Vec3::new(0.0, 0.0, OMEGA_EARTH);
+    let velocity_itrf =
+        teme_to_itrf * Vec3::from_row_slice(velocity_teme) - earth_rotation.cross(&position_itrf);
+    FrameState {
+        position: to_array(&position_itrf),
+        velocity: to_array(&velocity_itrf),
+    }
+}
+
+/// Rotate an ITRF state into TEME; the inverse of [`teme_to_itrf_state`].
+pub fn itrf_to_teme_state(
+    teme_to_itrf: &Quaternion,
+    position_itrf: &[f64; 3],
+    velocity_itrf: &[f64; 3],
+) -> FrameState {
+    let position_itrf = Vec3::from_row_slice(position_itrf);
+    let earth_rotation = Vec3::new(0.0, 0.0, OMEGA_EARTH);
+    let itrf_to_teme = teme_to_itrf.conjugate();
+    let velocity_teme =
+        itrf_to_teme * (Vec3::from_row_slice(velocity_itrf) + earth_rotation.cross(&position_itrf));
+    FrameState {
+        position: to_array(&(itrf_to_teme * position_itrf)),
+        velocity: to_array(&velocity_teme),
+    }
+}
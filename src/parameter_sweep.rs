@@ -0,0 +1,461 @@
+use std::fmt::Write as _;
+use std::sync::atomic::AtomicUsize;
+
+use satkit::consts::{EARTH_RADIUS, MU_EARTH};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::batch::run_batch;
+use crate::initial_state_model::{GroundStation, InitialSimulationState};
+use crate::satellite_state::{MAX_SATELLITE_RADIUS_M, SimulationRun};
+
+/// Irradiance in full sunlight, as returned by `calculate_sun_irradiance_received_w_per_m2`.
+const SOLAR_CONSTANT_W_PER_M2: f64 = 1361.0;
+
+/// An input that a sweep axis can vary.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum SweepParameter {
+    /// Sets the mean motion to that of a circular orbit at this altitude above the equatorial
+    /// radius. Like the other orbit parameters, not available with a TLE history, whose sets
+    /// replace the primary's elements.
+    #[default]
+    AltitudeKm,
+    InclinationDeg,
+    RaanDeg,
+    Eccentricity,
+    /// B* of the primary. Every other element set (constellation members, TLE history) is
+    /// scaled by the same factor.
+    Bstar,
+    /// C_d. B* is scaled with it, as the TLE's B* goes with the nominal C_d·A.
+    DragCoefficient,
+    /// Drag area. B* is scaled with it, as for `DragCoefficient`. Not available with a panel
+    /// model, whose drag area comes from the panels.
+    DragAreaM2,
+    /// Minimum elevation of every ground station.
+    MinElevationDeg,
+    /// Latitude of every ground station.
+    StationLatitudeDeg,
+}
+
+impl SweepParameter {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SweepParameter::AltitudeKm => "Altitude (km)",
+            SweepParameter::InclinationDeg => "Inclination (deg)",
+            SweepParameter::RaanDeg => "RAAN (deg)",
+            SweepParameter::Eccentricity => "Eccentricity",
+            SweepParameter::Bstar => "B* (1/earth radii)",
+            SweepParameter::DragCoefficient => "Drag coefficient",
+            SweepParameter::DragAreaM2 => "Drag area (m²)",
+            SweepParameter::MinElevationDeg => "Station min elevation (deg)",
+            SweepParameter::StationLatitudeDeg => "Station latitude (deg)",
+        }
+    }
+
+    /// Why this parameter cannot be swept for `nominal`, where varying it would have no effect.
+    fn unsupported_reason(&self, nominal: &InitialSimulationState) -> Option<&'static str> {
+        match self {
+            SweepParameter::AltitudeKm
+            | SweepParameter::InclinationDeg
+            | SweepParameter::RaanDeg
+            | SweepParameter::Eccentricity
+                if nominal.tle_history.is_some() =>
+            {
+                Some("the TLE history replaces the primary's orbit")
+            }
+            SweepParameter::DragAreaM2 if !nominal.satellite.panels.is_empty() => {
+                Some("the panel model sets the drag area")
+            }
+            _ => None,
+        }
+    }
+
+    /// Column header of the CSV export.
+    fn header(&self) -> &'static str {
+        match self {
+            SweepParameter::AltitudeKm => "altitude_km",
+            SweepParameter::InclinationDeg => "inclination_deg",
+            SweepParameter::RaanDeg => "raan_deg",
+            SweepParameter::Eccentricity => "eccentricity",
+            SweepParameter::Bstar => "bstar",
+            SweepParameter::DragCoefficient => "drag_coefficient",
+            SweepParameter::DragAreaM2 => "drag_area_m2",
+            SweepParameter::MinElevationDeg => "station_min_elevation_deg",
+            SweepParameter::StationLatitudeDeg => "station_latitude_deg",
+        }
+    }
+
+    /// Set this parameter of `initial` to `value`. Orbit parameters apply to the primary only.
+    fn apply(&self, initial: &mut InitialSimulationState, value: f64) -> Result<(), String> {
+        match self {
+            SweepParameter::AltitudeKm => {
+                let semi_major_axis_m = EARTH_RADIUS + value * 1000.0;
+                if semi_major_axis_m <= EARTH_RADIUS {
+                    return Err("Sweep altitude must be > 0 km".into());
+                }
+                initial.tle.mean_motion = (MU_EARTH / semi_major_axis_m.powi(3)).sqrt() * 86_400.0
+                    / (2.0 * std::f64::consts::PI);
+            }
+            SweepParameter::InclinationDeg => {
+                if !(0.0..=180.0).contains(&value) {
+                    return Err("Sweep inclination must be between 0 and 180 degrees".into());
+                }
+                initial.tle.inclination = value;
+            }
+            SweepParameter::RaanDeg => initial.tle.raan = value.rem_euclid(360.0),
+            SweepParameter::Eccentricity => {
+                if !(0.0..1.0).contains(&value) {
+                    return Err("Sweep eccentricity must be in [0, 1)".into());
+                }
+                initial.tle.eccen = value;
+            }
+            SweepParameter::Bstar => {
+                if initial.tle.bstar == 0.0 {
+                    return Err("Sweeping B* needs a non-zero nominal B*".into());
+                }
+                let factor = value / initial.tle.bstar;
+                initial.scale_bstar(factor);
+            }
+            SweepParameter::DragCoefficient => {
+                if value <= 0.0 || initial.satellite.drag_coefficient <= 0.0 {
+                    return Err("Sweep drag coefficient must be > 0".into());
+                }
+                let factor = value / initial.satellite.drag_coefficient;
+                initial.satellite.drag_coefficient = value;
+                initial.scale_bstar(factor);
+            }
+            SweepParameter::DragAreaM2 => {
+                if value <= 0.0 || initial.satellite.drag_area_m2 <= 0.0 {
+                    return Err("Sweep drag area must be > 0".into());
+                }
+                let factor = value / initial.satellite.drag_area_m2;
+                initial.satellite.drag_area_m2 = value;
+                initial.scale_bstar(factor);
+            }
+            SweepParameter::MinElevationDeg => {
+                for station in &mut initial.ground_stations {
+                    station.min_elevation_deg = value;
+                }
+            }
+            SweepParameter::StationLatitudeDeg => {
+                // Rebuilt rather than edited, so that the cached position is not carried over.
+                initial.ground_stations = initial
+                    .ground_stations
+                    .iter()
+                    .map(|station| {
+                        GroundStation::new(
+                            station.name.clone(),
+                            value,
+                            station.longitude_deg,
+                            station.elevation_m,
+                            station.altitude_m,
+                            station.min_elevation_deg,
+                        )
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reject an orbit (e.g. a high altitude combined with a large eccentricity) whose apogee is
+/// beyond what a simulation step supports.
+fn check_apogee_in_range(initial: &InitialSimulationState) -> Result<(), String> {
+    let mean_motion_rad_per_s = initial.tle.mean_motion * 2.0 * std::f64::consts::PI / 86_400.0;
+    let semi_major_axis_m = (MU_EARTH / mean_motion_rad_per_s.powi(2)).cbrt();
+    let apogee_radius_m = semi_major_axis_m * (1.0 + initial.tle.eccen);
+    if apogee_radius_m >= MAX_SATELLITE_RADIUS_M {
+        return Err(format!(
+            "Apogee altitude {:.0} km is beyond the simulation's limit of {:.0} km",
+            (apogee_radius_m - EARTH_RADIUS) / 1000.0,
+            (MAX_SATELLITE_RADIUS_M - EARTH_RADIUS) / 1000.0
+        ));
+    }
+    Ok(())
+}
+
+/// Evenly spaced values of one parameter, `start` and `stop` included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepAxis {
+    pub parameter: SweepParameter,
+    pub start: f64,
+    pub stop: f64,
+    pub steps: usize,
+}
+
+impl SweepAxis {
+    pub fn new(
+        parameter: SweepParameter,
+        start: f64,
+        stop: f64,
+        steps: usize,
+    ) -> Result<Self, String> {
+        if steps == 0 {
+            return Err("Sweep axis needs at least one step".into());
+        }
+        if steps > 1 && start == stop {
+            return Err("Sweep axis start and stop must differ".into());
+        }
+        Ok(Self {
+            parameter,
+            start,
+            stop,
+            steps,
+        })
+    }
+
+    pub fn values(&self) -> Vec<f64> {
+        if self.steps == 1 {
+            return vec![self.start];
+        }
+        (0..self.steps)
+            .map(|i| self.start + (self.stop - self.start) * i as f64 / (self.steps - 1) as f64)
+            .collect()
+    }
+}
+
+/// A grid of runs: every combination of the axis values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepDefinition {
+    /// One or two axes. The first one varies fastest through the rows.
+    pub axes: Vec<SweepAxis>,
+    pub threads: usize,
+}
+
+impl SweepDefinition {
+    pub fn new(axes: Vec<SweepAxis>, threads: usize) -> Result<Self, String> {
+        if axes.is_empty() || axes.len() > 2 {
+            return Err("A sweep needs one or two axes".into());
+        }
+        if axes.len() == 2 && axes[0].parameter == axes[1].parameter {
+            return Err("Sweep axes must vary different parameters".into());
+        }
+        if threads == 0 {
+            return Err("A sweep needs at least one thread".into());
+        }
+        Ok(Self { axes, threads })
+    }
+
+    /// Parameter values of every run, one value per axis.
+    fn combinations(&self) -> Vec<Vec<f64>> {
+        self.axes
+            .iter()
+            .fold(vec![Vec::new()], |combinations, axis| {
+                axis.values()
+                    .into_iter()
+                    .flat_map(|value| {
+                        combinations.iter().map(move |combination| {
+                            let mut combination = combination.clone();
+                            combination.push(value);
+                            combination
+                        })
+                    })
+                    .collect()
+            })
+    }
+
+    pub fn run_count(&self) -> usize {
+        self.axes.iter().map(|axis| axis.steps).product()
+    }
+}
+
+/// Summary of one run of a sweep.
+#[derive(Debug, Clone, Copy)]
+pub struct SweepMetrics {
+    /// Reentry time in days since the TLE epoch, if it happened within the run.
+    pub lifetime_days: Option<f64>,
+    pub simulated_days: f64,
+    /// Time with at least one ground station above its minimum elevation.
+    pub contact_minutes_per_day: f64,
+    /// Fraction of the time in Earth's shadow (penumbra counts in part).
+    pub eclipse_fraction: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum SweepMetric {
+    #[default]
+    LifetimeDays,
+    ContactMinutesPerDay,
+    EclipseFraction,
+}
+
+impl SweepMetric {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SweepMetric::LifetimeDays => "Lifetime (days)",
+            SweepMetric::ContactMinutesPerDay => "Contact (minutes/day)",
+            SweepMetric::EclipseFraction => "Eclipse fraction",
+        }
+    }
+
+    /// Runs that did not reenter count with their simulated duration, a lower bound of their
+    /// lifetime.
+    pub fn value(&self, metrics: &SweepMetrics) -> f64 {
+        match self {
+            SweepMetric::LifetimeDays => metrics.lifetime_days.unwrap_or(metrics.simulated_days),
+            SweepMetric::ContactMinutesPerDay => metrics.contact_minutes_per_day,
+            SweepMetric::EclipseFraction => metrics.eclipse_fraction,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SweepRow {
+    /// One value per axis of the sweep.
+    pub values: Vec<f64>,
+    pub metrics: Result<SweepMetrics, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SweepResult {
+    pub axes: Vec<SweepAxis>,
+    pub rows: Vec<SweepRow>,
+}
+
+impl SweepResult {
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        for axis in &self.axes {
+            let _ = write!(csv, "{},", axis.parameter.header());
+        }
+        csv.push_str(
+            "lifetime_days,reentered,simulated_days,contact_minutes_per_day,eclipse_fraction,error\n",
+        );
+        for row in &self.rows {
+            for value in &row.values {
+                let _ = write!(csv, "{value},");
+            }
+            let _ = match &row.metrics {
+                Ok(metrics) => writeln!(
+                    csv,
+                    "{:.4},{},{:.4},{:.3},{:.5},",
+                    SweepMetric::LifetimeDays.value(metrics),
+                    metrics.lifetime_days.is_some(),
+                    metrics.simulated_days,
+                    metrics.contact_minutes_per_day,
+                    metrics.eclipse_fraction
+                ),
+                Err(e) => writeln!(csv, ",,,,,\"{}\"", e.replace('"', "'")),
+            };
+        }
+        csv
+    }
+
+    pub fn write_csv(&self, path: &str) -> anyhow::Result<()> {
+        std::fs::write(path, self.to_csv())?;
+        Ok(())
+    }
+}
+
+/// Fraction of the interval between two samples during which a linearly interpolated
+/// `margin` is positive.
+fn positive_fraction(before: f64, after: f64) -> f64 {
+    match (before > 0.0, after > 0.0) {
+        (true, true) => 1.0,
+        (false, false) => 0.0,
+        (true, false) => before / (before - after),
+        (false, true) => after / (after - before),
+    }
+}
+
+/// Run to the end of `max_days` or to reentry.
+fn run_combination(initial: InitialSimulationState) -> anyhow::Result<SweepMetrics> {
+    let max_hours = initial.simulation_settings.max_days * 24.0;
+    let epoch = initial.tle.epoch;
+    let min_elevations_deg = initial
+        .ground_stations
+        .iter()
+        .map(|station| station.min_elevation_deg)
+        .collect::<Vec<_>>();
+    let mut run = SimulationRun::new(initial, false)?;
+
+    let mut lifetime_days = None;
+    let mut contact_seconds = 0.0;
+    let mut shadow_sum = 0.0;
+    let mut step_count = 0usize;
+    let mut previous: Option<(satkit::Instant, Vec<f64>)> = None;
+    while run.hours_since_epoch() < max_hours {
+        let telemetry = run.step()?;
+        shadow_sum += 1.0 - (telemetry.irradiance_w_per_m2 / SOLAR_CONSTANT_W_PER_M2).min(1.0);
+        step_count += 1;
+
+        if let Some((previous_time, previous_angles_deg)) = &previous {
+            // The union of the stations' passes is approximated by the longest one.
+            let in_view_fraction = min_elevations_deg
+                .iter()
+                .enumerate()
+                .map(|(station, min_elevation_deg)| {
+                    positive_fraction(
+                        previous_angles_deg[station] - min_elevation_deg,
+                        telemetry.elevation_angles_degrees[station] - min_elevation_deg,
+                    )
+                })
+                .fold(0.0, f64::max);
+            contact_seconds += in_view_fraction * (telemetry.time - *previous_time).as_seconds();
+        }
+        previous = Some((telemetry.time, telemetry.elevation_angles_degrees.clone()));
+
+        if telemetry.is_deorbited {
+            lifetime_days = Some((telemetry.time - epoch).as_days());
+            break;
+        }
+    }
+
+    let simulated_days = run.hours_since_epoch() / 24.0;
+    Ok(SweepMetrics {
+        lifetime_days,
+        simulated_days,
+        contact_minutes_per_day: if simulated_days > 0.0 {
+            contact_seconds / 60.0 / simulated_days
+        } else {
+            0.0
+        },
+        eclipse_fraction: if step_count > 0 {
+            shadow_sum / step_count as f64
+        } else {
+            0.0
+        },
+    })
+}
+
+/// Run every combination of `definition` on `nominal`. `completed` counts finished runs, for
+/// progress.
+pub fn run_sweep(
+    nominal: &InitialSimulationState,
+    definition: &SweepDefinition,
+    completed: &AtomicUsize,
+) -> anyhow::Result<SweepResult> {
+    for axis in &definition.axes {
+        if let Some(reason) = axis.parameter.unsupported_reason(nominal) {
+            return Err(anyhow::anyhow!(
+                "{} cannot be swept: {reason}",
+                axis.parameter.label()
+            ));
+        }
+    }
+    let combinations = definition.combinations();
+    let metrics = run_batch(
+        nominal,
+        combinations.len(),
+        definition.threads,
+        completed,
+        |nominal, index| {
+            let mut initial = nominal.clone();
+            for (axis, value) in definition.axes.iter().zip(&combinations[index]) {
+                axis.parameter.apply(&mut initial, *value)?;
+            }
+            check_apogee_in_range(&initial)?;
+            run_combination(initial).map_err(|e| e.to_string())
+        },
+    )?;
+
+    Ok(SweepResult {
+        axes: definition.axes.clone(),
+        rows: combinations
+            .into_iter()
+            .zip(metrics)
+            .map(|(values, metrics)| SweepRow { values, metrics })
+            .collect(),
+    })
+}
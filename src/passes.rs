@@ -0,0 +1,99 @@
+use satkit::{Duration, Instant};
+
+use crate::initial_state_model::GroundStation;
+use crate::satellite_state::{SimulationStateAtStep, pythag_3};
+
+/// One pass of the primary over a ground station, from the run's telemetry.
+#[derive(Debug, Clone)]
+pub struct PassWindow {
+    pub station: String,
+    /// Acquisition and loss of signal, interpolated between steps.
+    pub aos: Instant,
+    pub los: Instant,
+    /// Highest elevation angle among the steps of the pass.
+    pub max_elevation_deg: f64,
+    /// 1-sigma timing errors of AOS and LOS, from the along-track position error (`None`
+    /// without an uncertainty model). Radial and cross-track errors, which mostly change the
+    /// elevation reached, are not included.
+    pub aos_sigma_seconds: Option<f64>,
+    pub los_sigma_seconds: Option<f64>,
+}
+
+impl PassWindow {
+    /// The window opened by `sigmas` standard deviations of timing error on either side.
+    pub fn widened(&self, sigmas: f64) -> (Instant, Instant) {
+        let margin = |sigma_seconds: Option<f64>| {
+            Duration::from_seconds(sigmas * sigma_seconds.unwrap_or(0.0))
+        };
+        (
+            self.aos - margin(self.aos_sigma_seconds),
+            self.los + margin(self.los_sigma_seconds),
+        )
+    }
+}
+
+/// Along-track timing error at a step: the along-track sigma over the inertial speed.
+fn timing_sigma_seconds(step: &SimulationStateAtStep) -> Option<f64> {
+    step.uncertainty
+        .map(|u| u.along_track_sigma_m / pythag_3(&step.velocity_teme))
+}
+
+/// Time and timing sigma a `fraction` of the way from `before` to `after`.
+fn interpolate(
+    before: &SimulationStateAtStep,
+    after: &SimulationStateAtStep,
+    fraction: f64,
+) -> (Instant, Option<f64>) {
+    let time =
+        before.time + Duration::from_seconds(fraction * (after.time - before.time).as_seconds());
+    let sigma = timing_sigma_seconds(before)
+        .zip(timing_sigma_seconds(after))
+        .map(|(b, a)| b + (a - b) * fraction);
+    (time, sigma)
+}
+
+/// Complete passes (AOS and LOS both within the history) of every station, in time order.
+pub fn find_pass_windows(
+    history: &[SimulationStateAtStep],
+    stations: &[GroundStation],
+) -> Vec<PassWindow> {
+    let mut passes = Vec::new();
+    for (index, station) in stations.iter().enumerate() {
+        let margin = |step: &SimulationStateAtStep| {
+            step.elevation_angles_degrees[index] - station.min_elevation_deg
+        };
+        let mut open: Option<(Instant, Option<f64>, f64)> = None;
+        for pair in history.windows(2) {
+            let (before, after) = (&pair[0], &pair[1]);
+            let (margin_before, margin_after) = (margin(before), margin(after));
+            let fraction = margin_before / (margin_before - margin_after);
+            if margin_before <= 0.0 && margin_after > 0.0 {
+                let (aos, aos_sigma_seconds) = interpolate(before, after, fraction);
+                open = Some((aos, aos_sigma_seconds, f64::NEG_INFINITY));
+            }
+            if let Some((_, _, max_elevation_deg)) = &mut open {
+                *max_elevation_deg = max_elevation_deg.max(after.elevation_angles_degrees[index]);
+            }
+            if margin_before > 0.0
+                && margin_after <= 0.0
+                && let Some((aos, aos_sigma_seconds, max_elevation_deg)) = open.take()
+            {
+                let (los, los_sigma_seconds) = interpolate(before, after, fraction);
+                passes.push(PassWindow {
+                    station: station.name.clone(),
+                    aos,
+                    los,
+                    max_elevation_deg,
+                    aos_sigma_seconds,
+                    los_sigma_seconds,
+                });
+            }
+        }
+    }
+    passes.sort_by(|a, b| {
+        a.aos
+            .partial_cmp(&b.aos)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    passes
+}
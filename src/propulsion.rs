@@ -0,0 +1,252 @@
+use satkit::Instant;
+use satkit::types::Vec3;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::initial_state_model::TleData;
+use crate::orbital_elements::{fit_tle_to_state, rtn_basis};
+use crate::satellite_state::propagate_teme;
+
+/// Standard gravity, which relates specific impulse to exhaust velocity.
+pub const STANDARD_GRAVITY_M_PER_S2: f64 = 9.806_65;
+
+/// Finite burns are integrated as a series of impulses, one per sub-step of this length.
+const BURN_SUBSTEP_SECONDS: f64 = 60.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum ThrustDirection {
+    /// Along the inertial velocity (raises the orbit).
+    #[default]
+    AlongVelocity,
+    /// Against the inertial velocity (lowers the orbit).
+    AntiVelocity,
+    RadialOut,
+    RadialIn,
+    /// Along the orbit normal (r × v).
+    OrbitNormal,
+    AntiNormal,
+}
+
+impl ThrustDirection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThrustDirection::AlongVelocity => "Along velocity",
+            ThrustDirection::AntiVelocity => "Anti-velocity",
+            ThrustDirection::RadialOut => "Radial out",
+            ThrustDirection::RadialIn => "Radial in",
+            ThrustDirection::OrbitNormal => "Orbit normal",
+            ThrustDirection::AntiNormal => "Anti-normal",
+        }
+    }
+
+    /// Unit thrust direction in TEME for a satellite at the given TEME state.
+    pub fn unit_vector_teme(
+        &self,
+        position_teme_m: &[f64; 3],
+        velocity_teme_m_per_s: &[f64; 3],
+    ) -> [f64; 3] {
+        let [radial, _, normal] = rtn_basis(position_teme_m, velocity_teme_m_per_s);
+        let velocity = Vec3::from_row_slice(velocity_teme_m_per_s).normalize();
+        let along = [velocity[0], velocity[1], velocity[2]];
+        match self {
+            ThrustDirection::AlongVelocity => along,
+            ThrustDirection::AntiVelocity => along.map(|x| -x),
+            ThrustDirection::RadialOut => radial,
+            ThrustDirection::RadialIn => radial.map(|x| -x),
+            ThrustDirection::OrbitNormal => normal,
+            ThrustDirection::AntiNormal => normal.map(|x| -x),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Thruster {
+    pub thrust_n: f64,
+    pub specific_impulse_s: f64,
+
+    /// Mass with an empty tank.
+    pub dry_mass_kg: f64,
+    /// Mass at the TLE epoch, with a full tank.
+    pub wet_mass_kg: f64,
+
+    /// Fraction of the time the thruster fires during a burn (e.g. pulsed cold-gas or thermally
+    /// limited electric propulsion). Thrust is averaged over the cycle.
+    pub duty_cycle: f64,
+}
+
+impl Thruster {
+    pub fn new(
+        thrust_n: f64,
+        specific_impulse_s: f64,
+        dry_mass_kg: f64,
+        wet_mass_kg: f64,
+        duty_cycle: f64,
+    ) -> Result<Self, String> {
+        if thrust_n <= 0.0 {
+            return Err("Thrust must be > 0".into());
+        }
+        if specific_impulse_s <= 0.0 {
+            return Err("Specific impulse must be > 0".into());
+        }
+        if dry_mass_kg <= 0.0 {
+            return Err("Dry mass must be > 0".into());
+        }
+        if wet_mass_kg < dry_mass_kg {
+            return Err("Wet mass must be >= dry mass".into());
+        }
+        if !(duty_cycle > 0.0 && duty_cycle <= 1.0) {
+            return Err("Duty cycle must be within (0, 1]".into());
+        }
+        Ok(Self {
+            thrust_n,
+            specific_impulse_s,
+            dry_mass_kg,
+            wet_mass_kg,
+            duty_cycle,
+        })
+    }
+
+    pub fn exhaust_velocity_m_per_s(&self) -> f64 {
+        self.specific_impulse_s * STANDARD_GRAVITY_M_PER_S2
+    }
+
+    /// Propellant flow averaged over the duty cycle, in kg/s.
+    pub fn mass_flow_kg_per_s(&self) -> f64 {
+        self.thrust_n * self.duty_cycle / self.exhaust_velocity_m_per_s()
+    }
+
+    /// Delta-v from burning `propellant_kg` starting at `mass_kg` (Tsiolkovsky rocket equation).
+    pub fn delta_v_m_per_s(&self, mass_kg: f64, propellant_kg: f64) -> f64 {
+        self.exhaust_velocity_m_per_s() * (mass_kg / (mass_kg - propellant_kg)).ln()
+    }
+}
+
+/// A burn in the satellite's schedule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledBurn {
+    pub name: String,
+    pub start_days_since_epoch: f64,
+    pub duration_minutes: f64,
+    pub direction: ThrustDirection,
+}
+
+impl ScheduledBurn {
+    pub fn new(
+        name: String,
+        start_days_since_epoch: f64,
+        duration_minutes: f64,
+        direction: ThrustDirection,
+    ) -> Result<Self, String> {
+        if start_days_since_epoch < 0.0 {
+            return Err("Burn start must be >= 0 days".into());
+        }
+        if duration_minutes <= 0.0 {
+            return Err("Burn duration must be > 0".into());
+        }
+        Ok(Self {
+            name,
+            start_days_since_epoch,
+            duration_minutes,
+            direction,
+        })
+    }
+
+    pub fn start_time(&self, epoch: &Instant) -> Instant {
+        *epoch + satkit::Duration::from_days(self.start_days_since_epoch)
+    }
+
+    pub fn end_time(&self, epoch: &Instant) -> Instant {
+        self.start_time(epoch) + satkit::Duration::from_minutes(self.duration_minutes)
+    }
+}
+
+/// The part of a burn carried out between two simulation steps.
+#[derive(Debug, Clone)]
+pub struct BurnSegment {
+    pub name: String,
+    pub start: Instant,
+    pub end: Instant,
+    pub delta_v_m_per_s: f64,
+    pub propellant_used_kg: f64,
+    /// The tank ran dry before the end of the segment.
+    pub propellant_depleted: bool,
+}
+
+/// Propulsion state at a simulation step.
+#[derive(Debug, Clone)]
+pub struct PropulsionState {
+    pub mass_kg: f64,
+    pub propellant_remaining_kg: f64,
+    /// Delta-v of every burn so far.
+    pub total_delta_v_m_per_s: f64,
+    /// Burns carried out since the previous step.
+    pub burn_segments: Vec<BurnSegment>,
+}
+
+/// Fire the thruster over `[start, end]` (part of `burn`) and return the element set of the
+/// resulting orbit, with its epoch at the last sub-step.
+///
+/// SGP4 cannot integrate a force, so the burn is split into sub-steps; each one's delta-v is
+/// applied as an impulse at its midpoint and the element set is re-fitted to the new state there.
+/// B* is scaled with the mass, as the ballistic coefficient goes with 1/m. `mass_kg` is reduced
+/// by the propellant used; the burn stops early if the tank runs dry.
+pub fn fire_thruster(
+    thruster: &Thruster,
+    burn: &ScheduledBurn,
+    tle: &TleData,
+    mass_kg: &mut f64,
+    start: &Instant,
+    end: &Instant,
+) -> anyhow::Result<(TleData, BurnSegment)> {
+    let mut tle = tle.clone();
+    let mut segment = BurnSegment {
+        name: burn.name.clone(),
+        start: *start,
+        end: *start,
+        delta_v_m_per_s: 0.0,
+        propellant_used_kg: 0.0,
+        propellant_depleted: false,
+    };
+
+    let total_seconds = (*end - *start).as_seconds();
+    let mut elapsed_seconds = 0.0;
+    while elapsed_seconds < total_seconds {
+        let propellant_remaining_kg = *mass_kg - thruster.dry_mass_kg;
+        if propellant_remaining_kg <= 0.0 {
+            segment.propellant_depleted = true;
+            break;
+        }
+
+        let mut substep_seconds = BURN_SUBSTEP_SECONDS.min(total_seconds - elapsed_seconds);
+        let mut propellant_kg = thruster.mass_flow_kg_per_s() * substep_seconds;
+        if propellant_kg >= propellant_remaining_kg {
+            propellant_kg = propellant_remaining_kg;
+            substep_seconds = propellant_kg / thruster.mass_flow_kg_per_s();
+        }
+        let delta_v_m_per_s = thruster.delta_v_m_per_s(*mass_kg, propellant_kg);
+
+        let time = *start + satkit::Duration::from_seconds(elapsed_seconds + substep_seconds / 2.0);
+        let (position_teme, mut velocity_teme) = propagate_teme(&mut tle.to_satkit_tle(), &time)?;
+        let direction = burn
+            .direction
+            .unit_vector_teme(&position_teme, &velocity_teme);
+        for axis in 0..3 {
+            velocity_teme[axis] += delta_v_m_per_s * direction[axis];
+        }
+
+        let mut template = tle.clone();
+        template.bstar *= *mass_kg / (*mass_kg - propellant_kg);
+        tle = fit_tle_to_state(&template, time, &position_teme, &velocity_teme)?;
+
+        *mass_kg -= propellant_kg;
+        elapsed_seconds += substep_seconds;
+        segment.delta_v_m_per_s += delta_v_m_per_s;
+        segment.propellant_used_kg += propellant_kg;
+        segment.end = *start + satkit::Duration::from_seconds(elapsed_seconds);
+    }
+    if elapsed_seconds < total_seconds {
+        segment.propellant_depleted = true;
+    }
+
+    Ok((tle, segment))
+}
@@ -1,27 +1,68 @@
 use satkit::ITRFCoord;
 use satkit::consts::{EARTH_RADIUS, SUN_RADIUS, WGS84_A};
-use satkit::frametransform::qgcrf2itrf;
-use satkit::frametransform::qteme2itrf;
+use satkit::frametransform::{qgcrf2itrf, qteme2gcrf};
 use satkit::lpephem::sun::pos_gcrf;
 use satkit::sgp4::{SGP4Error, sgp4};
+use satkit::types::Quaternion;
 use satkit::{Instant, types::Vec3};
 
-use crate::initial_state_model::InitialSimulationState;
+use crate::aerobraking::{
+    BSTAR_TO_DRAG_AREA_PER_MASS, PerigeePass, analyze_perigee_pass, find_perigee_times,
+};
+use crate::attitude::{AttitudeState, compute_attitude};
+use crate::deployment::UndeployedComparison;
+use crate::earth_orientation::{EarthOrientationMode, qteme2itrf_with_mode};
+use crate::initial_state_model::{InitialSimulationState, TleData};
+use crate::orbital_elements::{
+    EquinoctialElements, KeplerianElements, fit_tle_to_state, orbit_averaged_semi_major_axis_m,
+    wrap_degrees_180, wrap_degrees_360,
+};
+use crate::output_frames::teme_to_itrf_state;
+use crate::propulsion::{BurnSegment, PropulsionState, ScheduledBurn, Thruster, fire_thruster};
+use crate::solar_power::{OrbitAveragePower, orbit_average_power};
+use crate::spacecraft_geometry::{
+    bstar_scale_from_panels, drag_area_m2, projected_area_m2, srp_force_body_n,
+};
+use crate::station_keeping::Reboost;
+use crate::uncertainty::{PositionUncertainty, UncertaintyPropagator};
+
+/// Farthest distance from the Earth's centre that the irradiance model (and so a simulation
+/// step) supports.
+pub const MAX_SATELLITE_RADIUS_M: f64 = 5.0 * EARTH_RADIUS;
 
 pub fn pythag_3(vector: &[f64; 3]) -> f64 {
     f64::sqrt(vector[0].powi(2) + vector[1].powi(2) + vector[2].powi(2))
 }
 
+/// Run SGP4 for a single timestamp, returning the TEME position (m) and velocity (m/s).
+pub fn propagate_teme(
+    satkit_tle: &mut satkit::TLE,
+    time: &Instant,
+) -> anyhow::Result<([f64; 3], [f64; 3])> {
+    let (position_teme, velocity_teme, errs) = sgp4(satkit_tle, &[*time]);
+    if let Some(err) = errs.first()
+        && *err != SGP4Error::SGP4Success
+    {
+        return Err(anyhow::anyhow!("SGP4 error: {}", err));
+    }
+    Ok((
+        [position_teme[0], position_teme[1], position_teme[2]],
+        [velocity_teme[0], velocity_teme[1], velocity_teme[2]],
+    ))
+}
+
 /// Calculate the elevation (above sea level) in kilometers from the position vector in kilometers.
 pub fn calculate_elevation_from_location_km(position_km: &[f64; 3]) -> f64 {
     let earth_radius_km = EARTH_RADIUS / 1000.0;
     let radius_km = pythag_3(position_km);
 
-    let elevation_km = radius_km - earth_radius_km;
-    elevation_km
+    radius_km - earth_radius_km
 }
 
-/// Compute the satellite's local solar time in hours [0, 24).
+/// Compute the satellite's local mean solar time in hours [0, 24).
+///
+/// This assumes a Sun moving uniformly along the equator; see
+/// `calculate_apparent_solar_time_hours` for the true (sundial) time.
 pub fn calculate_local_solar_time_hours(longitude_deg: f64, time: &Instant) -> f64 {
     let jd = time.as_jd() + 0.5; // jd=0.0 happens at 12:00 UTC
     let fractional_day = jd.fract();
@@ -30,6 +71,37 @@ pub fn calculate_local_solar_time_hours(longitude_deg: f64, time: &Instant) -> f
     (local_time + 24.0) % 24.0
 }
 
+/// Sun position in TEME (m), so it can be compared directly with SGP4 output.
+fn sun_position_teme_m(time: &Instant) -> Vec3 {
+    qteme2gcrf(time).conjugate() * pos_gcrf(time)
+}
+
+/// Local time, in hours [0, 24), of a meridian at the given inertial right ascension:
+/// 12h when the meridian faces the Sun.
+fn local_time_of_right_ascension_hours(right_ascension_deg: f64, time: &Instant) -> f64 {
+    let sun_teme_m = sun_position_teme_m(time);
+    let sun_right_ascension_deg = sun_teme_m[1].atan2(sun_teme_m[0]).to_degrees();
+    (12.0 + wrap_degrees_360(right_ascension_deg - sun_right_ascension_deg) / 15.0) % 24.0
+}
+
+/// Compute the satellite's local apparent (true) solar time in hours [0, 24).
+///
+/// This is the hour angle of the actual Sun at the sub-satellite point, so it includes the
+/// equation of time that `calculate_local_solar_time_hours` leaves out.
+pub fn calculate_apparent_solar_time_hours(position_teme_m: &[f64; 3], time: &Instant) -> f64 {
+    local_time_of_right_ascension_hours(
+        position_teme_m[1].atan2(position_teme_m[0]).to_degrees(),
+        time,
+    )
+}
+
+/// Local time of the ascending node (LTAN) in hours [0, 24), from the RAAN (TEME, degrees).
+///
+/// The local time of the descending node is 12 hours off from this.
+pub fn calculate_ltan_hours(raan_deg: f64, time: &Instant) -> f64 {
+    local_time_of_right_ascension_hours(raan_deg, time)
+}
+
 /// Calculate the elevation angle in degrees from a satellite's position to a ground station.
 /// The elevation angle is the angle above the local horizontal plane at the ground station.
 /// When >= 0 degrees, the satellite is above the horizon, and the ground station can communicate with it.
@@ -66,24 +138,38 @@ pub fn calculate_elevation_angle_degrees(
     elevation_rad.to_degrees()
 }
 
-pub fn calculate_power_from_atmospheric_drag_watts(
-    satellite: &crate::initial_state_model::Satellite,
+/// Atmospheric density (NRLMSISE-00), in kg/m³.
+pub fn calculate_atmospheric_density_kg_per_m3(
     elevation_km: f64,
     latitude_deg: Option<f64>,
     longitude_deg: Option<f64>,
-    speed_m_per_s: f64,
     time: Option<satkit::Instant>,
     enable_space_weather: bool,
 ) -> f64 {
     let (rho_density_kg_per_m3, _temperature_kelvin) = // TODO: Encorporate space weather data by passing in a date.
         satkit::nrlmsise::nrlmsise(elevation_km, latitude_deg, longitude_deg, time, enable_space_weather);
+    rho_density_kg_per_m3
+}
 
-    let power_watts = 0.5
-        * satellite.drag_coefficient
-        * rho_density_kg_per_m3
-        * satellite.drag_area_m2
-        * speed_m_per_s.powi(3);
-    power_watts
+/// Power dissipated by atmospheric drag, from the drag coefficient times the drag area (C_d·A).
+pub fn calculate_power_from_atmospheric_drag_watts(
+    drag_coefficient_area_m2: f64,
+    elevation_km: f64,
+    latitude_deg: Option<f64>,
+    longitude_deg: Option<f64>,
+    speed_m_per_s: f64,
+    time: Option<satkit::Instant>,
+    enable_space_weather: bool,
+) -> f64 {
+    let rho_density_kg_per_m3 = calculate_atmospheric_density_kg_per_m3(
+        elevation_km,
+        latitude_deg,
+        longitude_deg,
+        time,
+        enable_space_weather,
+    );
+
+    0.5 * drag_coefficient_area_m2 * rho_density_kg_per_m3 * speed_m_per_s.powi(3)
 }
 
 /// Estimate solar irradiance (W/m²) at the satellite's location, accounting for eclipse by Earth.
@@ -120,7 +206,7 @@ pub fn calculate_sun_irradiance_received_approx_w_per_m2(
         "Sun-Earth distance is not within expected range (1 AU)."
     );
     assert!(
-        sat_mag_m > EARTH_RADIUS && sat_mag_m < 5.0 * EARTH_RADIUS,
+        sat_mag_m > EARTH_RADIUS && sat_mag_m < MAX_SATELLITE_RADIUS_M,
         "Satellite distance is not within expected range (above Earth's surface, max 5 Earth radii)."
     );
 
@@ -145,6 +231,11 @@ pub fn calculate_sun_irradiance_received_approx_w_per_m2(
     }
 }
 
+/// Sun position in ITRF (m).
+pub fn sun_position_itrf_m(time: &Instant) -> Vec3 {
+    qgcrf2itrf(time).to_rotation_matrix() * pos_gcrf(time)
+}
+
 /// Estimate solar irradiance (W/m²) at the satellite's location, accounting for eclipse by Earth.
 ///
 /// Returns 1361.0 in full sunlight, 0.0 in umbra, or a partial value in penumbra.
@@ -154,15 +245,9 @@ pub fn calculate_sun_irradiance_received_w_per_m2(
 ) -> f64 {
     const SOLAR_CONSTANT_W_PER_M2: f64 = 1361.0;
 
-    let sun_itrf_m = {
-        // Step 1: Get Sun position in GCRF (in meters).
-        let sun_gcrf_m: Vec3 = pos_gcrf(time);
-        // Step 2: Transform Sun position from GCRF to ITRF.
-        let transform_gcrf_to_itrf = qgcrf2itrf(time).to_rotation_matrix();
-        transform_gcrf_to_itrf * sun_gcrf_m
-    };
+    let sun_itrf_m = sun_position_itrf_m(time);
 
-    // Step 3: Compute unit vectors and geometry.
+    // Compute unit vectors and geometry.
     let sat_itrf_vec = nalgebra::Vector3::<f64>::from_row_slice(satellite_position_itrf_m);
     // Note: Must reconstruct the following as different nalgebra versions are used across crates.
     let sun_itrf_vec = nalgebra::Vector3::<f64>::from_row_slice(sun_itrf_m.as_slice());
@@ -175,7 +260,7 @@ pub fn calculate_sun_irradiance_received_w_per_m2(
         "Sun-Earth distance is not within expected range (1 AU)."
     );
     assert!(
-        sat_mag_m > EARTH_RADIUS && sat_mag_m < 5.0 * EARTH_RADIUS,
+        sat_mag_m > EARTH_RADIUS && sat_mag_m < MAX_SATELLITE_RADIUS_M,
         "Satellite distance is not within expected range (above Earth's sea level, max 5 Earth radii)."
     );
 
@@ -206,10 +291,47 @@ pub fn calculate_sun_irradiance_received_w_per_m2(
     }
 }
 
+/// Altitude of an SGP4-propagated satellite at `time`, in km.
+fn elevation_at_km(tle: &mut satkit::TLE, time: &Instant) -> anyhow::Result<f64> {
+    let (position_teme, _) = propagate_teme(tle, time)?;
+    let position_itrf = qteme2itrf_with_mode(time).0 * Vec3::from_row_slice(&position_teme);
+    Ok(calculate_elevation_from_location_km(&[
+        position_itrf[0] / 1000.0,
+        position_itrf[1] / 1000.0,
+        position_itrf[2] / 1000.0,
+    ]))
+}
+
+/// First time from `start` to `end`, sampled every `step`, at which the satellite is below
+/// 100 km (or SGP4 gives up on it). `None` if it stays up until `end`.
+pub fn predict_reentry_time(
+    tle: &mut satkit::TLE,
+    start: &Instant,
+    end: &Instant,
+    step: satkit::Duration,
+) -> Option<Instant> {
+    let mut time = *start;
+    while time <= *end {
+        match elevation_at_km(tle, &time) {
+            Ok(elevation_km) if elevation_km >= 100.0 => {}
+            _ => return Some(time),
+        }
+        time += step;
+    }
+    None
+}
+
 #[derive(Debug, Clone)]
 pub struct SimulationStateAtStep {
     pub time: Instant,
     pub hours_since_epoch: f64,
+
+    /// SGP4 output state. See `output_frames::OutputFrame` for other frames.
+    pub position_teme: [f64; 3],
+    pub velocity_teme: [f64; 3],
+    pub teme_to_itrf: Quaternion,
+
+    /// Earth-fixed state. The velocity is relative to the rotating Earth.
     pub position_itrf: [f64; 3],
     pub velocity_itrf: [f64; 3],
     pub speed_m_per_s: f64,
@@ -218,7 +340,111 @@ pub struct SimulationStateAtStep {
     pub drag_power_watts: f64,
     pub irradiance_approx_w_per_m2: f64,
     pub irradiance_w_per_m2: f64,
+    /// Local mean solar time at the sub-satellite point.
     pub local_time_hours: f64,
+    /// Local apparent (true) solar time at the sub-satellite point.
+    pub apparent_solar_time_hours: f64,
+    /// Apparent minus mean solar time, in minutes.
+    pub equation_of_time_minutes: f64,
+
+    /// Local time of the ascending node, from the osculating RAAN.
+    pub ltan_hours: f64,
+    /// Change in LTAN since the first step, unwrapped so it can exceed 12 hours.
+    pub ltan_drift_minutes: f64,
+    /// Set on the step right after the satellite crossed the equator.
+    pub node_crossing: Option<NodeCrossing>,
+
+    pub is_deorbited: bool,
+
+    pub attitude: AttitudeState,
+
+    /// Drag coefficient in effect (changed by deployment events).
+    pub drag_coefficient: f64,
+    /// Area presented to the airflow (from the panel model or the latest deployment event).
+    pub drag_area_m2: f64,
+    /// Factor applied to the TLE's B* so far, by the panel model and any deployment events.
+    pub drag_bstar_scale: f64,
+    /// Names of the `Satellite::deployment_events` that fired at this step.
+    pub deployment_events: Vec<String>,
+    /// The satellite as if no deployment event had fired (`None` without deployment events).
+    pub undeployed: Option<UndeployedComparison>,
+
+    /// Mass, propellant and burns (`None` without a thruster).
+    pub propulsion: Option<PropulsionState>,
+    /// Reboost scheduled by the station-keeping controller at this step.
+    pub reboost: Option<Reboost>,
+    /// Perigee passes since the previous step (only with a nose radius set).
+    pub perigee_passes: Vec<PerigeePass>,
+    /// 1-sigma position error (`None` without an uncertainty model).
+    pub uncertainty: Option<PositionUncertainty>,
+    /// Epoch of the element set propagated at this step (see `tle_history`).
+    pub tle_epoch: Instant,
+    /// Panel area facing the Sun (zero without a panel model).
+    pub sun_projected_area_m2: f64,
+    /// Solar radiation pressure force on the panels, in the body frame.
+    pub srp_force_body_n: [f64; 3],
+
+    /// Electrical power from each of `Satellite::solar_arrays`, in the same order.
+    pub solar_array_power_watts: Vec<f64>,
+    /// Power averaged over the most recent orbit that was sampled, which is refreshed once per
+    /// orbital period (`None` without solar arrays, or if the orbit could not be propagated).
+    pub orbit_average_power: Option<OrbitAveragePower>,
+
+    /// How the TEME -> ITRF rotation (and so all ITRF/geodetic outputs) was computed.
+    pub earth_orientation_mode: EarthOrientationMode,
+
+    /// Osculating elements, computed from the TEME (inertial) state.
+    pub orbital_elements: KeplerianElements,
+    pub equinoctial_elements: EquinoctialElements,
+    /// Semi-major axis averaged over the orbit starting at this step (osculating value if the
+    /// orbit can no longer be propagated that far). Without station keeping, a thruster or an
+    /// uncertainty model, it is only refreshed once per orbital period.
+    pub mean_semi_major_axis_m: f64,
+
+    /// State of each `InitialSimulationState::constellation` member, in the same order.
+    pub constellation: Vec<ConstellationMemberState>,
+
+    /// Number of satellites (primary and constellation) above each ground station's minimum
+    /// elevation, in `InitialSimulationState::ground_stations` order.
+    pub satellites_in_view_per_station: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Ascending,
+    Descending,
+}
+
+impl NodeKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NodeKind::Ascending => "ascending",
+            NodeKind::Descending => "descending",
+        }
+    }
+}
+
+/// An equator crossing found between two steps, with the local solar time at which it happened.
+#[derive(Debug, Clone)]
+pub struct NodeCrossing {
+    pub kind: NodeKind,
+    pub time: Instant,
+    /// Revolution count since the start of the run, incremented at each ascending node.
+    pub orbit_number: u32,
+    pub apparent_solar_time_hours: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConstellationMemberState {
+    pub name: String,
+    pub position_teme: [f64; 3],
+    pub velocity_teme: [f64; 3],
+    pub elevation_km: f64,
+
+    /// Argument-of-latitude lead over the primary satellite, in [-180, 180) degrees.
+    /// Positive means the member is ahead of the primary along its orbit.
+    pub along_track_offset_deg: f64,
+    pub elevation_angles_degrees: Vec<f64>,
     pub is_deorbited: bool,
 }
 
@@ -230,58 +456,603 @@ pub struct SimulationRun {
 
     // Evolving state
     satkit_tle_mut: satkit::TLE,
+    constellation_tles_mut: Vec<satkit::TLE>,
+    constellation_deorbited: Vec<bool>,
     current_sim_time: Instant,
+    orbit_number: u32,
+    drag_bstar_scale: f64,
+
+    // Deployment events
+    /// Element set currently being propagated (re-fitted when B* changes).
+    tle_data_mut: TleData,
+    deployment_fired: Vec<bool>,
+    /// Cd and area from the latest fired event, replacing the satellite's own drag model.
+    deployed_drag: Option<(f64, f64)>,
+    /// Cd·A that the current B* corresponds to.
+    effective_drag_coefficient_area_m2: f64,
+    undeployed_tle_mut: Option<satkit::TLE>,
+    undeployed_reentry_time: Option<Instant>,
+
+    // Propulsion
+    /// The satellite's thruster, with the dry mass reduced by any jettisoned mass.
+    thruster: Option<Thruster>,
+    burn_schedule: Vec<ScheduledBurn>,
+    mass_kg: f64,
+    total_burn_delta_v_m_per_s: f64,
+    reboost_count: u32,
+
+    last_perigee_time: Option<Instant>,
+
+    uncertainty: Option<UncertaintyPropagator>,
+
+    /// Element sets of `initial.tle_history`, with B* rescaled like `tle_data_mut`.
+    tle_history_sets: Vec<TleData>,
+    /// Index of the history set being propagated.
+    active_tle_set: Option<usize>,
 
     pub latest_telemetry: Option<SimulationStateAtStep>,
+    /// Time and TEME position of the previous step, for burns and node crossings.
+    previous_step: Option<(Instant, [f64; 3])>,
+    /// Start time and result of the last orbit-averaged semi-major axis.
+    last_mean_semi_major_axis: Option<(Instant, f64)>,
+    /// Start time and result of the last orbit-average power sample.
+    last_orbit_average_power: Option<(Instant, Option<OrbitAveragePower>)>,
+    /// LTAN and accumulated LTAN drift of the previous step.
+    previous_ltan: Option<(f64, f64)>,
+
+    /// Telemetry of the steps not yet collected with `take_history` (`None` unless the run is
+    /// interactive).
+    pending_history: Option<Vec<SimulationStateAtStep>>,
+    /// Print each step to stdout.
+    verbose: bool,
 }
 
 impl SimulationRun {
     /// Seed a new run from the initial state bundle.
-    pub fn new(initial: InitialSimulationState) -> Self {
+    ///
+    /// With a panel model, each element set's B* is rescaled to the model's orbit-average drag
+    /// area (see `bstar_scale_from_panels`). An `interactive` run keeps every step's telemetry
+    /// until collected with `take_history` and prints each step to stdout; batch runs do neither.
+    pub fn new(initial: InitialSimulationState, interactive: bool) -> anyhow::Result<Self> {
         let epoch = initial.tle.epoch;
-        Self {
-            satkit_tle_mut: initial.tle.to_satkit_tle(),
-            initial,
+        let scaled_tle = |tle: &TleData| -> anyhow::Result<(TleData, f64)> {
+            let scale = bstar_scale_from_panels(&initial.satellite, tle)?;
+            let mut tle = tle.clone();
+            tle.bstar *= scale;
+            Ok((tle, scale))
+        };
+
+        let (tle_data_mut, drag_bstar_scale) = scaled_tle(&initial.tle)?;
+        let constellation_tles_mut = initial
+            .constellation
+            .iter()
+            .map(|tle| scaled_tle(tle).map(|(tle, _)| tle.to_satkit_tle()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let tle_history_sets = initial
+            .tle_history
+            .iter()
+            .flat_map(|history| &history.sets)
+            .map(|tle| scaled_tle(tle).map(|(tle, _)| tle))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let satellite = &initial.satellite;
+        if !tle_history_sets.is_empty()
+            && (!satellite.burn_schedule.is_empty()
+                || satellite.station_keeping.is_some()
+                || !satellite.deployment_events.is_empty())
+        {
+            return Err(anyhow::anyhow!(
+                "A TLE history cannot be combined with burns, station keeping or deployment \
+                 events, which change the orbit that the history observes"
+            ));
+        }
+        if satellite.thruster.is_none() && !satellite.burn_schedule.is_empty() {
+            return Err(anyhow::anyhow!("A burn schedule requires a thruster"));
+        }
+        if satellite.thruster.is_none() && satellite.station_keeping.is_some() {
+            return Err(anyhow::anyhow!("Station keeping requires a thruster"));
+        }
+        let mut burn_schedule = satellite.burn_schedule.clone();
+        burn_schedule.sort_by(|a, b| {
+            a.start_days_since_epoch
+                .total_cmp(&b.start_days_since_epoch)
+        });
+
+        let undeployed_tle_mut =
+            (!satellite.deployment_events.is_empty()).then(|| tle_data_mut.to_satkit_tle());
+
+        Ok(Self {
+            satkit_tle_mut: tle_data_mut.to_satkit_tle(),
+            constellation_tles_mut,
+            constellation_deorbited: vec![false; initial.constellation.len()],
             current_sim_time: epoch,
+            orbit_number: 0,
+            drag_bstar_scale,
+            deployment_fired: vec![false; satellite.deployment_events.len()],
+            deployed_drag: None,
+            effective_drag_coefficient_area_m2: satellite.drag_coefficient
+                * satellite.drag_area_m2
+                * drag_bstar_scale,
+            undeployed_tle_mut,
+            undeployed_reentry_time: None,
+            thruster: satellite.thruster.clone(),
+            burn_schedule,
+            mass_kg: satellite
+                .thruster
+                .as_ref()
+                .map_or(0.0, |thruster| thruster.wet_mass_kg),
+            total_burn_delta_v_m_per_s: 0.0,
+            reboost_count: 0,
+            last_perigee_time: None,
+            uncertainty: initial.uncertainty.clone().map(UncertaintyPropagator::new),
+            tle_history_sets,
+            active_tle_set: None,
+            tle_data_mut,
+            initial,
             latest_telemetry: None,
-        }
+            previous_step: None,
+            last_mean_semi_major_axis: None,
+            last_orbit_average_power: None,
+            previous_ltan: None,
+            pending_history: interactive.then(Vec::new),
+            verbose: interactive,
+        })
+    }
+
+    /// Telemetry of the steps since the previous call (empty unless the history is retained).
+    pub fn take_history(&mut self) -> Vec<SimulationStateAtStep> {
+        self.pending_history
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
     }
 
     pub fn hours_since_epoch(&self) -> f64 {
         (self.current_sim_time - self.initial.tle.epoch).as_hours()
     }
 
-    /// Advance one simulation step.
+    /// Element set being propagated, as re-fitted after burns and drag changes.
+    pub fn current_tle(&self) -> &TleData {
+        &self.tle_data_mut
+    }
+
+    /// Epoch of the element set being propagated.
+    fn tle_epoch(&self) -> Instant {
+        self.active_tle_set.map_or(self.initial.tle.epoch, |index| {
+            self.tle_history_sets[index].epoch
+        })
+    }
+
+    /// With a TLE history, switch to the element set that it selects at `time`. The
+    /// uncertainty restarts from its initial value with each new set.
+    fn switch_tle_set(&mut self, time: &Instant) {
+        let Some(history) = &self.initial.tle_history else {
+            return;
+        };
+        let index = history.select(time);
+        if self.active_tle_set == Some(index) {
+            return;
+        }
+        self.active_tle_set = Some(index);
+        self.tle_data_mut = self.tle_history_sets[index].clone();
+        self.satkit_tle_mut = self.tle_data_mut.to_satkit_tle();
+        if let Some(propagator) = &mut self.uncertainty {
+            propagator.restart();
+        }
+        if self.verbose {
+            println!(
+                "Switched to the element set of epoch {}",
+                self.tle_data_mut.epoch
+            );
+        }
+    }
+
+    /// Propagate every constellation member to `time`.
     ///
-    /// Returns per-step telemetry. `telemetry.deorbited == true` when elevation < 100 km.
-    pub fn step(&mut self) -> anyhow::Result<SimulationStateAtStep> {
-        let settings = &self.initial.simulation_settings;
+    /// Members that have decayed (or fail to propagate) are marked deorbited and are no longer
+    /// propagated; they do not stop the run.
+    fn step_constellation(
+        &mut self,
+        time: &Instant,
+        primary_elements: &KeplerianElements,
+    ) -> Vec<ConstellationMemberState> {
         let gs = &self.initial.ground_stations;
-        let sat = &self.initial.satellite;
+        let transform_matrix = qteme2itrf_with_mode(time).0.to_rotation_matrix();
 
-        let time = self.current_sim_time;
+        let mut states = Vec::with_capacity(self.constellation_tles_mut.len());
+        for ((tle, is_deorbited), tle_data) in self
+            .constellation_tles_mut
+            .iter_mut()
+            .zip(self.constellation_deorbited.iter_mut())
+            .zip(self.initial.constellation.iter())
+        {
+            let propagated = if *is_deorbited {
+                None
+            } else {
+                propagate_teme(tle, time).ok()
+            };
+
+            let Some((position_teme, velocity_teme)) = propagated else {
+                *is_deorbited = true;
+                states.push(ConstellationMemberState {
+                    name: tle_data.name.clone(),
+                    position_teme: [f64::NAN; 3],
+                    velocity_teme: [f64::NAN; 3],
+                    elevation_km: f64::NAN,
+                    along_track_offset_deg: f64::NAN,
+                    elevation_angles_degrees: vec![f64::NAN; gs.len()],
+                    is_deorbited: true,
+                });
+                continue;
+            };
 
-        // SGP4 over a single timestamp (slice)
-        let (position_teme, velocity_teme, errs) = sgp4(&mut self.satkit_tle_mut, &[time]);
-        if let Some(err) = errs.first() {
-            if *err != SGP4Error::SGP4Success {
-                return Err(anyhow::anyhow!("SGP4 error: {}", err));
+            let position_itrf_matrix = transform_matrix * Vec3::from_row_slice(&position_teme);
+            let position_itrf = [
+                position_itrf_matrix[0],
+                position_itrf_matrix[1],
+                position_itrf_matrix[2],
+            ];
+            let position_km = position_itrf.map(|x| x / 1000.0);
+            let elevation_km = calculate_elevation_from_location_km(&position_km);
+            *is_deorbited = elevation_km < 100.0;
+
+            let elements = KeplerianElements::from_state_vector(&position_teme, &velocity_teme);
+
+            states.push(ConstellationMemberState {
+                name: tle_data.name.clone(),
+                position_teme,
+                velocity_teme,
+                elevation_km,
+                along_track_offset_deg: wrap_degrees_180(
+                    elements.arg_of_latitude_deg - primary_elements.arg_of_latitude_deg,
+                ),
+                elevation_angles_degrees: gs
+                    .iter()
+                    .map(|station| calculate_elevation_angle_degrees(&position_km, station))
+                    .collect(),
+                is_deorbited: *is_deorbited,
+            });
+        }
+        states
+    }
+
+    /// Fire any deployment events whose trigger is met at this step.
+    ///
+    /// SGP4 cannot change B* part-way along an element set, so the new B* (scaled by the ratio
+    /// of the new Cd·A to the old) is carried by an element set re-fitted to the current state.
+    /// Returns the names of the events fired.
+    fn apply_deployment_events(
+        &mut self,
+        time: &Instant,
+        elevation_km: f64,
+        position_teme: &[f64; 3],
+        velocity_teme: &[f64; 3],
+    ) -> anyhow::Result<Vec<String>> {
+        let days_since_epoch = (*time - self.initial.tle.epoch).as_days();
+        let mut fired_names = Vec::new();
+        for (event, fired) in self
+            .initial
+            .satellite
+            .deployment_events
+            .iter()
+            .zip(self.deployment_fired.iter_mut())
+        {
+            if *fired || !event.is_triggered(days_since_epoch, elevation_km) {
+                continue;
             }
+            *fired = true;
+
+            let drag_coefficient_area_m2 = event.drag_coefficient * event.drag_area_m2;
+            let mut ratio = drag_coefficient_area_m2 / self.effective_drag_coefficient_area_m2;
+            if event.jettisoned_mass_kg > 0.0 {
+                // B* goes as C_d·A/m. Without a thruster, the mass is the one the current B*
+                // implies for the current C_d·A.
+                let mass_before_kg = match &mut self.thruster {
+                    Some(thruster) => {
+                        if event.jettisoned_mass_kg >= thruster.dry_mass_kg {
+                            return Err(anyhow::anyhow!(
+                                "Deployment event \"{}\" jettisons more than the dry mass",
+                                event.name
+                            ));
+                        }
+                        thruster.dry_mass_kg -= event.jettisoned_mass_kg;
+                        let mass_before_kg = self.mass_kg;
+                        self.mass_kg -= event.jettisoned_mass_kg;
+                        mass_before_kg
+                    }
+                    None if self.tle_data_mut.bstar > 0.0 => {
+                        self.effective_drag_coefficient_area_m2
+                            / (BSTAR_TO_DRAG_AREA_PER_MASS * self.tle_data_mut.bstar)
+                    }
+                    None => {
+                        return Err(anyhow::anyhow!(
+                            "Deployment event \"{}\" jettisons mass, which needs a thruster \
+                             mass or a positive B*",
+                            event.name
+                        ));
+                    }
+                };
+                if event.jettisoned_mass_kg >= mass_before_kg {
+                    return Err(anyhow::anyhow!(
+                        "Deployment event \"{}\" jettisons more than the satellite's {:.3} kg",
+                        event.name,
+                        mass_before_kg
+                    ));
+                }
+                ratio *= mass_before_kg / (mass_before_kg - event.jettisoned_mass_kg);
+            }
+            let mut template = self.tle_data_mut.clone();
+            template.bstar *= ratio;
+            self.tle_data_mut = fit_tle_to_state(&template, *time, position_teme, velocity_teme)?;
+            self.satkit_tle_mut = self.tle_data_mut.to_satkit_tle();
+
+            self.drag_bstar_scale *= ratio;
+            self.effective_drag_coefficient_area_m2 = drag_coefficient_area_m2;
+            self.deployed_drag = Some((event.drag_coefficient, event.drag_area_m2));
+            fired_names.push(event.name.clone());
         }
+        Ok(fired_names)
+    }
 
-        // Transform TEME -> ITRF (cap matrix far in the future like your original)
-        let max_tf_time = Instant::new(1767250888000 * 1000);
-        let tf_time = if time < max_tf_time {
-            time
+    /// Carry out the scheduled burns (or the parts of them) that fall within `[from, to)`.
+    fn execute_burns(&mut self, from: &Instant, to: &Instant) -> anyhow::Result<Vec<BurnSegment>> {
+        let Some(thruster) = &self.thruster else {
+            return Ok(Vec::new());
+        };
+        let epoch = self.initial.tle.epoch;
+
+        let mut segments = Vec::new();
+        for burn in &self.burn_schedule {
+            let start = std::cmp::max(*from, burn.start_time(&epoch));
+            let end = std::cmp::min(*to, burn.end_time(&epoch));
+            if end <= start {
+                continue;
+            }
+
+            let mass_before_kg = self.mass_kg;
+            let (tle, segment) = fire_thruster(
+                thruster,
+                burn,
+                &self.tle_data_mut,
+                &mut self.mass_kg,
+                &start,
+                &end,
+            )?;
+            self.satkit_tle_mut = tle.to_satkit_tle();
+            self.tle_data_mut = tle;
+            self.drag_bstar_scale *= mass_before_kg / self.mass_kg;
+            self.total_burn_delta_v_m_per_s += segment.delta_v_m_per_s;
+
+            if self.verbose {
+                println!(
+                    "Burn \"{}\": {:.4} m/s, {:.4} kg propellant from {} to {}",
+                    segment.name,
+                    segment.delta_v_m_per_s,
+                    segment.propellant_used_kg,
+                    segment.start,
+                    segment.end
+                );
+            }
+            let depleted = segment.propellant_depleted;
+            segments.push(segment);
+            if depleted {
+                break;
+            }
+        }
+        Ok(segments)
+    }
+
+    /// Run the station-keeping controller: add a reboost to the burn schedule if the mean
+    /// altitude has dropped out of the deadband and no burn is under way.
+    fn schedule_reboost(&mut self, time: &Instant, mean_semi_major_axis_m: f64) -> Option<Reboost> {
+        let satellite = &self.initial.satellite;
+        let station_keeping = satellite.station_keeping.as_ref()?;
+        let thruster = self.thruster.as_ref()?;
+        let epoch = self.initial.tle.epoch;
+
+        let burning = self
+            .burn_schedule
+            .iter()
+            .any(|burn| burn.start_time(&epoch) <= *time && burn.end_time(&epoch) > *time);
+        if burning {
+            return None;
+        }
+        let reboost = station_keeping.reboost(
+            thruster,
+            self.mass_kg,
+            mean_semi_major_axis_m,
+            time,
+            &epoch,
+            self.reboost_count + 1,
+        )?;
+        self.reboost_count += 1;
+
+        let index = self.burn_schedule.partition_point(|burn| {
+            burn.start_days_since_epoch <= reboost.burn.start_days_since_epoch
+        });
+        self.burn_schedule.insert(index, reboost.burn.clone());
+        if self.verbose {
+            println!(
+                "Scheduled \"{}\" at mean altitude {:.3} km: {:.4} m/s over {:.2} min",
+                reboost.burn.name,
+                reboost.mean_altitude_km,
+                reboost.planned_delta_v_m_per_s,
+                reboost.burn.duration_minutes
+            );
+        }
+        Some(reboost)
+    }
+
+    /// Propagate the undeployed copy of the satellite to `time`, if there is one.
+    ///
+    /// Once the deployed satellite has reentered the run stops, so the undeployed one's reentry is
+    /// then searched for ahead up to `max_days`.
+    fn step_undeployed(
+        &mut self,
+        time: &Instant,
+        is_deorbited: bool,
+    ) -> Option<UndeployedComparison> {
+        let undeployed_tle = self.undeployed_tle_mut.as_mut()?;
+
+        let mut elevation_km = f64::NAN;
+        if self.undeployed_reentry_time.is_none() {
+            match elevation_at_km(undeployed_tle, time) {
+                Ok(km) if km >= 100.0 => elevation_km = km,
+                _ => self.undeployed_reentry_time = Some(*time),
+            }
+        }
+
+        if is_deorbited && self.undeployed_reentry_time.is_none() {
+            let settings = &self.initial.simulation_settings;
+            let end_time = self.initial.tle.epoch + satkit::Duration::from_days(settings.max_days);
+            self.undeployed_reentry_time = predict_reentry_time(
+                undeployed_tle,
+                time,
+                &end_time,
+                satkit::Duration::from_hours(settings.step_interval_hours),
+            );
+        }
+
+        Some(UndeployedComparison {
+            elevation_km,
+            reentry_time: self.undeployed_reentry_time,
+        })
+    }
+
+    /// Analyse the perigee passes between the previous step and this one.
+    ///
+    /// At most one pass is counted per half orbit, so the small radius wiggles of a
+    /// near-circular orbit are not taken for separate passes. Passes that cannot be propagated
+    /// through (reentry) are left out.
+    fn analyze_perigee_passes(
+        &mut self,
+        from: &Instant,
+        to: &Instant,
+        period_minutes: f64,
+    ) -> Vec<PerigeePass> {
+        let Some(nose_radius_m) = self.initial.satellite.nose_radius_m else {
+            return Vec::new();
+        };
+        let Ok(perigee_times) = find_perigee_times(&mut self.satkit_tle_mut, from, to) else {
+            return Vec::new();
+        };
+
+        let mut passes = Vec::new();
+        for perigee_time in perigee_times {
+            if let Some(last) = self.last_perigee_time
+                && (perigee_time - last).as_minutes() < period_minutes / 2.0
+            {
+                continue;
+            }
+            self.last_perigee_time = Some(perigee_time);
+
+            let Ok(pass) = analyze_perigee_pass(
+                &mut self.satkit_tle_mut,
+                &perigee_time,
+                period_minutes,
+                nose_radius_m,
+                self.effective_drag_coefficient_area_m2,
+                self.tle_data_mut.bstar,
+                self.initial
+                    .simulation_settings
+                    .drag_power_enable_space_weather,
+            ) else {
+                continue;
+            };
+            if self.verbose {
+                println!(
+                    "Perigee pass at {} ({:.2} km): peak q {:.3} Pa, peak heat flux {:.1} W/m², heat load {:.1} kJ/m², Δv lost {:.4} m/s, apogee decay {:.3} km",
+                    pass.perigee_time,
+                    pass.perigee_altitude_km,
+                    pass.peak_dynamic_pressure_pa,
+                    pass.peak_heat_flux_w_per_m2,
+                    pass.heat_load_j_per_m2 / 1000.0,
+                    pass.delta_v_lost_m_per_s,
+                    pass.apogee_decay_km()
+                );
+            }
+            passes.push(pass);
+        }
+        passes
+    }
+
+    /// Find an equator crossing between the previous step and this one.
+    ///
+    /// Only detected when the steps are shorter than half an orbit; otherwise crossings cannot
+    /// be told apart and `None` is returned.
+    fn detect_node_crossing(
+        &mut self,
+        time: &Instant,
+        position_teme: &[f64; 3],
+        period_minutes: f64,
+    ) -> Option<NodeCrossing> {
+        let (previous_time, previous_position_teme) = self.previous_step?;
+        let step_seconds = (*time - previous_time).as_seconds();
+        if step_seconds >= period_minutes * 30.0 {
+            return None;
+        }
+
+        let (z_before, z_after) = (previous_position_teme[2], position_teme[2]);
+        let kind = if z_before < 0.0 && z_after >= 0.0 {
+            NodeKind::Ascending
+        } else if z_before > 0.0 && z_after <= 0.0 {
+            NodeKind::Descending
         } else {
-            max_tf_time
+            return None;
         };
-        let transform_matrix = qteme2itrf(&tf_time).to_rotation_matrix();
-        let position_itrf_matrix = transform_matrix * position_teme;
-        let velocity_itrf_matrix = transform_matrix * velocity_teme;
 
-        let position_itrf = ITRFCoord::from_slice(position_itrf_matrix.as_slice()).unwrap();
-        let velocity_itrf = ITRFCoord::from_slice(velocity_itrf_matrix.as_slice()).unwrap();
+        // Linear interpolation is plenty near the node, where z is nearly linear in time.
+        let fraction = z_before / (z_before - z_after);
+        let crossing_time = previous_time + satkit::Duration::from_seconds(step_seconds * fraction);
+        let crossing_position = [0, 1, 2].map(|i| {
+            previous_position_teme[i] + fraction * (position_teme[i] - previous_position_teme[i])
+        });
+
+        if kind == NodeKind::Ascending {
+            self.orbit_number += 1;
+        }
+        Some(NodeCrossing {
+            kind,
+            time: crossing_time,
+            orbit_number: self.orbit_number,
+            apparent_solar_time_hours: calculate_apparent_solar_time_hours(
+                &crossing_position,
+                &crossing_time,
+            ),
+        })
+    }
+
+    /// Advance one simulation step.
+    ///
+    /// Returns per-step telemetry. `telemetry.deorbited == true` when elevation < 100 km.
+    pub fn step(&mut self) -> anyhow::Result<SimulationStateAtStep> {
+        let time = self.current_sim_time;
+        self.switch_tle_set(&time);
+        let tle_epoch = self.tle_epoch();
+
+        let previous_time = self
+            .previous_step
+            .map_or(time, |(previous_time, _)| previous_time);
+        let burn_segments = self.execute_burns(&previous_time, &time)?;
+        let propulsion = self.thruster.as_ref().map(|thruster| PropulsionState {
+            mass_kg: self.mass_kg,
+            propellant_remaining_kg: self.mass_kg - thruster.dry_mass_kg,
+            total_delta_v_m_per_s: self.total_burn_delta_v_m_per_s,
+            burn_segments,
+        });
+        let gs = &self.initial.ground_stations;
+        let sat = &self.initial.satellite;
+
+        // SGP4 over a single timestamp
+        let (position_teme, velocity_teme) = propagate_teme(&mut self.satkit_tle_mut, &time)?;
+
+        // Transform TEME -> ITRF (extrapolating Earth orientation past the end of the EOP tables)
+        let (teme_to_itrf, earth_orientation_mode) = qteme2itrf_with_mode(&time);
+        let itrf_state = teme_to_itrf_state(&teme_to_itrf, &position_teme, &velocity_teme);
+
+        let position_itrf = ITRFCoord::from_slice(&itrf_state.position).unwrap();
+        let velocity_itrf = ITRFCoord::from_slice(&itrf_state.velocity).unwrap();
 
         let speed_m_per_s = pythag_3(&[
             velocity_itrf.itrf[0],
@@ -302,8 +1073,24 @@ impl SimulationRun {
             .map(|station| calculate_elevation_angle_degrees(&position_km, station))
             .collect::<Vec<_>>();
 
+        let attitude = compute_attitude(
+            &sat.attitude,
+            &position_teme,
+            &velocity_teme,
+            &time,
+            &self.initial.tle.epoch,
+        );
+
+        let deployment_events =
+            self.apply_deployment_events(&time, elevation_km, &position_teme, &velocity_teme)?;
+        let settings = &self.initial.simulation_settings;
+        let gs = &self.initial.ground_stations;
+        let sat = &self.initial.satellite;
+        let (drag_coefficient, drag_area_m2) = self
+            .deployed_drag
+            .unwrap_or_else(|| (sat.drag_coefficient, drag_area_m2(sat, &attitude)));
         let drag_power_watts = calculate_power_from_atmospheric_drag_watts(
-            sat,
+            drag_coefficient * drag_area_m2,
             elevation_km,
             Some(position_itrf.latitude_deg()),
             Some(position_itrf.longitude_deg()),
@@ -315,40 +1102,115 @@ impl SimulationRun {
         let local_time_hours: f64 =
             calculate_local_solar_time_hours(position_itrf.longitude_deg(), &time);
 
-        // (Keep your prints for now; you can remove or gate them with a flag later.)
-        println!(
-            "Time: TLE Epoch + {:.2} days = {:.2} years => UTC {} => Local Time: {:.2}h = {}:{:02}",
-            self.hours_since_epoch() / 24.0,
-            self.hours_since_epoch() / (24.0 * 365.0),
-            time,
-            local_time_hours,
-            local_time_hours.floor() as u32,
-            (local_time_hours % 1.0 * 60.0).round() as u32
-        );
-        println!("Position: {}", position_itrf);
-        println!(
-            "Position: {:?} km = {:.2} km = ({:.5}, {:.5}, h={:.3} km)",
-            position_km,
-            elevation_km,
-            position_itrf.latitude_deg(),
-            position_itrf.longitude_deg(),
-            position_itrf.hae() / 1000.0
-        );
-        println!(
-            "Velocity: {:?} km/s = {:.2} km/s",
-            [
-                velocity_itrf.itrf[0] / 1000.0,
-                velocity_itrf.itrf[1] / 1000.0,
-                velocity_itrf.itrf[2] / 1000.0
-            ],
-            speed_m_per_s / 1000.0
-        );
-        println!(
-            "Drag Power: {:.3} W (Elevation: {:.2} km, Speed: {:.2} km/s)",
-            drag_power_watts,
-            elevation_km,
-            speed_m_per_s / 1000.0
-        );
+        if self.verbose {
+            println!(
+                "Time: TLE Epoch + {:.2} days = {:.2} years => UTC {} => Local Time: {:.2}h = {}:{:02}",
+                self.hours_since_epoch() / 24.0,
+                self.hours_since_epoch() / (24.0 * 365.0),
+                time,
+                local_time_hours,
+                local_time_hours.floor() as u32,
+                (local_time_hours % 1.0 * 60.0).round() as u32
+            );
+            println!("Position: {}", position_itrf);
+            println!(
+                "Position: {:?} km = {:.2} km = ({:.5}, {:.5}, h={:.3} km)",
+                position_km,
+                elevation_km,
+                position_itrf.latitude_deg(),
+                position_itrf.longitude_deg(),
+                position_itrf.hae() / 1000.0
+            );
+            println!(
+                "Velocity: {:?} km/s = {:.2} km/s",
+                [
+                    velocity_itrf.itrf[0] / 1000.0,
+                    velocity_itrf.itrf[1] / 1000.0,
+                    velocity_itrf.itrf[2] / 1000.0
+                ],
+                speed_m_per_s / 1000.0
+            );
+            println!(
+                "Drag Power: {:.3} W (Elevation: {:.2} km, Speed: {:.2} km/s)",
+                drag_power_watts,
+                elevation_km,
+                speed_m_per_s / 1000.0
+            );
+        }
+        let orbital_elements = KeplerianElements::from_state_vector(&position_teme, &velocity_teme);
+        let equinoctial_elements = orbital_elements.to_equinoctial();
+        // Averaging takes many propagations, so unless station keeping, burns or the
+        // uncertainty model need it every step, reuse the last value for an orbital period.
+        let needed_every_step =
+            sat.station_keeping.is_some() || self.thruster.is_some() || self.uncertainty.is_some();
+        let mean_semi_major_axis_m = match self.last_mean_semi_major_axis {
+            Some((sampled_at, mean_semi_major_axis_m))
+                if !needed_every_step
+                    && (time - sampled_at).as_minutes() < orbital_elements.period_minutes() =>
+            {
+                mean_semi_major_axis_m
+            }
+            _ => {
+                let mean_semi_major_axis_m = orbit_averaged_semi_major_axis_m(
+                    &mut self.satkit_tle_mut,
+                    &time,
+                    orbital_elements.period_minutes(),
+                )
+                .unwrap_or(orbital_elements.semi_major_axis_m);
+                self.last_mean_semi_major_axis = Some((time, mean_semi_major_axis_m));
+                mean_semi_major_axis_m
+            }
+        };
+
+        // ½ρv² from the drag power, times the C_d·A/m that the current B* implies.
+        let drag_deceleration_m_per_s2 = if drag_coefficient * drag_area_m2 * speed_m_per_s > 0.0 {
+            drag_power_watts / (drag_coefficient * drag_area_m2 * speed_m_per_s)
+                * (BSTAR_TO_DRAG_AREA_PER_MASS * self.tle_data_mut.bstar).max(0.0)
+        } else {
+            0.0
+        };
+        let uncertainty = self.uncertainty.as_mut().map(|propagator| {
+            propagator.step(
+                &time,
+                &tle_epoch,
+                mean_semi_major_axis_m,
+                drag_deceleration_m_per_s2,
+            )
+        });
+
+        let apparent_solar_time_hours = calculate_apparent_solar_time_hours(&position_teme, &time);
+        let equation_of_time_minutes =
+            wrap_degrees_180((apparent_solar_time_hours - local_time_hours) * 15.0) * 4.0;
+        let ltan_hours = calculate_ltan_hours(orbital_elements.raan_deg, &time);
+        // Accumulate the wrapped change since the previous step, so that drift beyond
+        // +/- 12 hours keeps growing instead of folding back.
+        let ltan_drift_minutes = match self.previous_ltan {
+            Some((previous_ltan_hours, previous_drift_minutes)) => {
+                previous_drift_minutes
+                    + wrap_degrees_180((ltan_hours - previous_ltan_hours) * 15.0) * 4.0
+            }
+            None => 0.0,
+        };
+        self.previous_ltan = Some((ltan_hours, ltan_drift_minutes));
+        if self.verbose {
+            println!(
+                "Apparent solar time: {:.3}h (equation of time {:+.2} min), LTAN: {:.3}h (drift {:+.2} min)",
+                apparent_solar_time_hours, equation_of_time_minutes, ltan_hours, ltan_drift_minutes
+            );
+            println!(
+                "Elements: a={:.3} km, e={:.6}, i={:.4}°, RAAN={:.4}°, ω={:.4}°, ν={:.4}°, M={:.4}° (apogee {:.2} km, perigee {:.2} km, period {:.2} min)",
+                orbital_elements.semi_major_axis_m / 1000.0,
+                orbital_elements.eccentricity,
+                orbital_elements.inclination_deg,
+                orbital_elements.raan_deg,
+                orbital_elements.arg_of_perigee_deg,
+                orbital_elements.true_anomaly_deg,
+                orbital_elements.mean_anomaly_deg,
+                orbital_elements.apogee_altitude_km(),
+                orbital_elements.perigee_altitude_km(),
+                orbital_elements.period_minutes()
+            );
+        }
 
         let irradiance_approx_w_per_m2 = calculate_sun_irradiance_received_approx_w_per_m2(
             &[
@@ -366,36 +1228,71 @@ impl SimulationRun {
             ],
             &time,
         );
-        println!(
-            "Solar Irradiance (Way 1, Approx): {:.2} W/m²",
-            irradiance_approx_w_per_m2
-        );
-        println!(
-            "Solar Irradiance (Way 2, Cones): {:.2} W/m²",
-            irradiance_w_per_m2
-        );
+        let solar_array_power_watts = sat
+            .solar_arrays
+            .iter()
+            .map(|array| array.power_watts(&attitude.sun_body, irradiance_w_per_m2))
+            .collect::<Vec<_>>();
+        let orbit_average_power = if sat.solar_arrays.is_empty() {
+            None
+        } else {
+            // Sampling an orbit takes many propagations, so only resample once per period.
+            let period_minutes = orbital_elements.period_minutes();
+            match &self.last_orbit_average_power {
+                Some((sampled_at, power)) if (time - *sampled_at).as_minutes() < period_minutes => {
+                    power.clone()
+                }
+                _ => {
+                    let power = orbit_average_power(
+                        &sat.solar_arrays,
+                        &sat.attitude,
+                        &mut self.satkit_tle_mut,
+                        &time,
+                        period_minutes,
+                        &self.initial.tle.epoch,
+                    )
+                    .ok();
+                    self.last_orbit_average_power = Some((time, power.clone()));
+                    power
+                }
+            }
+        };
+        let sun_projected_area_m2 = projected_area_m2(&sat.panels, &attitude.sun_body);
+        let srp_force_body_n =
+            srp_force_body_n(&sat.panels, &attitude.sun_body, irradiance_w_per_m2);
 
-        for (station, angle_deg) in gs.iter().zip(elevation_angles_degrees.iter().copied()) {
+        if self.verbose {
+            println!(
+                "Solar Irradiance (Way 1, Approx): {:.2} W/m²",
+                irradiance_approx_w_per_m2
+            );
             println!(
-                "Ground station \"{}\" -> {} Elevation: {:.2} degrees (Distance: {:.2} km)",
-                station.name,
-                if angle_deg > station.min_elevation_deg {
-                    "✅"
-                } else {
-                    "❌"
-                },
-                angle_deg,
-                pythag_3(&[
-                    position_km[0] - station.ecef_xyz_m()[0] / 1000.0,
-                    position_km[1] - station.ecef_xyz_m()[1] / 1000.0,
-                    position_km[2] - station.ecef_xyz_m()[2] / 1000.0,
-                ])
+                "Solar Irradiance (Way 2, Cones): {:.2} W/m²",
+                irradiance_w_per_m2
             );
+
+            for (station, angle_deg) in gs.iter().zip(elevation_angles_degrees.iter().copied()) {
+                println!(
+                    "Ground station \"{}\" -> {} Elevation: {:.2} degrees (Distance: {:.2} km)",
+                    station.name,
+                    if angle_deg > station.min_elevation_deg {
+                        "✅"
+                    } else {
+                        "❌"
+                    },
+                    angle_deg,
+                    pythag_3(&[
+                        position_km[0] - station.ecef_xyz_m()[0] / 1000.0,
+                        position_km[1] - station.ecef_xyz_m()[1] / 1000.0,
+                        position_km[2] - station.ecef_xyz_m()[2] / 1000.0,
+                    ])
+                );
+            }
+            println!();
         }
-        println!();
 
         let is_deorbited = elevation_km < 100.0;
-        if is_deorbited {
+        if is_deorbited && self.verbose {
             println!(
                 "Deorbit achieved at {:.2} days = {:.2} years since epoch = {}",
                 self.hours_since_epoch() / 24.0,
@@ -404,12 +1301,52 @@ impl SimulationRun {
             );
         }
 
+        let reboost = self.schedule_reboost(&time, mean_semi_major_axis_m);
+        let perigee_passes =
+            self.analyze_perigee_passes(&previous_time, &time, orbital_elements.period_minutes());
+        let undeployed = self.step_undeployed(&time, is_deorbited);
+        if let Some(undeployed) = &undeployed
+            && is_deorbited
+            && self.verbose
+        {
+            match undeployed.reentry_time {
+                Some(reentry_time) => println!(
+                    "Without deployment, reentry at {} ({:+.2} days)",
+                    reentry_time,
+                    (reentry_time - time).as_days()
+                ),
+                None => println!("Without deployment, still in orbit at the end of the run"),
+            }
+        }
+
+        let node_crossing =
+            self.detect_node_crossing(&time, &position_teme, orbital_elements.period_minutes());
+        let constellation = self.step_constellation(&time, &orbital_elements);
+
+        // Pass overlap: how many satellites (primary + constellation) each station sees right now.
+        let satellites_in_view_per_station = self
+            .initial
+            .ground_stations
+            .iter()
+            .enumerate()
+            .map(|(i, station)| {
+                std::iter::once(elevation_angles_degrees[i])
+                    .chain(constellation.iter().map(|m| m.elevation_angles_degrees[i]))
+                    .filter(|angle_deg| *angle_deg > station.min_elevation_deg)
+                    .count()
+            })
+            .collect::<Vec<_>>();
+
         // Advance the clock for the *next* call to step().
-        self.current_sim_time += satkit::Duration::from_hours(settings.step_interval_hours);
+        self.current_sim_time +=
+            satkit::Duration::from_hours(self.initial.simulation_settings.step_interval_hours);
 
         let simulation_state = SimulationStateAtStep {
             time,
             hours_since_epoch: self.hours_since_epoch(), // now points to the *next* tick
+            position_teme,
+            velocity_teme,
+            teme_to_itrf,
             position_itrf: [
                 position_itrf.itrf[0],
                 position_itrf.itrf[1],
@@ -427,9 +1364,39 @@ impl SimulationRun {
             irradiance_approx_w_per_m2,
             irradiance_w_per_m2,
             local_time_hours,
+            apparent_solar_time_hours,
+            equation_of_time_minutes,
+            ltan_hours,
+            ltan_drift_minutes,
+            node_crossing,
             is_deorbited,
+            attitude,
+            drag_coefficient,
+            drag_area_m2,
+            drag_bstar_scale: self.drag_bstar_scale,
+            deployment_events,
+            undeployed,
+            propulsion,
+            reboost,
+            perigee_passes,
+            uncertainty,
+            tle_epoch,
+            sun_projected_area_m2,
+            srp_force_body_n,
+            solar_array_power_watts,
+            orbit_average_power,
+            earth_orientation_mode,
+            orbital_elements,
+            equinoctial_elements,
+            mean_semi_major_axis_m,
+            constellation,
+            satellites_in_view_per_station,
         };
+        self.previous_step = Some((time, position_teme));
         self.latest_telemetry = Some(simulation_state.clone());
+        if let Some(history) = &mut self.pending_history {
+            history.push(simulation_state.clone());
+        }
         Ok(simulation_state)
     }
 }
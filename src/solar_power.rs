@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::attitude::{AttitudeSettings, compute_attitude};
+use crate::earth_orientation::qteme2itrf_with_mode;
+use crate::output_frames::teme_to_itrf_state;
+use crate::satellite_state::{calculate_sun_irradiance_received_w_per_m2, propagate_teme};
+
+/// Cell temperature at which `SolarArray::cell_efficiency` is specified. 28 °C is the usual
+/// reference for space cells measured under AM0 illumination.
+const REFERENCE_CELL_TEMPERATURE_C: f64 = 28.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum SolarArrayMounting {
+    /// Cells on a face of the bus, normal fixed in the body frame.
+    #[default]
+    BodyMounted,
+    /// Cells on a deployed wing, normal fixed in the body frame. Modelled exactly like
+    /// `BodyMounted` (deployed from the start of the run); set a lower
+    /// `SolarArray::operating_temperature_c` if the wing runs cooler.
+    Deployable,
+    /// A wing rotated about `SolarArray::orientation_body` to face the Sun as closely as it can.
+    SunTracking,
+}
+
+impl SolarArrayMounting {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SolarArrayMounting::BodyMounted => "Body-mounted",
+            SolarArrayMounting::Deployable => "Deployable",
+            SolarArrayMounting::SunTracking => "Sun-tracking (1-axis)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolarArray {
+    pub name: String,
+    pub mounting: SolarArrayMounting,
+
+    /// Active cell area.
+    pub area_m2: f64,
+
+    /// Conversion efficiency at 28 °C, as a fraction (e.g. 0.295 for triple-junction cells).
+    pub cell_efficiency: f64,
+
+    /// Relative change in efficiency per °C above 28 °C (e.g. -0.0025).
+    pub temperature_coefficient_per_c: f64,
+
+    /// Assumed cell temperature while generating power.
+    pub operating_temperature_c: f64,
+
+    /// Unit vector in the body frame: the cell normal for fixed arrays, or the drive axis for
+    /// sun-tracking arrays.
+    pub orientation_body: [f64; 3],
+}
+
+impl SolarArray {
+    pub fn new(
+        name: String,
+        mounting: SolarArrayMounting,
+        area_m2: f64,
+        cell_efficiency: f64,
+        temperature_coefficient_per_c: f64,
+        operating_temperature_c: f64,
+        orientation_body: [f64; 3],
+    ) -> Result<Self, String> {
+        if area_m2 <= 0.0 {
+            return Err("Array area must be > 0".into());
+        }
+        if !(0.0..=1.0).contains(&cell_efficiency) {
+            return Err("Cell efficiency must be within [0, 1]".into());
+        }
+        let norm = (orientation_body[0].powi(2)
+            + orientation_body[1].powi(2)
+            + orientation_body[2].powi(2))
+        .sqrt();
+        if norm < 1e-9 {
+            return Err("Array orientation must not be zero".into());
+        }
+        Ok(Self {
+            name,
+            mounting,
+            area_m2,
+            cell_efficiency,
+            temperature_coefficient_per_c,
+            operating_temperature_c,
+            orientation_body: orientation_body.map(|x| x / norm),
+        })
+    }
+
+    /// Efficiency at the operating temperature.
+    pub fn efficiency(&self) -> f64 {
+        let derating = 1.0
+            + self.temperature_coefficient_per_c
+                * (self.operating_temperature_c - REFERENCE_CELL_TEMPERATURE_C);
+        (self.cell_efficiency * derating).max(0.0)
+    }
+
+    /// Cosine of the Sun's incidence angle on the cells (0 if the cells face away).
+    pub fn incidence_cosine(&self, sun_body: &[f64; 3]) -> f64 {
+        let along = self.orientation_body[0] * sun_body[0]
+            + self.orientation_body[1] * sun_body[1]
+            + self.orientation_body[2] * sun_body[2];
+        match self.mounting {
+            SolarArrayMounting::BodyMounted | SolarArrayMounting::Deployable => along.max(0.0),
+            // Rotating about the axis removes every error except the Sun's elevation off
+            // the rotation plane.
+            SolarArrayMounting::SunTracking => (1.0 - along * along).max(0.0).sqrt(),
+        }
+    }
+
+    /// Electrical power generated, in W.
+    pub fn power_watts(&self, sun_body: &[f64; 3], irradiance_w_per_m2: f64) -> f64 {
+        irradiance_w_per_m2 * self.area_m2 * self.efficiency() * self.incidence_cosine(sun_body)
+    }
+}
+
+/// Total power of all arrays, in W.
+pub fn total_solar_power_watts(
+    arrays: &[SolarArray],
+    sun_body: &[f64; 3],
+    irradiance_w_per_m2: f64,
+) -> f64 {
+    arrays
+        .iter()
+        .map(|array| array.power_watts(sun_body, irradiance_w_per_m2))
+        .sum()
+}
+
+/// Power averaged over one orbit.
+#[derive(Debug, Clone)]
+pub struct OrbitAveragePower {
+    pub average_power_watts: f64,
+    /// Fraction of the orbit not in umbra.
+    pub sunlit_fraction: f64,
+}
+
+/// Average the arrays' power over one orbit starting at `start_time`.
+///
+/// The orbit is sampled with SGP4 (rather than averaged over the coarse simulation steps), so
+/// the result is meaningful whatever the step interval.
+pub fn orbit_average_power(
+    arrays: &[SolarArray],
+    attitude: &AttitudeSettings,
+    satkit_tle: &mut satkit::TLE,
+    start_time: &satkit::Instant,
+    period_minutes: f64,
+    attitude_start_time: &satkit::Instant,
+) -> anyhow::Result<OrbitAveragePower> {
+    const SAMPLES_PER_ORBIT: usize = 120;
+
+    let mut total_power_watts = 0.0;
+    let mut sunlit_samples = 0;
+    for i in 0..SAMPLES_PER_ORBIT {
+        let time = *start_time
+            + satkit::Duration::from_seconds(
+                period_minutes * 60.0 * i as f64 / SAMPLES_PER_ORBIT as f64,
+            );
+        let (position_teme, velocity_teme) = propagate_teme(satkit_tle, &time)?;
+        let position_itrf = teme_to_itrf_state(
+            &qteme2itrf_with_mode(&time).0,
+            &position_teme,
+            &velocity_teme,
+        )
+        .position;
+        let irradiance_w_per_m2 = calculate_sun_irradiance_received_w_per_m2(&position_itrf, &time);
+        if irradiance_w_per_m2 > 0.0 {
+            sunlit_samples += 1;
+        }
+
+        let sun_body = compute_attitude(
+            attitude,
+            &position_teme,
+            &velocity_teme,
+            &time,
+            attitude_start_time,
+        )
+        .sun_body;
+        total_power_watts += total_solar_power_watts(arrays, &sun_body, irradiance_w_per_m2);
+    }
+
+    Ok(OrbitAveragePower {
+        average_power_watts: total_power_watts / SAMPLES_PER_ORBIT as f64,
+        sunlit_fraction: sunlit_samples as f64 / SAMPLES_PER_ORBIT as f64,
+    })
+}
@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::attitude::{AttitudeState, compute_attitude};
+use crate::initial_state_model::{Satellite, TleData};
+use crate::satellite_state::propagate_teme;
+
+/// Speed of light, for converting irradiance to radiation pressure.
+const SPEED_OF_LIGHT_M_PER_S: f64 = 299_792_458.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum PanelKind {
+    /// A face of the spacecraft bus. Only its outward side is exposed.
+    #[default]
+    BodyFace,
+    /// A thin deployed panel (solar array, sail). Both sides are exposed.
+    DeployableArray,
+}
+
+impl PanelKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PanelKind::BodyFace => "Body face",
+            PanelKind::DeployableArray => "Deployable array",
+        }
+    }
+}
+
+/// A flat panel of the spacecraft's surface.
+///
+/// Self-shadowing between panels is not modelled, so the exposed area of concave shapes
+/// (e.g. a bus behind its arrays) is overestimated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Panel {
+    pub name: String,
+    pub kind: PanelKind,
+    pub area_m2: f64,
+
+    /// Outward unit normal in the body frame.
+    pub normal_body: [f64; 3],
+
+    /// Fraction of incident light reflected specularly.
+    pub specular_reflectivity: f64,
+    /// Fraction of incident light reflected diffusely. The rest is absorbed.
+    pub diffuse_reflectivity: f64,
+}
+
+impl Panel {
+    pub fn new(
+        name: String,
+        kind: PanelKind,
+        area_m2: f64,
+        normal_body: [f64; 3],
+        specular_reflectivity: f64,
+        diffuse_reflectivity: f64,
+    ) -> Result<Self, String> {
+        if area_m2 <= 0.0 {
+            return Err("Panel area must be > 0".into());
+        }
+        let norm =
+            (normal_body[0].powi(2) + normal_body[1].powi(2) + normal_body[2].powi(2)).sqrt();
+        if norm < 1e-9 {
+            return Err("Panel normal must not be zero".into());
+        }
+        if !(0.0..=1.0).contains(&specular_reflectivity)
+            || !(0.0..=1.0).contains(&diffuse_reflectivity)
+            || specular_reflectivity + diffuse_reflectivity > 1.0
+        {
+            return Err("Panel reflectivities must be within [0, 1] and sum to at most 1".into());
+        }
+        Ok(Self {
+            name,
+            kind,
+            area_m2,
+            normal_body: normal_body.map(|x| x / norm),
+            specular_reflectivity,
+            diffuse_reflectivity,
+        })
+    }
+
+    /// Cosine of the angle between the panel's exposed side and `direction_body` (a unit
+    /// vector), or 0 if the panel faces away.
+    fn exposure_cosine(&self, direction_body: &[f64; 3]) -> f64 {
+        let cos = self.normal_body[0] * direction_body[0]
+            + self.normal_body[1] * direction_body[1]
+            + self.normal_body[2] * direction_body[2];
+        match self.kind {
+            PanelKind::BodyFace => cos.max(0.0),
+            PanelKind::DeployableArray => cos.abs(),
+        }
+    }
+}
+
+/// Preset panel sets for the UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum PanelPreset {
+    CubeSat1U,
+    CubeSat3U,
+    CubeSat3UWithArrays,
+}
+
+impl PanelPreset {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PanelPreset::CubeSat1U => "1U CubeSat",
+            PanelPreset::CubeSat3U => "3U CubeSat",
+            PanelPreset::CubeSat3UWithArrays => "3U CubeSat + 2 deployed arrays",
+        }
+    }
+
+    /// Panels of the preset. 3U CubeSats have their long axis along body Z.
+    pub fn panels(&self) -> Vec<Panel> {
+        // Typical values for solar cells / anodized aluminium.
+        let (specular, diffuse) = (0.05, 0.2);
+        let face = |name: &str, area_m2: f64, normal_body: [f64; 3]| Panel {
+            name: name.to_string(),
+            kind: PanelKind::BodyFace,
+            area_m2,
+            normal_body,
+            specular_reflectivity: specular,
+            diffuse_reflectivity: diffuse,
+        };
+        let box_faces = |side_area_m2: f64, end_area_m2: f64| {
+            vec![
+                face("+X", side_area_m2, [1.0, 0.0, 0.0]),
+                face("-X", side_area_m2, [-1.0, 0.0, 0.0]),
+                face("+Y", side_area_m2, [0.0, 1.0, 0.0]),
+                face("-Y", side_area_m2, [0.0, -1.0, 0.0]),
+                face("+Z", end_area_m2, [0.0, 0.0, 1.0]),
+                face("-Z", end_area_m2, [0.0, 0.0, -1.0]),
+            ]
+        };
+
+        match self {
+            PanelPreset::CubeSat1U => box_faces(0.01, 0.01),
+            PanelPreset::CubeSat3U => box_faces(0.03, 0.01),
+            PanelPreset::CubeSat3UWithArrays => {
+                let mut panels = box_faces(0.03, 0.01);
+                for name in ["Array +Y", "Array -Y"] {
+                    panels.push(Panel {
+                        name: name.to_string(),
+                        kind: PanelKind::DeployableArray,
+                        area_m2: 0.03,
+                        normal_body: [0.0, 0.0, -1.0],
+                        specular_reflectivity: specular,
+                        diffuse_reflectivity: diffuse,
+                    });
+                }
+                panels
+            }
+        }
+    }
+}
+
+/// Area projected onto a plane perpendicular to `direction_body` (a unit vector), in m².
+pub fn projected_area_m2(panels: &[Panel], direction_body: &[f64; 3]) -> f64 {
+    panels
+        .iter()
+        .map(|panel| panel.area_m2 * panel.exposure_cosine(direction_body))
+        .sum()
+}
+
+/// Solar radiation pressure force on the panels, in N, in the body frame.
+///
+/// Standard flat-plate model: absorbed and specularly reflected light push along the incoming
+/// light and along the normal respectively; diffuse (Lambertian) reflection adds 2/3 of its
+/// momentum along the normal.
+pub fn srp_force_body_n(
+    panels: &[Panel],
+    sun_body: &[f64; 3],
+    irradiance_w_per_m2: f64,
+) -> [f64; 3] {
+    let pressure = irradiance_w_per_m2 / SPEED_OF_LIGHT_M_PER_S;
+    let mut force = [0.0; 3];
+    for panel in panels {
+        let cos = panel.exposure_cosine(sun_body);
+        if cos <= 0.0 {
+            continue;
+        }
+        // Normal on the lit side (deployable arrays may be lit from behind).
+        let dot = panel.normal_body[0] * sun_body[0]
+            + panel.normal_body[1] * sun_body[1]
+            + panel.normal_body[2] * sun_body[2];
+        let lit_normal = panel.normal_body.map(|x| x * dot.signum());
+
+        let along_sun = 1.0 - panel.specular_reflectivity;
+        let along_normal =
+            2.0 * (panel.specular_reflectivity * cos + panel.diffuse_reflectivity / 3.0);
+        for axis in 0..3 {
+            force[axis] -= pressure
+                * panel.area_m2
+                * cos
+                * (along_sun * sun_body[axis] + along_normal * lit_normal[axis]);
+        }
+    }
+    force
+}
+
+/// Drag area to use at a step: projected against the ram direction if the satellite has a
+/// panel model, otherwise the constant `Satellite::drag_area_m2`.
+pub fn drag_area_m2(satellite: &Satellite, attitude: &AttitudeState) -> f64 {
+    if satellite.panels.is_empty() {
+        satellite.drag_area_m2
+    } else {
+        projected_area_m2(&satellite.panels, &attitude.velocity_body)
+    }
+}
+
+/// Factor to scale a TLE's B* by so SGP4 decays the orbit according to the panel model.
+///
+/// B* is fitted to the satellite's past decay, which we take to correspond to
+/// `Satellite::drag_area_m2`. The panel model's drag area is averaged over one orbit from the
+/// TLE epoch in the configured attitude, and B* is scaled by its ratio to that reference area.
+/// Returns 1 when there is no panel model.
+pub fn bstar_scale_from_panels(satellite: &Satellite, tle: &TleData) -> anyhow::Result<f64> {
+    if satellite.panels.is_empty() {
+        return Ok(1.0);
+    }
+    if satellite.drag_area_m2 <= 0.0 {
+        return Err(anyhow::anyhow!(
+            "Drag area must be > 0 to scale B* by the panel model"
+        ));
+    }
+
+    const SAMPLES_PER_ORBIT: usize = 72;
+    let period_seconds = 86_400.0 / tle.mean_motion;
+    let mut satkit_tle = tle.to_satkit_tle();
+    let mut total_area_m2 = 0.0;
+    for i in 0..SAMPLES_PER_ORBIT {
+        let time = tle.epoch
+            + satkit::Duration::from_seconds(period_seconds * i as f64 / SAMPLES_PER_ORBIT as f64);
+        let (position_teme, velocity_teme) = propagate_teme(&mut satkit_tle, &time)?;
+        let attitude = compute_attitude(
+            &satellite.attitude,
+            &position_teme,
+            &velocity_teme,
+            &time,
+            &tle.epoch,
+        );
+        total_area_m2 += drag_area_m2(satellite, &attitude);
+    }
+    Ok(total_area_m2 / SAMPLES_PER_ORBIT as f64 / satellite.drag_area_m2)
+}
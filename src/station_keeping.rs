@@ -0,0 +1,112 @@
+use satkit::Instant;
+use satkit::consts::{EARTH_RADIUS, MU_EARTH};
+use serde::{Deserialize, Serialize};
+
+use crate::propulsion::{ScheduledBurn, ThrustDirection, Thruster};
+
+/// Settings of the altitude-maintenance autopilot.
+///
+/// The controller watches the orbit-average altitude (mean semi-major axis above the equatorial
+/// radius). Once it drops below `target_altitude_km - deadband_km`, a reboost is added to the
+/// burn schedule, starting at that step and sized to raise the orbit to
+/// `target_altitude_km + deadband_km`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StationKeeping {
+    pub target_altitude_km: f64,
+    pub deadband_km: f64,
+    /// When the controller is switched on, and for how long.
+    pub start_days_since_epoch: f64,
+    pub duration_days: f64,
+}
+
+/// Name prefix of the burns scheduled by the controller.
+const REBOOST_NAME_PREFIX: &str = "Station-keeping reboost";
+
+impl StationKeeping {
+    pub fn new(
+        target_altitude_km: f64,
+        deadband_km: f64,
+        start_days_since_epoch: f64,
+        duration_days: f64,
+    ) -> Result<Self, String> {
+        if target_altitude_km <= 0.0 {
+            return Err("Station-keeping target altitude must be > 0".into());
+        }
+        if deadband_km <= 0.0 {
+            return Err("Station-keeping deadband must be > 0".into());
+        }
+        if start_days_since_epoch < 0.0 {
+            return Err("Station-keeping start must be >= 0 days".into());
+        }
+        if duration_days <= 0.0 {
+            return Err("Station-keeping duration must be > 0".into());
+        }
+        Ok(Self {
+            target_altitude_km,
+            deadband_km,
+            start_days_since_epoch,
+            duration_days,
+        })
+    }
+
+    pub fn is_active(&self, days_since_epoch: f64) -> bool {
+        days_since_epoch >= self.start_days_since_epoch
+            && days_since_epoch < self.start_days_since_epoch + self.duration_days
+    }
+
+    /// The reboost to schedule at `time`, if the mean altitude has left the deadband.
+    ///
+    /// The burn is along-velocity, lasting long enough to give the delta-v of a slow spiral
+    /// between circular orbits at the current and the upper-band semi-major axis.
+    pub fn reboost(
+        &self,
+        thruster: &Thruster,
+        mass_kg: f64,
+        mean_semi_major_axis_m: f64,
+        time: &Instant,
+        epoch: &Instant,
+        reboost_number: u32,
+    ) -> Option<Reboost> {
+        let days_since_epoch = (*time - *epoch).as_days();
+        let mean_altitude_km = (mean_semi_major_axis_m - EARTH_RADIUS) / 1000.0;
+        if !self.is_active(days_since_epoch)
+            || mean_altitude_km >= self.target_altitude_km - self.deadband_km
+        {
+            return None;
+        }
+
+        let goal_semi_major_axis_m =
+            EARTH_RADIUS + (self.target_altitude_km + self.deadband_km) * 1000.0;
+        let delta_v_m_per_s =
+            (MU_EARTH / mean_semi_major_axis_m).sqrt() - (MU_EARTH / goal_semi_major_axis_m).sqrt();
+        let propellant_kg =
+            mass_kg * (1.0 - (-delta_v_m_per_s / thruster.exhaust_velocity_m_per_s()).exp());
+        let duration_minutes = propellant_kg / thruster.mass_flow_kg_per_s() / 60.0;
+
+        let burn = ScheduledBurn::new(
+            format!("{REBOOST_NAME_PREFIX} {reboost_number}"),
+            days_since_epoch,
+            duration_minutes,
+            ThrustDirection::AlongVelocity,
+        )
+        .ok()?;
+        Some(Reboost {
+            burn,
+            mean_altitude_km,
+            planned_delta_v_m_per_s: delta_v_m_per_s,
+        })
+    }
+}
+
+/// A reboost scheduled by the station-keeping controller.
+#[derive(Debug, Clone)]
+pub struct Reboost {
+    pub burn: ScheduledBurn,
+    /// Mean altitude that triggered it.
+    pub mean_altitude_km: f64,
+    pub planned_delta_v_m_per_s: f64,
+}
+
+pub fn is_reboost_burn(name: &str) -> bool {
+    name.starts_with(REBOOST_NAME_PREFIX)
+}
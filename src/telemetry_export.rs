@@ -0,0 +1,515 @@
+use std::fmt::Write as _;
+
+use crate::output_frames::OutputFrame;
+use crate::satellite_state::SimulationStateAtStep;
+
+/// One column of the telemetry CSV export.
+struct TelemetryColumn {
+    header: &'static str,
+    value: fn(&SimulationStateAtStep) -> String,
+}
+
+const TELEMETRY_COLUMNS: &[TelemetryColumn] = &[
+    TelemetryColumn {
+        header: "speed_m_per_s",
+        value: |t| format!("{:.6}", t.speed_m_per_s),
+    },
+    TelemetryColumn {
+        header: "elevation_km",
+        value: |t| format!("{:.6}", t.elevation_km),
+    },
+    TelemetryColumn {
+        header: "drag_power_watts",
+        value: |t| format!("{:.6}", t.drag_power_watts),
+    },
+    TelemetryColumn {
+        header: "irradiance_w_per_m2",
+        value: |t| format!("{:.3}", t.irradiance_w_per_m2),
+    },
+    TelemetryColumn {
+        header: "local_time_hours",
+        value: |t| format!("{:.6}", t.local_time_hours),
+    },
+    TelemetryColumn {
+        header: "apparent_solar_time_hours",
+        value: |t| format!("{:.6}", t.apparent_solar_time_hours),
+    },
+    TelemetryColumn {
+        header: "equation_of_time_minutes",
+        value: |t| format!("{:.4}", t.equation_of_time_minutes),
+    },
+    TelemetryColumn {
+        header: "ltan_hours",
+        value: |t| format!("{:.6}", t.ltan_hours),
+    },
+    TelemetryColumn {
+        header: "ltdn_hours",
+        value: |t| format!("{:.6}", (t.ltan_hours + 12.0) % 24.0),
+    },
+    TelemetryColumn {
+        header: "ltan_drift_minutes",
+        value: |t| format!("{:.4}", t.ltan_drift_minutes),
+    },
+    TelemetryColumn {
+        header: "node_crossing",
+        value: |t| {
+            t.node_crossing
+                .as_ref()
+                .map(|c| c.kind.label().to_string())
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "node_crossing_time_utc",
+        value: |t| {
+            t.node_crossing
+                .as_ref()
+                .map(|c| c.time.as_iso8601())
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "node_crossing_orbit_number",
+        value: |t| {
+            t.node_crossing
+                .as_ref()
+                .map(|c| c.orbit_number.to_string())
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "node_crossing_solar_time_hours",
+        value: |t| {
+            t.node_crossing
+                .as_ref()
+                .map(|c| format!("{:.6}", c.apparent_solar_time_hours))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "semi_major_axis_km",
+        value: |t| format!("{:.6}", t.orbital_elements.semi_major_axis_m / 1000.0),
+    },
+    TelemetryColumn {
+        header: "mean_semi_major_axis_km",
+        value: |t| format!("{:.6}", t.mean_semi_major_axis_m / 1000.0),
+    },
+    TelemetryColumn {
+        header: "eccentricity",
+        value: |t| format!("{:.8}", t.orbital_elements.eccentricity),
+    },
+    TelemetryColumn {
+        header: "inclination_deg",
+        value: |t| format!("{:.6}", t.orbital_elements.inclination_deg),
+    },
+    TelemetryColumn {
+        header: "raan_deg",
+        value: |t| format!("{:.6}", t.orbital_elements.raan_deg),
+    },
+    TelemetryColumn {
+        header: "arg_of_perigee_deg",
+        value: |t| format!("{:.6}", t.orbital_elements.arg_of_perigee_deg),
+    },
+    TelemetryColumn {
+        header: "true_anomaly_deg",
+        value: |t| format!("{:.6}", t.orbital_elements.true_anomaly_deg),
+    },
+    TelemetryColumn {
+        header: "mean_anomaly_deg",
+        value: |t| format!("{:.6}", t.orbital_elements.mean_anomaly_deg),
+    },
+    TelemetryColumn {
+        header: "apogee_altitude_km",
+        value: |t| format!("{:.6}", t.orbital_elements.apogee_altitude_km()),
+    },
+    TelemetryColumn {
+        header: "perigee_altitude_km",
+        value: |t| format!("{:.6}", t.orbital_elements.perigee_altitude_km()),
+    },
+    TelemetryColumn {
+        header: "period_minutes",
+        value: |t| format!("{:.6}", t.orbital_elements.period_minutes()),
+    },
+    TelemetryColumn {
+        header: "equinoctial_h",
+        value: |t| format!("{:.8}", t.equinoctial_elements.h),
+    },
+    TelemetryColumn {
+        header: "equinoctial_k",
+        value: |t| format!("{:.8}", t.equinoctial_elements.k),
+    },
+    TelemetryColumn {
+        header: "equinoctial_p",
+        value: |t| format!("{:.8}", t.equinoctial_elements.p),
+    },
+    TelemetryColumn {
+        header: "equinoctial_q",
+        value: |t| format!("{:.8}", t.equinoctial_elements.q),
+    },
+    TelemetryColumn {
+        header: "mean_longitude_deg",
+        value: |t| format!("{:.6}", t.equinoctial_elements.mean_longitude_deg),
+    },
+    TelemetryColumn {
+        header: "attitude_mode",
+        value: |t| t.attitude.mode.label().to_string(),
+    },
+    TelemetryColumn {
+        header: "attitude_q_w",
+        value: |t| format!("{:.8}", t.attitude.quaternion_wxyz()[0]),
+    },
+    TelemetryColumn {
+        header: "attitude_q_x",
+        value: |t| format!("{:.8}", t.attitude.quaternion_wxyz()[1]),
+    },
+    TelemetryColumn {
+        header: "attitude_q_y",
+        value: |t| format!("{:.8}", t.attitude.quaternion_wxyz()[2]),
+    },
+    TelemetryColumn {
+        header: "attitude_q_z",
+        value: |t| format!("{:.8}", t.attitude.quaternion_wxyz()[3]),
+    },
+    TelemetryColumn {
+        header: "sun_body_x",
+        value: |t| format!("{:.6}", t.attitude.sun_body[0]),
+    },
+    TelemetryColumn {
+        header: "sun_body_y",
+        value: |t| format!("{:.6}", t.attitude.sun_body[1]),
+    },
+    TelemetryColumn {
+        header: "sun_body_z",
+        value: |t| format!("{:.6}", t.attitude.sun_body[2]),
+    },
+    TelemetryColumn {
+        header: "nadir_body_x",
+        value: |t| format!("{:.6}", t.attitude.nadir_body[0]),
+    },
+    TelemetryColumn {
+        header: "nadir_body_y",
+        value: |t| format!("{:.6}", t.attitude.nadir_body[1]),
+    },
+    TelemetryColumn {
+        header: "nadir_body_z",
+        value: |t| format!("{:.6}", t.attitude.nadir_body[2]),
+    },
+    TelemetryColumn {
+        header: "velocity_body_x",
+        value: |t| format!("{:.6}", t.attitude.velocity_body[0]),
+    },
+    TelemetryColumn {
+        header: "velocity_body_y",
+        value: |t| format!("{:.6}", t.attitude.velocity_body[1]),
+    },
+    TelemetryColumn {
+        header: "velocity_body_z",
+        value: |t| format!("{:.6}", t.attitude.velocity_body[2]),
+    },
+    TelemetryColumn {
+        header: "drag_coefficient",
+        value: |t| format!("{:.4}", t.drag_coefficient),
+    },
+    TelemetryColumn {
+        header: "drag_area_m2",
+        value: |t| format!("{:.6}", t.drag_area_m2),
+    },
+    TelemetryColumn {
+        header: "drag_bstar_scale",
+        value: |t| format!("{:.6}", t.drag_bstar_scale),
+    },
+    TelemetryColumn {
+        header: "deployment_events",
+        value: |t| t.deployment_events.join(";"),
+    },
+    TelemetryColumn {
+        header: "undeployed_elevation_km",
+        value: |t| {
+            t.undeployed
+                .as_ref()
+                .map(|u| format!("{:.6}", u.elevation_km))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "undeployed_reentry_utc",
+        value: |t| {
+            t.undeployed
+                .as_ref()
+                .and_then(|u| u.reentry_time)
+                .map(|time| time.as_iso8601())
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "mass_kg",
+        value: |t| {
+            t.propulsion
+                .as_ref()
+                .map(|p| format!("{:.6}", p.mass_kg))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "propellant_remaining_kg",
+        value: |t| {
+            t.propulsion
+                .as_ref()
+                .map(|p| format!("{:.6}", p.propellant_remaining_kg))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "burn_delta_v_m_per_s",
+        value: |t| {
+            t.propulsion
+                .as_ref()
+                .map(|p| {
+                    let delta_v: f64 = p.burn_segments.iter().map(|s| s.delta_v_m_per_s).sum();
+                    format!("{delta_v:.6}")
+                })
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "total_burn_delta_v_m_per_s",
+        value: |t| {
+            t.propulsion
+                .as_ref()
+                .map(|p| format!("{:.6}", p.total_delta_v_m_per_s))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "burns",
+        value: |t| {
+            t.propulsion
+                .as_ref()
+                .map(|p| {
+                    p.burn_segments
+                        .iter()
+                        .map(|s| s.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(";")
+                })
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "reboost_scheduled",
+        value: |t| {
+            t.reboost
+                .as_ref()
+                .map(|r| r.burn.name.clone())
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "reboost_planned_delta_v_m_per_s",
+        value: |t| {
+            t.reboost
+                .as_ref()
+                .map(|r| format!("{:.6}", r.planned_delta_v_m_per_s))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "perigee_passes",
+        value: |t| t.perigee_passes.len().to_string(),
+    },
+    TelemetryColumn {
+        header: "perigee_peak_dynamic_pressure_pa",
+        value: |t| {
+            t.perigee_passes
+                .iter()
+                .map(|p| p.peak_dynamic_pressure_pa)
+                .reduce(f64::max)
+                .map(|q| format!("{q:.6e}"))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "perigee_peak_heat_flux_w_per_m2",
+        value: |t| {
+            t.perigee_passes
+                .iter()
+                .map(|p| p.peak_heat_flux_w_per_m2)
+                .reduce(f64::max)
+                .map(|q| format!("{q:.6e}"))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "perigee_heat_load_j_per_m2",
+        value: |t| {
+            t.perigee_passes
+                .iter()
+                .map(|p| p.heat_load_j_per_m2)
+                .reduce(|a, b| a + b)
+                .map(|q| format!("{q:.6e}"))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "perigee_delta_v_lost_m_per_s",
+        value: |t| {
+            t.perigee_passes
+                .iter()
+                .map(|p| p.delta_v_lost_m_per_s)
+                .reduce(|a, b| a + b)
+                .map(|dv| format!("{dv:.6}"))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "perigee_apogee_decay_km",
+        value: |t| {
+            t.perigee_passes
+                .iter()
+                .map(|p| p.apogee_decay_km())
+                .reduce(|a, b| a + b)
+                .map(|km| format!("{km:.6}"))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "radial_sigma_m",
+        value: |t| {
+            t.uncertainty
+                .map(|u| format!("{:.3}", u.radial_sigma_m))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "along_track_sigma_m",
+        value: |t| {
+            t.uncertainty
+                .map(|u| format!("{:.3}", u.along_track_sigma_m))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "cross_track_sigma_m",
+        value: |t| {
+            t.uncertainty
+                .map(|u| format!("{:.3}", u.cross_track_sigma_m))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "tle_epoch_utc",
+        value: |t| t.tle_epoch.as_iso8601(),
+    },
+    TelemetryColumn {
+        header: "sun_projected_area_m2",
+        value: |t| format!("{:.6}", t.sun_projected_area_m2),
+    },
+    TelemetryColumn {
+        header: "srp_force_body_x_n",
+        value: |t| format!("{:.6e}", t.srp_force_body_n[0]),
+    },
+    TelemetryColumn {
+        header: "srp_force_body_y_n",
+        value: |t| format!("{:.6e}", t.srp_force_body_n[1]),
+    },
+    TelemetryColumn {
+        header: "srp_force_body_z_n",
+        value: |t| format!("{:.6e}", t.srp_force_body_n[2]),
+    },
+    TelemetryColumn {
+        header: "solar_power_watts",
+        value: |t| format!("{:.6}", t.solar_array_power_watts.iter().sum::<f64>()),
+    },
+    TelemetryColumn {
+        header: "orbit_average_power_watts",
+        value: |t| {
+            t.orbit_average_power
+                .as_ref()
+                .map(|avg| format!("{:.6}", avg.average_power_watts))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "orbit_sunlit_fraction",
+        value: |t| {
+            t.orbit_average_power
+                .as_ref()
+                .map(|avg| format!("{:.6}", avg.sunlit_fraction))
+                .unwrap_or_default()
+        },
+    },
+    TelemetryColumn {
+        header: "earth_orientation_mode",
+        value: |t| t.earth_orientation_mode.label().to_string(),
+    },
+    TelemetryColumn {
+        header: "is_deorbited",
+        value: |t| t.is_deorbited.to_string(),
+    },
+];
+
+/// Render the telemetry history as CSV, one row per step.
+///
+/// Each frame in `frames` contributes six position/velocity columns (named
+/// `<frame>_<component>`) after the timestamp. Ground station elevation angles are appended as
+/// `elevation_angle_deg_gs<N>` columns.
+pub fn telemetry_history_to_csv(
+    history: &[SimulationStateAtStep],
+    frames: &[OutputFrame],
+) -> String {
+    let station_count = history
+        .first()
+        .map(|t| t.elevation_angles_degrees.len())
+        .unwrap_or(0);
+
+    let mut csv = String::new();
+    let frame_headers = frames.iter().flat_map(|frame| {
+        let (position_labels, velocity_labels) = frame.component_labels();
+        position_labels
+            .into_iter()
+            .chain(velocity_labels)
+            .map(|label| format!("{}_{}", frame.key(), label))
+    });
+    let headers = std::iter::once("time_utc".to_string())
+        .chain(frame_headers)
+        .chain(TELEMETRY_COLUMNS.iter().map(|c| c.header.to_string()))
+        .chain((1..=station_count).map(|i| format!("elevation_angle_deg_gs{i}")))
+        .collect::<Vec<_>>();
+    let _ = writeln!(csv, "{}", headers.join(","));
+
+    for t in history {
+        let frame_values = frames.iter().flat_map(|frame| {
+            let state = frame.convert(t);
+            state
+                .position
+                .into_iter()
+                .chain(state.velocity)
+                .map(|value| format!("{:.6}", value))
+        });
+        let row = std::iter::once(t.time.as_iso8601())
+            .chain(frame_values)
+            .chain(TELEMETRY_COLUMNS.iter().map(|c| (c.value)(t)))
+            .chain(
+                t.elevation_angles_degrees
+                    .iter()
+                    .map(|angle| format!("{:.6}", angle)),
+            )
+            .collect::<Vec<_>>();
+        let _ = writeln!(csv, "{}", row.join(","));
+    }
+    csv
+}
+
+/// Write the telemetry history to a CSV file at `path`, with position/velocity in each of
+/// `frames`.
+pub fn write_telemetry_csv(
+    path: &str,
+    history: &[SimulationStateAtStep],
+    frames: &[OutputFrame],
+) -> anyhow::Result<()> {
+    if history.is_empty() {
+        return Err(anyhow::anyhow!("No telemetry to export"));
+    }
+    std::fs::write(path, telemetry_history_to_csv(history, frames))?;
+    Ok(())
+}
@@ -0,0 +1,287 @@
+use satkit::Instant;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::initial_state_model::TleData;
+use crate::orbital_elements::rtn_basis;
+use crate::satellite_state::{propagate_teme, pythag_3};
+
+/// Which element set of a history is propagated at a given time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum TleSelection {
+    /// The set whose epoch is closest to the time, before or after.
+    #[default]
+    Nearest,
+    /// The latest set with an epoch at or before the time, as an operator would have had it.
+    /// Times before the first epoch use the first set.
+    NearestPreceding,
+}
+
+impl TleSelection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TleSelection::Nearest => "Nearest epoch",
+            TleSelection::NearestPreceding => "Nearest preceding epoch",
+        }
+    }
+}
+
+/// Successive element sets of one satellite, in epoch order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TleHistory {
+    pub sets: Vec<TleData>,
+    pub selection: TleSelection,
+}
+
+impl TleHistory {
+    /// Sort `sets` by epoch. Of several sets with the same epoch, the last one given is kept.
+    pub fn new(mut sets: Vec<TleData>, selection: TleSelection) -> Result<Self, String> {
+        let Some(first) = sets.first() else {
+            return Err("A TLE history needs at least one element set".into());
+        };
+        let sat_num = first.sat_num;
+        if let Some(other) = sets.iter().find(|tle| tle.sat_num != sat_num) {
+            return Err(format!(
+                "All element sets must be of one satellite (found NORAD ids {sat_num} and {})",
+                other.sat_num
+            ));
+        }
+        sets.reverse();
+        sets.sort_by(|a, b| {
+            a.epoch
+                .partial_cmp(&b.epoch)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        sets.dedup_by(|later, earlier| later.epoch == earlier.epoch);
+        Ok(Self { sets, selection })
+    }
+
+    /// Index of the set to propagate at `time`.
+    pub fn select(&self, time: &Instant) -> usize {
+        match self.selection {
+            TleSelection::Nearest => self
+                .sets
+                .iter()
+                .map(|tle| (*time - tle.epoch).as_seconds().abs())
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map_or(0, |(index, _)| index),
+            TleSelection::NearestPreceding => self
+                .sets
+                .iter()
+                .rposition(|tle| tle.epoch <= *time)
+                .unwrap_or(0),
+        }
+    }
+
+    /// Position jumps between consecutive sets (see `TleDiscontinuity`).
+    pub fn discontinuities(&self) -> anyhow::Result<Vec<TleDiscontinuity>> {
+        self.sets
+            .windows(2)
+            .map(|pair| {
+                let position_rtn_m =
+                    offset_rtn_m(&mut pair[0].to_satkit_tle(), &mut pair[1].to_satkit_tle())?;
+                Ok(TleDiscontinuity {
+                    previous_epoch: pair[0].epoch,
+                    epoch: pair[1].epoch,
+                    position_m: pythag_3(&position_rtn_m),
+                    position_rtn_m,
+                })
+            })
+            .collect()
+    }
+
+    /// Propagate every set forward and compare it against each later set, up to
+    /// `settings.max_age_days` ahead, at that set's epoch.
+    ///
+    /// Later sets are treated as truth, so the curves include their own fit error, which
+    /// dominates at short prediction ages.
+    pub fn validate_accuracy(
+        &self,
+        settings: &AccuracyValidationSettings,
+    ) -> anyhow::Result<AccuracyValidation> {
+        // Initialise SGP4 once per set rather than once per pair.
+        let mut satkit_tles = self
+            .sets
+            .iter()
+            .map(TleData::to_satkit_tle)
+            .collect::<Vec<_>>();
+        let mut errors = Vec::new();
+        let mut failed_pairs = Vec::new();
+        for index in 0..satkit_tles.len() {
+            let (earlier, later) = satkit_tles.split_at_mut(index + 1);
+            let predictor = &mut earlier[index];
+            for truth in later {
+                let age_days = (truth.epoch - predictor.epoch).as_days();
+                if age_days > settings.max_age_days {
+                    break;
+                }
+                match offset_rtn_m(predictor, truth) {
+                    Ok(position_rtn_m) => errors.push(PredictionError {
+                        age_days,
+                        position_rtn_m,
+                    }),
+                    Err(e) => failed_pairs.push((predictor.epoch, truth.epoch, e.to_string())),
+                }
+            }
+        }
+
+        let bin_count = (settings.max_age_days / settings.bin_days).ceil() as usize;
+        let mut sums = vec![([0.0; 3], 0_usize); bin_count];
+        for error in &errors {
+            let bin = ((error.age_days / settings.bin_days).ceil() as usize).clamp(1, bin_count);
+            let (sum, count) = &mut sums[bin - 1];
+            for (sum, component) in sum.iter_mut().zip(error.position_rtn_m) {
+                *sum += component.powi(2);
+            }
+            *count += 1;
+        }
+        let bins = sums
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (_, count))| *count > 0)
+            .map(|(index, (sum, count))| ErrorGrowthBin {
+                max_age_days: ((index + 1) as f64 * settings.bin_days).min(settings.max_age_days),
+                samples: count,
+                rms_rtn_m: sum.map(|s| (s / count as f64).sqrt()),
+            })
+            .collect::<Vec<_>>();
+
+        let max_age_within_tolerance_days = bins
+            .iter()
+            .take_while(|bin| bin.rms_m() <= settings.tolerance_m)
+            .last()
+            .map(|bin| bin.max_age_days);
+        Ok(AccuracyValidation {
+            errors,
+            failed_pairs,
+            bins,
+            tolerance_m: settings.tolerance_m,
+            max_age_within_tolerance_days,
+        })
+    }
+}
+
+/// Position of `later` at its epoch minus the prediction of `earlier`, along the
+/// radial/transverse/normal axes of `later`.
+fn offset_rtn_m(earlier: &mut satkit::TLE, later: &mut satkit::TLE) -> anyhow::Result<[f64; 3]> {
+    let epoch = later.epoch;
+    let (predicted, _) = propagate_teme(earlier, &epoch)?;
+    let (position, velocity) = propagate_teme(later, &epoch)?;
+    Ok(rtn_basis(&position, &velocity).map(|unit| {
+        (0..3)
+            .map(|axis| unit[axis] * (position[axis] - predicted[axis]))
+            .sum::<f64>()
+    }))
+}
+
+/// Difference between an element set and its predecessor propagated to its epoch: a measure
+/// of how far the predecessor had drifted, and of the jump a run sees when it switches sets.
+#[derive(Debug, Clone)]
+pub struct TleDiscontinuity {
+    pub previous_epoch: Instant,
+    pub epoch: Instant,
+    pub position_m: f64,
+    /// Along the radial/transverse/normal axes of the newer set.
+    pub position_rtn_m: [f64; 3],
+}
+
+#[derive(Debug, Clone)]
+pub struct AccuracyValidationSettings {
+    /// Longest prediction age to evaluate.
+    pub max_age_days: f64,
+    /// Width of the age bins of the error-growth curves.
+    pub bin_days: f64,
+    /// Total RMS position error that pass scheduling can tolerate.
+    pub tolerance_m: f64,
+}
+
+impl AccuracyValidationSettings {
+    pub fn new(max_age_days: f64, bin_days: f64, tolerance_m: f64) -> Result<Self, String> {
+        if max_age_days <= 0.0 || bin_days <= 0.0 {
+            return Err("Maximum age and bin width must be > 0".into());
+        }
+        if tolerance_m <= 0.0 {
+            return Err("Tolerance must be > 0".into());
+        }
+        Ok(Self {
+            max_age_days,
+            bin_days,
+            tolerance_m,
+        })
+    }
+}
+
+/// Error of one set's prediction at the epoch of a later set: the later set minus the
+/// prediction, as for `TleDiscontinuity`.
+#[derive(Debug, Clone)]
+pub struct PredictionError {
+    pub age_days: f64,
+    pub position_rtn_m: [f64; 3],
+}
+
+/// RMS prediction error over the ages `(max_age_days - bin_days, max_age_days]`.
+#[derive(Debug, Clone)]
+pub struct ErrorGrowthBin {
+    pub max_age_days: f64,
+    pub samples: usize,
+    pub rms_rtn_m: [f64; 3],
+}
+
+impl ErrorGrowthBin {
+    /// Total RMS position error.
+    pub fn rms_m(&self) -> f64 {
+        pythag_3(&self.rms_rtn_m)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AccuracyValidation {
+    pub errors: Vec<PredictionError>,
+    /// Predictor and truth epochs of the pairs that failed to propagate, with the error.
+    pub failed_pairs: Vec<(Instant, Instant, String)>,
+    /// Only bins with samples.
+    pub bins: Vec<ErrorGrowthBin>,
+    /// `AccuracyValidationSettings::tolerance_m` the validation was run with.
+    pub tolerance_m: f64,
+    /// Upper edge of the last bin before the RMS error first exceeds the tolerance: how old a
+    /// TLE may get before it needs replacing. `None` when even the first bin exceeds it.
+    pub max_age_within_tolerance_days: Option<f64>,
+}
+
+/// Parse element sets in two-line or three-line (name line first) format, as downloaded from
+/// Space-Track, Celestrak or satdb. Blank lines are ignored.
+pub fn parse_tle_sets(text: &str) -> Result<Vec<TleData>, String> {
+    let lines = text
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>();
+    let mut sets = Vec::new();
+    let mut name: Option<&str> = None;
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        if line.starts_with("1 ") {
+            let Some(line2) = lines.get(index + 1).filter(|l| l.starts_with("2 ")) else {
+                return Err(format!("Line 1 without a line 2: '{line}'"));
+            };
+            let mut tle = satkit::TLE::load_2line(line, line2)
+                .map_err(|e| format!("Invalid element set '{line}': {e}"))?;
+            if let Some(name) = name.take() {
+                tle.name = name.trim_start_matches("0 ").trim().to_string();
+            }
+            sets.push(TleData::from_satkit_tle(&tle));
+            index += 2;
+        } else if line.starts_with("2 ") {
+            return Err(format!("Line 2 without a line 1: '{line}'"));
+        } else {
+            name = Some(line);
+            index += 1;
+        }
+    }
+    if sets.is_empty() {
+        return Err("No element sets found".into());
+    }
+    Ok(sets)
+}
@@ -1,17 +1,36 @@
 // ui_egui.rs
 use crate::{
-    initial_state_model::{InitialSimulationState, TleData},
-    satellite_state::{SimulationRun, SimulationStateAtStep},
+    attitude::AttitudeMode,
+    collision_avoidance::AvoidanceResult,
+    collision_probability::{CollisionProbability, ConjunctionDataMessage},
+    conjunction::ScreeningResult,
+    constellation::{
+        SeparationDirection, WalkerPatternKind, generate_rideshare_dispersal,
+        generate_walker_constellation,
+    },
+    decay_trend::{DecayCalibration, TrendQuantity},
+    delta_v_budget::DeltaVBudget,
+    imaging::ImagingOpportunity,
+    initial_state_model::{GroundStation, InitialSimulationState, Satellite, TleData},
+    maneuver_planner::ManeuverPlan,
+    monte_carlo::MonteCarloResult,
+    orbit_determination::OrbitDeterminationResult,
+    output_frames::{FrameState, OutputFrame},
+    parameter_sweep::{SweepMetric, SweepResult},
+    satellite_state::{SimulationRun, SimulationStateAtStep, pythag_3},
+    tle_history::{AccuracyValidation, TleDiscontinuity, TleHistory},
     ui::{
         fields::{
-            GroundStationField, MyAppInputFields, SatelliteField, SimulationBoolField,
-            SimulationField, TleParameterField,
+            AttitudeField, ConstellationField, GroundStationField, MyAppInputFields,
+            SatelliteField, SimulationBoolField, SimulationField, TleParameterField,
         },
+        plots::PlotQuantity,
         sim_background_worker::spawn_stepper_loop,
     },
 };
 use eframe::egui::{self, FontId, RichText};
 use satkit::TLE;
+use std::sync::atomic::AtomicUsize;
 use std::sync::{Arc, Mutex, mpsc};
 use std::time::Duration;
 
@@ -23,10 +42,19 @@ pub struct StepOutcome {
     pub done: bool,          // stop condition reached?
     pub status_line: String, // what to put into run_status
     pub latest_telemetry: Option<SimulationStateAtStep>,
+    pub new_history: Vec<SimulationStateAtStep>, // steps completed since the previous outcome
 }
 
 pub type StepTx = mpsc::Sender<Result<StepOutcome, String>>;
 pub type StepRx = mpsc::Receiver<Result<StepOutcome, String>>;
+pub type MonteCarloRx = mpsc::Receiver<Result<MonteCarloResult, String>>;
+pub type SweepRx = mpsc::Receiver<Result<SweepResult, String>>;
+pub type ConjunctionRx = mpsc::Receiver<Result<ScreeningResult, String>>;
+pub type AvoidanceRx = mpsc::Receiver<Result<AvoidanceResult, String>>;
+pub type ImagingRx = mpsc::Receiver<Result<Vec<ImagingOpportunity>, String>>;
+pub type AccuracyValidationRx = mpsc::Receiver<Result<AccuracyValidation, String>>;
+pub type DecayCalibrationRx = mpsc::Receiver<Result<DecayCalibration, String>>;
+pub type OrbitDeterminationRx = mpsc::Receiver<Result<OrbitDeterminationResult, String>>;
 
 // -------------------------------------
 // App State (egui)
@@ -41,13 +69,96 @@ pub struct MyApp {
 
     pub input_fields: MyAppInputFields,
 
+    /// Additional element sets generated from the TLE (Walker constellation / rideshare).
+    pub constellation_tles: Vec<TleData>,
+
     /// Status message to display the result of the last run.
     pub run_status: String,
 
+    /// Result of the last "Plan Maneuver".
+    pub maneuver_plan: Option<ManeuverPlan>,
+
+    /// Element sets loaded with "Load TLE History", and the jumps between them.
+    pub tle_history: Vec<TleData>,
+    pub tle_history_discontinuities: Vec<TleDiscontinuity>,
+    /// Result of the last "Validate Accuracy" over the loaded history.
+    pub tle_accuracy: Option<AccuracyValidation>,
+    pub tle_accuracy_rx: Option<AccuracyValidationRx>,
+    /// Quantity shown in the element trend plot.
+    pub trend_quantity: TrendQuantity,
+    /// Result of the last "Calibrate Drag" over the loaded history.
+    pub decay_calibration: Option<DecayCalibration>,
+    pub decay_calibration_rx: Option<DecayCalibrationRx>,
+
+    /// Result of the last "Fit Observations".
+    pub orbit_determination_result: Option<OrbitDeterminationResult>,
+    pub orbit_determination_rx: Option<OrbitDeterminationRx>,
+
     // Simulation
     pub simulation_run: Option<Arc<Mutex<SimulationRun>>>,
     pub latest_telemetry: Option<SimulationStateAtStep>,
+    pub telemetry_history: Vec<SimulationStateAtStep>,
     pub is_running: bool,
+    /// Satellite of the latest run, for its delta-v budget.
+    pub run_satellite: Option<Satellite>,
+    /// Ground stations of the latest run, for its pass windows.
+    pub run_ground_stations: Vec<GroundStation>,
+
+    // Plots and telemetry export
+    pub plot_quantity: PlotQuantity,
+    pub telemetry_export_path: String,
+    /// Frame in which the latest position/velocity is displayed.
+    pub display_frame: OutputFrame,
+    /// Frames whose position/velocity columns are included in the CSV export.
+    pub export_frames: Vec<OutputFrame>,
+
+    // Delta-v budget of the latest run
+    pub delta_v_budget: Option<DeltaVBudget>,
+    pub delta_v_budget_json: String,
+
+    // Monte Carlo
+    pub monte_carlo_result: Option<MonteCarloResult>,
+    /// Samples finished by the Monte Carlo run in progress.
+    pub monte_carlo_progress: Arc<AtomicUsize>,
+    pub monte_carlo_samples: usize,
+    pub monte_carlo_rx: Option<MonteCarloRx>,
+
+    // Parameter sweep
+    pub sweep_result: Option<SweepResult>,
+    /// Runs finished by the sweep in progress.
+    pub sweep_progress: Arc<AtomicUsize>,
+    pub sweep_runs: usize,
+    pub sweep_rx: Option<SweepRx>,
+    /// Metric shown in the sweep heatmap.
+    pub sweep_metric: SweepMetric,
+    pub sweep_export_path: String,
+
+    // Conjunction screening
+    pub conjunction_result: Option<ScreeningResult>,
+    /// Objects finished by the screening in progress.
+    pub conjunction_progress: Arc<AtomicUsize>,
+    pub conjunction_objects: usize,
+    pub conjunction_rx: Option<ConjunctionRx>,
+    /// Pc of each screened conjunction, in the same order.
+    pub conjunction_probabilities: Vec<Result<CollisionProbability, String>>,
+    /// Latest CDM loaded and its recomputed Pc.
+    pub cdm_comparison: Option<(ConjunctionDataMessage, CollisionProbability)>,
+
+    // Collision avoidance
+    /// Index of the conjunction the avoidance search is for.
+    pub avoidance_conjunction: usize,
+    pub avoidance_result: Option<AvoidanceResult>,
+    /// Candidates finished by the search in progress.
+    pub avoidance_progress: Arc<AtomicUsize>,
+    pub avoidance_candidates: usize,
+    pub avoidance_rx: Option<AvoidanceRx>,
+
+    // Imaging opportunities
+    pub imaging_opportunities: Option<Vec<ImagingOpportunity>>,
+    /// Targets finished by the search in progress.
+    pub imaging_progress: Arc<AtomicUsize>,
+    pub imaging_targets: usize,
+    pub imaging_rx: Option<ImagingRx>,
 
     // JSON I/O buffer
     pub inputs_json_buffer: String,
@@ -60,10 +171,13 @@ pub const SIMULATION_MAX_UI_UPDATE_PERIOD_MS: usize = 600; // ms
 
 impl MyApp {
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            export_frames: vec![OutputFrame::Itrf],
+            ..Self::default()
+        }
     }
 
-    fn try_parse_tle(&mut self) {
+    pub fn try_parse_tle(&mut self) {
         if let Ok(satkit_tle) = TLE::load_2line(&self.tle_line1, &self.tle_line2) {
             let tle_data = TleData::from_satkit_tle(&satkit_tle);
 
@@ -74,6 +188,8 @@ impl MyApp {
             }
 
             self.tle_data = Some(tle_data);
+            // Members were generated from the previous TLE.
+            self.constellation_tles.clear();
             self.run_status.clear();
         } else {
             self.tle_data = None;
@@ -197,6 +313,48 @@ impl MyApp {
         }
     }
 
+    fn on_generate_walker(&mut self) {
+        let Some(seed) = &self.tle_data else {
+            self.run_status = "No valid TLE available.".into();
+            return;
+        };
+        match self.read_walker_pattern() {
+            Ok(pattern) => {
+                // The seed itself is slot P1-S1, which is the primary satellite.
+                self.constellation_tles = generate_walker_constellation(seed, &pattern)
+                    .into_iter()
+                    .skip(1)
+                    .collect();
+                self.run_status = format!(
+                    "Generated Walker constellation with {} additional satellites.",
+                    self.constellation_tles.len()
+                );
+            }
+            Err(e) => self.run_status = format!("Invalid Walker pattern: {e}"),
+        }
+    }
+
+    fn on_generate_rideshare(&mut self) {
+        let Some(parent) = &self.tle_data else {
+            self.run_status = "No valid TLE available.".into();
+            return;
+        };
+        let deployment = match self.read_rideshare_deployment() {
+            Ok(d) => d,
+            Err(e) => {
+                self.run_status = format!("Invalid rideshare deployment: {e}");
+                return;
+            }
+        };
+        match generate_rideshare_dispersal(parent, &deployment) {
+            Ok(children) => {
+                self.run_status = format!("Generated {} rideshare children.", children.len());
+                self.constellation_tles = children;
+            }
+            Err(e) => self.run_status = format!("Failed to generate rideshare dispersal: {e}"),
+        }
+    }
+
     fn on_button_pressed_run(&mut self, ctx: &egui::Context) {
         // Initialize.
         let run = match self.init_simulation_run() {
@@ -207,9 +365,15 @@ impl MyApp {
             }
         };
 
+        self.run_satellite = Some(run.initial.satellite.clone());
+        self.run_ground_stations = run.initial.ground_stations.clone();
+        self.delta_v_budget = None;
+        self.delta_v_budget_json.clear();
+
         // Wrap for background stepping.
         let run = Arc::new(Mutex::new(run));
         self.simulation_run = Some(run.clone());
+        self.telemetry_history.clear();
         self.is_running = true;
         self.run_status = "Starting simulation...".to_string();
 
@@ -232,6 +396,7 @@ impl MyApp {
                     Ok(outcome) => {
                         self.run_status = outcome.status_line;
                         self.latest_telemetry = outcome.latest_telemetry;
+                        self.telemetry_history.extend(outcome.new_history);
 
                         if outcome.done {
                             self.is_running = false;
@@ -262,6 +427,10 @@ impl MyApp {
     }
 
     fn init_simulation_run(&mut self) -> Result<SimulationRun, String> {
+        SimulationRun::new(self.read_initial_simulation_state()?, true).map_err(|e| e.to_string())
+    }
+
+    pub fn read_initial_simulation_state(&self) -> Result<InitialSimulationState, String> {
         let ground_station_dom = self.read_ground_station()?;
         let satellite_dom = self.read_satellite()?;
         let simulation_settings_dom = self.read_simulation_settings()?;
@@ -273,14 +442,18 @@ impl MyApp {
 
         let ground_stations = [ground_station_dom];
 
-        let initial_simulation_state = InitialSimulationState {
+        Ok(InitialSimulationState {
             tle: tle_data.clone(),
             ground_stations: ground_stations.to_vec(),
+            ground_targets: self.input_fields.ground_targets.clone(),
             satellite: satellite_dom,
             simulation_settings: simulation_settings_dom,
-        };
-
-        Ok(SimulationRun::new(initial_simulation_state))
+            constellation: self.constellation_tles.clone(),
+            uncertainty: self.read_uncertainty_model()?,
+            tle_history: (!self.tle_history.is_empty())
+                .then(|| TleHistory::new(self.tle_history.clone(), self.input_fields.tle_selection))
+                .transpose()?,
+        })
     }
 
     /// Serialize the current `input_fields` to a pretty JSON string.
@@ -305,6 +478,14 @@ impl eframe::App for MyApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Poll worker (if running)
         self.poll_worker(ctx);
+        self.poll_monte_carlo(ctx);
+        self.poll_sweep(ctx);
+        self.poll_conjunctions(ctx);
+        self.poll_avoidance(ctx);
+        self.poll_imaging(ctx);
+        self.poll_tle_accuracy(ctx);
+        self.poll_decay_calibration(ctx);
+        self.poll_orbit_determination(ctx);
 
         egui::TopBottomPanel::top("top_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
@@ -351,6 +532,7 @@ impl eframe::App for MyApp {
                     if need_parse {
                         self.try_parse_tle();
                     }
+                    self.tle_history_section(ui);
 
                     ui.add_space(8.0);
                     ui.separator();
@@ -408,6 +590,22 @@ impl eframe::App for MyApp {
                     ui.add_space(8.0);
                     ui.separator();
 
+                    // ------------------------------
+                    // Ground Targets
+                    // ------------------------------
+                    self.ground_targets_section(ui);
+
+                    ui.add_space(8.0);
+                    ui.separator();
+
+                    // ------------------------------
+                    // Orbit Determination
+                    // ------------------------------
+                    self.orbit_determination_section(ui);
+
+                    ui.add_space(8.0);
+                    ui.separator();
+
                     // ------------------------------
                     // Satellite
                     // ------------------------------
@@ -430,6 +628,116 @@ impl eframe::App for MyApp {
                             }
                         });
                     }
+                    egui::ComboBox::from_label("Attitude Mode")
+                        .selected_text(self.input_fields.attitude_mode.label())
+                        .show_ui(ui, |ui| {
+                            for mode in AttitudeMode::iter() {
+                                ui.selectable_value(
+                                    &mut self.input_fields.attitude_mode,
+                                    mode,
+                                    mode.label(),
+                                );
+                            }
+                        });
+                    for f in AttitudeField::iter() {
+                        let label = f.label();
+                        let val = self
+                            .input_fields
+                            .attitude_inputs
+                            .get(&f)
+                            .cloned()
+                            .unwrap_or_default();
+                        let mut val_mut = val.clone();
+                        ui.horizontal(|ui| {
+                            ui.label(label);
+                            if ui.text_edit_singleline(&mut val_mut).changed() {
+                                self.input_fields
+                                    .attitude_inputs
+                                    .insert(f.clone(), val_mut.clone());
+                            }
+                        });
+                    }
+                    ui.add_space(4.0);
+                    self.panel_geometry_section(ui);
+                    ui.add_space(4.0);
+                    self.solar_array_section(ui);
+                    ui.add_space(4.0);
+                    self.deployment_events_section(ui);
+                    ui.add_space(4.0);
+                    self.propulsion_section(ui);
+                    ui.add_space(4.0);
+                    self.imager_section(ui);
+
+                    ui.add_space(8.0);
+                    ui.separator();
+
+                    // ------------------------------
+                    // Constellation
+                    // ------------------------------
+                    ui.heading("Constellation");
+                    for f in ConstellationField::iter() {
+                        let label = f.label();
+                        let val = self
+                            .input_fields
+                            .constellation_inputs
+                            .get(&f)
+                            .cloned()
+                            .unwrap_or_default();
+                        let mut val_mut = val.clone();
+                        ui.horizontal(|ui| {
+                            ui.label(label); // .min_size(egui::vec2(180.0, 0.0));
+                            if ui.text_edit_singleline(&mut val_mut).changed() {
+                                self.input_fields
+                                    .constellation_inputs
+                                    .insert(f.clone(), val_mut.clone());
+                            }
+                        });
+                    }
+                    egui::ComboBox::from_label("Walker Pattern")
+                        .selected_text(self.input_fields.walker_pattern_kind.label())
+                        .show_ui(ui, |ui| {
+                            for kind in WalkerPatternKind::iter() {
+                                ui.selectable_value(
+                                    &mut self.input_fields.walker_pattern_kind,
+                                    kind,
+                                    kind.label(),
+                                );
+                            }
+                        });
+                    egui::ComboBox::from_label("Rideshare Separation Direction")
+                        .selected_text(self.input_fields.rideshare_separation_direction.label())
+                        .show_ui(ui, |ui| {
+                            for direction in SeparationDirection::iter() {
+                                ui.selectable_value(
+                                    &mut self.input_fields.rideshare_separation_direction,
+                                    direction,
+                                    direction.label(),
+                                );
+                            }
+                        });
+                    ui.horizontal(|ui| {
+                        if ui.button("Generate Walker").clicked() {
+                            self.on_generate_walker();
+                        }
+                        if ui.button("Generate Rideshare").clicked() {
+                            self.on_generate_rideshare();
+                        }
+                        if ui.button("Clear").clicked() {
+                            self.constellation_tles.clear();
+                        }
+                        ui.label(format!(
+                            "{} additional satellites",
+                            self.constellation_tles.len()
+                        ));
+                    });
+
+                    ui.add_space(8.0);
+                    ui.separator();
+
+                    // ------------------------------
+                    // Maneuver Planner
+                    // ------------------------------
+                    self.maneuver_planner_section(ui);
 
                     ui.add_space(8.0);
                     ui.separator();
@@ -473,6 +781,14 @@ impl eframe::App for MyApp {
                     ui.add_space(8.0);
                     ui.separator();
 
+                    // ------------------------------
+                    // Position Uncertainty
+                    // ------------------------------
+                    self.uncertainty_section(ui);
+
+                    ui.add_space(8.0);
+                    ui.separator();
+
                     // ------------------------------
                     // Inputs JSON I/O
                     // ------------------------------
@@ -518,6 +834,13 @@ impl eframe::App for MyApp {
                     // Telemetry
                     // ------------------------------
                     ui.heading("Latest Telemetry");
+                    egui::ComboBox::from_label("Display frame")
+                        .selected_text(self.display_frame.label())
+                        .show_ui(ui, |ui| {
+                            for frame in OutputFrame::iter() {
+                                ui.selectable_value(&mut self.display_frame, frame, frame.label());
+                            }
+                        });
                     match &self.latest_telemetry {
                         Some(t) => {
                             let angles_preview = if t.elevation_angles_degrees.is_empty() {
@@ -542,6 +865,7 @@ impl eframe::App for MyApp {
                             };
 
                             grid_kv(ui, "Data Point Timestamp", &t.time.as_iso8601());
+                            grid_kv(ui, "Element set epoch", &t.tle_epoch.as_iso8601());
                             grid_kv(
                                 ui,
                                 "Time since epoch",
@@ -551,8 +875,19 @@ impl eframe::App for MyApp {
                                     t.hours_since_epoch / 24.0
                                 ),
                             );
-                            grid_kv(ui, "ITRF position", &format!("{:?}", t.position_itrf));
-                            grid_kv(ui, "ITRF velocity", &format!("{:?}", t.velocity_itrf));
+                            let frame = self.display_frame;
+                            let (position_labels, velocity_labels) = frame.component_labels();
+                            let state = frame.convert(t);
+                            grid_kv(
+                                ui,
+                                &format!("{} position", frame.label()),
+                                &format_components(&position_labels, &state.position),
+                            );
+                            grid_kv(
+                                ui,
+                                &format!("{} velocity", frame.label()),
+                                &format_components(&velocity_labels, &state.velocity),
+                            );
                             grid_kv(ui, "Speed (m/s)", &format!("{:.3}", t.speed_m_per_s));
                             grid_kv(ui, "Elevation (km)", &format!("{:.3}", t.elevation_km));
                             grid_kv(ui, "Elevation angles (deg)", &angles_preview);
@@ -567,13 +902,317 @@ impl eframe::App for MyApp {
                                 "Irradiance (W/m²)",
                                 &format!("{:.1}", t.irradiance_w_per_m2),
                             );
-                            grid_kv(ui, "Local time (h)", &format!("{:.3}", t.local_time_hours));
+                            grid_kv(
+                                ui,
+                                "Local mean / apparent solar time (h)",
+                                &format!(
+                                    "{:.3} / {:.3}",
+                                    t.local_time_hours, t.apparent_solar_time_hours
+                                ),
+                            );
+                            grid_kv(
+                                ui,
+                                "Equation of time (min)",
+                                &format!("{:+.2}", t.equation_of_time_minutes),
+                            );
+                            grid_kv(
+                                ui,
+                                "LTAN / LTDN (h)",
+                                &format!(
+                                    "{:.3} / {:.3}",
+                                    t.ltan_hours,
+                                    (t.ltan_hours + 12.0) % 24.0
+                                ),
+                            );
+                            let days_elapsed = self
+                                .telemetry_history
+                                .first()
+                                .map(|first| (t.time - first.time).as_days())
+                                .unwrap_or(0.0);
+                            grid_kv(
+                                ui,
+                                "LTAN drift (min)",
+                                &if days_elapsed > 0.0 {
+                                    format!(
+                                        "{:+.2} ({:+.3} min/day)",
+                                        t.ltan_drift_minutes,
+                                        t.ltan_drift_minutes / days_elapsed
+                                    )
+                                } else {
+                                    format!("{:+.2}", t.ltan_drift_minutes)
+                                },
+                            );
+                            if let Some(crossing) = self
+                                .telemetry_history
+                                .iter()
+                                .rev()
+                                .find_map(|h| h.node_crossing.as_ref())
+                            {
+                                grid_kv(
+                                    ui,
+                                    "Last node crossing",
+                                    &format!(
+                                        "{} node, orbit {}, {} (solar time {:.3} h)",
+                                        crossing.kind.label(),
+                                        crossing.orbit_number,
+                                        crossing.time.as_iso8601(),
+                                        crossing.apparent_solar_time_hours
+                                    ),
+                                );
+                            }
                             grid_kv(ui, "Deorbited?", if t.is_deorbited { "yes" } else { "no" });
+                            grid_kv(ui, "Earth orientation", t.earth_orientation_mode.label());
+
+                            let att = &t.attitude;
+                            grid_kv(ui, "Attitude mode", att.mode.label());
+                            let [w, x, y, z] = att.quaternion_wxyz();
+                            grid_kv(
+                                ui,
+                                "Body → GCRF quaternion (w, x, y, z)",
+                                &format!("{w:.5}, {x:.5}, {y:.5}, {z:.5}"),
+                            );
+                            grid_kv(ui, "Sun (body)", &format_unit_vector(&att.sun_body));
+                            grid_kv(ui, "Nadir (body)", &format_unit_vector(&att.nadir_body));
+                            grid_kv(
+                                ui,
+                                "Velocity (body)",
+                                &format_unit_vector(&att.velocity_body),
+                            );
+                            grid_kv(
+                                ui,
+                                "Drag area (m²)",
+                                &format!(
+                                    "{:.4} (B* scaled ×{:.3})",
+                                    t.drag_area_m2, t.drag_bstar_scale
+                                ),
+                            );
+                            grid_kv(
+                                ui,
+                                "Drag coefficient",
+                                &format!("{:.3}", t.drag_coefficient),
+                            );
+                            let fired_deployments = self
+                                .telemetry_history
+                                .iter()
+                                .flat_map(|h| {
+                                    h.deployment_events
+                                        .iter()
+                                        .map(|name| format!("{name} at {}", h.time.as_iso8601()))
+                                })
+                                .collect::<Vec<_>>();
+                            if !fired_deployments.is_empty() {
+                                grid_kv(ui, "Deployments", &fired_deployments.join("; "));
+                            }
+                            if let Some(propulsion) = &t.propulsion {
+                                grid_kv(
+                                    ui,
+                                    "Mass / propellant remaining (kg)",
+                                    &format!(
+                                        "{:.4} / {:.4}",
+                                        propulsion.mass_kg, propulsion.propellant_remaining_kg
+                                    ),
+                                );
+                                grid_kv(
+                                    ui,
+                                    "Total burn Δv (m/s)",
+                                    &format!("{:.4}", propulsion.total_delta_v_m_per_s),
+                                );
+                                if let Some(segment) = self
+                                    .telemetry_history
+                                    .iter()
+                                    .rev()
+                                    .find_map(|h| h.propulsion.as_ref()?.burn_segments.last())
+                                {
+                                    grid_kv(
+                                        ui,
+                                        "Last burn",
+                                        &format!(
+                                            "{}: {:.4} m/s, {:.4} kg, ended {}{}",
+                                            segment.name,
+                                            segment.delta_v_m_per_s,
+                                            segment.propellant_used_kg,
+                                            segment.end.as_iso8601(),
+                                            if segment.propellant_depleted {
+                                                " (tank empty)"
+                                            } else {
+                                                ""
+                                            }
+                                        ),
+                                    );
+                                }
+                                let reboosts = self
+                                    .telemetry_history
+                                    .iter()
+                                    .filter_map(|h| h.reboost.as_ref())
+                                    .collect::<Vec<_>>();
+                                if let Some(reboost) = reboosts.last() {
+                                    grid_kv(
+                                        ui,
+                                        "Station-keeping reboosts",
+                                        &format!(
+                                            "{} (last at mean altitude {:.3} km, {:.4} m/s planned)",
+                                            reboosts.len(),
+                                            reboost.mean_altitude_km,
+                                            reboost.planned_delta_v_m_per_s
+                                        ),
+                                    );
+                                }
+                            }
+                            if let Some(uncertainty) = &t.uncertainty {
+                                grid_kv(
+                                    ui,
+                                    "Position 1σ (radial / along / cross)",
+                                    &format!(
+                                        "{:.1} / {:.1} / {:.1} m",
+                                        uncertainty.radial_sigma_m,
+                                        uncertainty.along_track_sigma_m,
+                                        uncertainty.cross_track_sigma_m
+                                    ),
+                                );
+                            }
+                            if let Some(undeployed) = &t.undeployed {
+                                let deployed_reentry = self
+                                    .telemetry_history
+                                    .iter()
+                                    .find(|h| h.is_deorbited)
+                                    .map(|h| h.time);
+                                grid_kv(
+                                    ui,
+                                    "Without deployment",
+                                    &match (undeployed.reentry_time, deployed_reentry) {
+                                        (Some(undeployed_time), Some(deployed_time)) => format!(
+                                            "reentry {} ({:+.2} days vs deployed)",
+                                            undeployed_time.as_iso8601(),
+                                            (undeployed_time - deployed_time).as_days()
+                                        ),
+                                        (Some(undeployed_time), None) => {
+                                            format!("reentry {}", undeployed_time.as_iso8601())
+                                        }
+                                        (None, Some(_)) => {
+                                            "still in orbit at the end of the run".to_string()
+                                        }
+                                        (None, None) => {
+                                            format!("altitude {:.3} km", undeployed.elevation_km)
+                                        }
+                                    },
+                                );
+                            }
+                            if !t.solar_array_power_watts.is_empty() {
+                                grid_kv(
+                                    ui,
+                                    "Solar power (W)",
+                                    &format!(
+                                        "{:.3} ({})",
+                                        t.solar_array_power_watts.iter().sum::<f64>(),
+                                        t.solar_array_power_watts
+                                            .iter()
+                                            .map(|p| format!("{p:.3}"))
+                                            .collect::<Vec<_>>()
+                                            .join(", ")
+                                    ),
+                                );
+                                grid_kv(
+                                    ui,
+                                    "Orbit-average power (W) / sunlit fraction",
+                                    &match &t.orbit_average_power {
+                                        Some(avg) => format!(
+                                            "{:.3} / {:.3}",
+                                            avg.average_power_watts, avg.sunlit_fraction
+                                        ),
+                                        None => "n/a".to_string(),
+                                    },
+                                );
+                            }
+                            let srp = t.srp_force_body_n;
+                            grid_kv(
+                                ui,
+                                "Sun-facing area (m²) / SRP force (µN)",
+                                &format!(
+                                    "{:.4} / {:.3}",
+                                    t.sun_projected_area_m2,
+                                    pythag_3(&srp) * 1e6
+                                ),
+                            );
+
+                            let el = &t.orbital_elements;
+                            grid_kv(
+                                ui,
+                                "Semi-major axis (km)",
+                                &format!("{:.3}", el.semi_major_axis_m / 1000.0),
+                            );
+                            grid_kv(ui, "Eccentricity", &format!("{:.6}", el.eccentricity));
+                            grid_kv(
+                                ui,
+                                "Inclination (deg)",
+                                &format!("{:.4}", el.inclination_deg),
+                            );
+                            grid_kv(ui, "RAAN (deg)", &format!("{:.4}", el.raan_deg));
+                            grid_kv(
+                                ui,
+                                "Argument of perigee (deg)",
+                                &format!("{:.4}", el.arg_of_perigee_deg),
+                            );
+                            grid_kv(
+                                ui,
+                                "True / mean anomaly (deg)",
+                                &format!("{:.4} / {:.4}", el.true_anomaly_deg, el.mean_anomaly_deg),
+                            );
+                            grid_kv(
+                                ui,
+                                "Apogee / perigee altitude (km)",
+                                &format!(
+                                    "{:.3} / {:.3}",
+                                    el.apogee_altitude_km(),
+                                    el.perigee_altitude_km()
+                                ),
+                            );
+                            grid_kv(ui, "Period (min)", &format!("{:.3}", el.period_minutes()));
+                            let eq = &t.equinoctial_elements;
+                            grid_kv(
+                                ui,
+                                "Equinoctial (h, k, p, q, λ)",
+                                &format!(
+                                    "{:.6}, {:.6}, {:.6}, {:.6}, {:.4}°",
+                                    eq.h, eq.k, eq.p, eq.q, eq.mean_longitude_deg
+                                ),
+                            );
+
+                            if !t.constellation.is_empty() {
+                                constellation_table(ui, t, self.display_frame);
+                            }
                         }
                         None => {
                             ui.label("No telemetry yet. Press Run to start.");
                         }
                     }
+
+                    ui.add_space(8.0);
+                    ui.separator();
+
+                    // ------------------------------
+                    // Telemetry plots and export
+                    // ------------------------------
+                    self.telemetry_plot_section(ui);
+                    self.perigee_pass_section(ui);
+                    self.pass_window_section(ui);
+                    self.telemetry_export_section(ui);
+                    self.delta_v_budget_section(ui);
+
+                    ui.add_space(8.0);
+                    ui.separator();
+                    self.monte_carlo_section(ui);
+
+                    ui.add_space(8.0);
+                    ui.separator();
+                    self.parameter_sweep_section(ui);
+
+                    ui.add_space(8.0);
+                    ui.separator();
+                    self.conjunction_section(ui);
+
+                    ui.add_space(8.0);
+                    ui.separator();
+                    self.imaging_section(ui);
                 });
         });
     }
@@ -586,6 +1225,82 @@ fn grid_kv(ui: &mut egui::Ui, key: &str, val: &str) {
     });
 }
 
+fn format_unit_vector(v: &[f64; 3]) -> String {
+    format!("[{:+.4}, {:+.4}, {:+.4}]", v[0], v[1], v[2])
+}
+
+fn format_components(labels: &[&str; 3], values: &[f64; 3]) -> String {
+    labels
+        .iter()
+        .zip(values)
+        .map(|(label, value)| format!("{label}={value:.3}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn constellation_table(ui: &mut egui::Ui, t: &SimulationStateAtStep, frame: OutputFrame) {
+    // Phases around the orbit, so the gap across the ±180° wrap counts like any other.
+    let mut phases_deg = t
+        .constellation
+        .iter()
+        .filter(|m| !m.is_deorbited)
+        .map(|m| m.along_track_offset_deg.rem_euclid(360.0))
+        .chain(std::iter::once(0.0))
+        .collect::<Vec<_>>();
+    phases_deg.sort_by(f64::total_cmp);
+    let wrap_gap_deg = phases_deg[0] + 360.0 - phases_deg[phases_deg.len() - 1];
+    let largest_gap_deg = phases_deg
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .fold(wrap_gap_deg, f64::max);
+
+    ui.add_space(8.0);
+    ui.label(RichText::new("Constellation").strong());
+    grid_kv(
+        ui,
+        "Largest along-track gap (deg)",
+        &format!("{:.3}", largest_gap_deg),
+    );
+    grid_kv(
+        ui,
+        "Satellites in view per station",
+        &format!("{:?}", t.satellites_in_view_per_station),
+    );
+    egui::Grid::new("constellation_grid")
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Name");
+            ui.label("Elevation (km)");
+            ui.label("Along-track offset (deg)");
+            ui.label("Elevation angles (deg)");
+            ui.label(format!("{} position", frame.label()));
+            ui.end_row();
+            for member in &t.constellation {
+                ui.label(&member.name);
+                if member.is_deorbited {
+                    ui.label("deorbited");
+                    ui.label("");
+                    ui.label("");
+                    ui.label("");
+                } else {
+                    ui.label(format!("{:.3}", member.elevation_km));
+                    ui.label(format!("{:+.3}", member.along_track_offset_deg));
+                    ui.label(
+                        member
+                            .elevation_angles_degrees
+                            .iter()
+                            .map(|v| format!("{:.2}", v))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    );
+                    let FrameState { position, .. } = frame.convert_member(t, member);
+                    ui.label(format_components(&frame.component_labels().0, &position));
+                }
+                ui.end_row();
+            }
+        });
+}
+
 // -------------------------------------
 // eframe entry point
 // -------------------------------------
@@ -0,0 +1,64 @@
+use eframe::egui;
+
+use crate::ui::actions::MyApp;
+
+impl MyApp {
+    pub fn perigee_pass_section(&mut self, ui: &mut egui::Ui) {
+        let passes = self
+            .telemetry_history
+            .iter()
+            .flat_map(|t| &t.perigee_passes)
+            .collect::<Vec<_>>();
+        if passes.is_empty() {
+            return;
+        }
+
+        ui.heading("Perigee Passes");
+        let delta_v_lost_m_per_s: f64 = passes.iter().map(|p| p.delta_v_lost_m_per_s).sum();
+        let peak_heat_flux_w_per_m2 = passes
+            .iter()
+            .map(|p| p.peak_heat_flux_w_per_m2)
+            .fold(0.0, f64::max);
+        let max_heat_load_j_per_m2 = passes
+            .iter()
+            .map(|p| p.heat_load_j_per_m2)
+            .fold(0.0, f64::max);
+        ui.label(format!(
+            "{} passes: {:.4} m/s lost in total, peak heat flux {:.1} W/m², worst pass heat load {:.2} kJ/m²",
+            passes.len(),
+            delta_v_lost_m_per_s,
+            peak_heat_flux_w_per_m2,
+            max_heat_load_j_per_m2 / 1000.0
+        ));
+
+        egui::ScrollArea::vertical()
+            .id_salt("perigee_pass_scroll")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                egui::Grid::new("perigee_pass_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("Perigee (UTC)");
+                        ui.label("Altitude (km)");
+                        ui.label("Peak q (Pa)");
+                        ui.label("Peak heat flux (W/m²)");
+                        ui.label("Heat load (kJ/m²)");
+                        ui.label("Peak drag power (W)");
+                        ui.label("Δv lost (m/s)");
+                        ui.label("Apogee decay (km)");
+                        ui.end_row();
+                        for pass in &passes {
+                            ui.label(pass.perigee_time.as_iso8601());
+                            ui.label(format!("{:.2}", pass.perigee_altitude_km));
+                            ui.label(format!("{:.4}", pass.peak_dynamic_pressure_pa));
+                            ui.label(format!("{:.2}", pass.peak_heat_flux_w_per_m2));
+                            ui.label(format!("{:.3}", pass.heat_load_j_per_m2 / 1000.0));
+                            ui.label(format!("{:.3}", pass.peak_drag_power_watts));
+                            ui.label(format!("{:.5}", pass.delta_v_lost_m_per_s));
+                            ui.label(format!("{:.4}", pass.apogee_decay_km()));
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
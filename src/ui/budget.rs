@@ -0,0 +1,127 @@
+use eframe::egui;
+
+use crate::delta_v_budget::DeltaVBudget;
+use crate::ui::actions::MyApp;
+
+impl MyApp {
+    fn on_generate_delta_v_budget(&mut self) {
+        let Some(satellite) = &self.run_satellite else {
+            return;
+        };
+        let budget = DeltaVBudget::from_history(&self.telemetry_history, satellite);
+        match budget.to_json() {
+            Ok(json) => {
+                self.run_status = match &budget.station_keeping {
+                    Some(station_keeping) => format!(
+                        "Δv budget: {:.3} m/s of maneuvers, {:.3} m/s of station keeping.",
+                        budget.total_maneuver_delta_v_m_per_s, station_keeping.delta_v_m_per_s
+                    ),
+                    None => format!(
+                        "Δv budget: {:.3} m/s of maneuvers, {:.3} m/s of drag makeup needed.",
+                        budget.total_maneuver_delta_v_m_per_s, budget.drag_makeup_delta_v_m_per_s
+                    ),
+                };
+                self.delta_v_budget_json = json;
+                self.delta_v_budget = Some(budget);
+            }
+            Err(e) => self.run_status = format!("Failed to serialize Δv budget: {e}"),
+        }
+    }
+
+    pub fn delta_v_budget_section(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Δv Budget");
+        if ui
+            .add_enabled(
+                !self.telemetry_history.is_empty(),
+                egui::Button::new("Generate Δv Budget"),
+            )
+            .clicked()
+        {
+            self.on_generate_delta_v_budget();
+        }
+
+        let Some(budget) = &self.delta_v_budget else {
+            return;
+        };
+        egui::Grid::new("delta_v_budget_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Maneuver");
+                ui.label("Start (UTC)");
+                ui.label("End (UTC)");
+                ui.label("Δv (m/s)");
+                ui.label("Propellant (kg)");
+                ui.end_row();
+                for maneuver in &budget.maneuvers {
+                    ui.label(if maneuver.propellant_depleted {
+                        format!("{} (tank empty)", maneuver.name)
+                    } else {
+                        maneuver.name.clone()
+                    });
+                    ui.label(&maneuver.start_utc);
+                    ui.label(&maneuver.end_utc);
+                    ui.label(format!("{:.3}", maneuver.delta_v_m_per_s));
+                    ui.label(format!("{:.4}", maneuver.propellant_kg));
+                    ui.end_row();
+                }
+                ui.label("Total maneuvers");
+                ui.label("");
+                ui.label("");
+                ui.label(format!("{:.3}", budget.total_maneuver_delta_v_m_per_s));
+                ui.label(format!("{:.4}", budget.total_propellant_kg));
+                ui.end_row();
+                ui.label(format!(
+                    "Drag makeup needed between burns ({:.2} days)",
+                    budget.simulated_days
+                ));
+                ui.label("");
+                ui.label("");
+                ui.label(format!(
+                    "{:.3} ({:.3}/yr)",
+                    budget.drag_makeup_delta_v_m_per_s, budget.drag_makeup_delta_v_m_per_s_per_year
+                ));
+                ui.label("");
+                ui.end_row();
+                if let Some(station_keeping) = &budget.station_keeping {
+                    ui.label(format!(
+                        "Station keeping ({} reboosts over {:.2} days)",
+                        station_keeping.reboosts, station_keeping.active_days
+                    ));
+                    ui.label("");
+                    ui.label("");
+                    ui.label(format!(
+                        "{:.3} ({:.3}/yr)",
+                        station_keeping.delta_v_m_per_s, station_keeping.delta_v_m_per_s_per_year
+                    ));
+                    ui.label(format!("{:.4}", station_keeping.propellant_kg));
+                    ui.end_row();
+                }
+                if let Some(margin) = &budget.margin {
+                    ui.label("Remaining margin");
+                    ui.label("");
+                    ui.label("");
+                    ui.label(format!("{:.3}", margin.delta_v_remaining_m_per_s));
+                    ui.label(format!("{:.4}", margin.propellant_remaining_kg));
+                    ui.end_row();
+                    if let Some(years) = margin.drag_makeup_years_remaining {
+                        ui.label("Drag makeup remaining (years)");
+                        ui.label(format!("{years:.2}"));
+                        ui.end_row();
+                    }
+                }
+            });
+
+        ui.collapsing("Budget JSON", |ui| {
+            egui::ScrollArea::vertical()
+                .id_salt("delta_v_budget_json_scroll")
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.delta_v_budget_json.as_str())
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+        });
+    }
+}
@@ -0,0 +1,294 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+use strum::IntoEnumIterator;
+
+use crate::collision_avoidance::{AvoidanceOutcome, search_avoidance_maneuvers};
+use crate::ui::actions::{MyApp, SIMULATION_MAX_UI_UPDATE_PERIOD_MS};
+use crate::ui::fields::AvoidanceField;
+
+/// Pc plotted on a log scale; zero is drawn at this floor.
+const LOG_PC_FLOOR: f64 = -30.0;
+
+impl MyApp {
+    fn on_search_avoidance(&mut self) {
+        let Some(conjunction) = self
+            .conjunction_result
+            .as_ref()
+            .and_then(|result| result.conjunctions.get(self.avoidance_conjunction))
+            .cloned()
+        else {
+            self.run_status = "Select a conjunction first.".into();
+            return;
+        };
+        let nominal = match self.read_initial_simulation_state() {
+            Ok(nominal) => nominal,
+            Err(e) => {
+                self.run_status = format!("Error initializing avoidance search: {e}");
+                return;
+            }
+        };
+        let (growth, hard_body_radius_m) = match self.read_collision_probability_model() {
+            Ok(model) => model,
+            Err(e) => {
+                self.run_status = format!("Cannot compute Pc: {e}");
+                return;
+            }
+        };
+        let settings = match self.read_avoidance_settings() {
+            Ok(settings) => settings,
+            Err(e) => {
+                self.run_status = format!("Invalid avoidance settings: {e}");
+                return;
+            }
+        };
+
+        let progress = Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = mpsc::channel();
+        self.avoidance_progress = progress.clone();
+        self.avoidance_candidates =
+            settings.lead_times_hours.len() * settings.delta_v_magnitudes_m_per_s.len() * 2;
+        self.avoidance_rx = Some(rx);
+        self.avoidance_result = None;
+        self.run_status = format!(
+            "Simulating {} avoidance burns on {} threads...",
+            self.avoidance_candidates, settings.threads
+        );
+
+        std::thread::spawn(move || {
+            let result = search_avoidance_maneuvers(
+                &nominal,
+                &conjunction,
+                &growth,
+                hard_body_radius_m,
+                &settings,
+                &progress,
+            )
+            .map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+    }
+
+    pub fn poll_avoidance(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.avoidance_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(result)) => {
+                self.run_status = match result.cheapest_acceptable() {
+                    Some(candidate) => format!(
+                        "Cheapest acceptable burn: {:+.3} m/s, {:.1} hours before TCA.",
+                        candidate.planned_delta_v_m_per_s, candidate.lead_time_hours
+                    ),
+                    None => format!(
+                        "No candidate brings Pc down to {:.1e}.",
+                        result.acceptable_pc
+                    ),
+                };
+                self.avoidance_result = Some(result);
+                self.avoidance_rx = None;
+            }
+            Ok(Err(e)) => {
+                self.run_status = format!("Avoidance search failed: {e}");
+                self.avoidance_rx = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(
+                    SIMULATION_MAX_UI_UPDATE_PERIOD_MS as u64,
+                ));
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.run_status = "Avoidance search worker stopped unexpectedly.".into();
+                self.avoidance_rx = None;
+            }
+        }
+    }
+
+    pub fn collision_avoidance_section(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            "Simulates along-track burns at several lead times before TCA with the thruster, \
+             and recomputes the miss distance and Pc after each.",
+        );
+        let Some(screening) = &self.conjunction_result else {
+            return;
+        };
+        let describe = |index: usize| {
+            screening
+                .conjunctions
+                .get(index)
+                .map_or(String::new(), |c| {
+                    format!("{} at {}", c.object, c.tca.as_iso8601())
+                })
+        };
+        egui::ComboBox::from_label("Conjunction")
+            .selected_text(describe(self.avoidance_conjunction))
+            .show_ui(ui, |ui| {
+                for index in 0..screening.conjunctions.len() {
+                    ui.selectable_value(&mut self.avoidance_conjunction, index, describe(index));
+                }
+            });
+
+        for f in AvoidanceField::iter() {
+            let mut val = self
+                .input_fields
+                .avoidance_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .avoidance_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+
+        ui.horizontal(|ui| {
+            let running = self.avoidance_rx.is_some();
+            if ui
+                .add_enabled(!running, egui::Button::new("Search Avoidance Burns"))
+                .clicked()
+            {
+                self.on_search_avoidance();
+            }
+            if running {
+                ui.label(format!(
+                    "{} / {} burns done",
+                    self.avoidance_progress.load(Ordering::Relaxed),
+                    self.avoidance_candidates
+                ));
+            }
+        });
+
+        let Some(result) = &self.avoidance_result else {
+            return;
+        };
+        let describe_outcome = |outcome: &AvoidanceOutcome| {
+            format!(
+                "miss {:.3} km, Pc {:.3e}",
+                outcome.probability.miss_distance_m / 1000.0,
+                outcome.probability.foster
+            )
+        };
+        ui.label(format!(
+            "Without a burn: {}",
+            describe_outcome(&result.nominal)
+        ));
+        let cheapest = result.cheapest_acceptable();
+        match cheapest.map(|candidate| (candidate, &candidate.outcome)) {
+            Some((candidate, Ok(outcome))) => {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "Cheapest acceptable: {:+.3} m/s {:.1} hours before TCA ({:.4} kg): {}",
+                        candidate.planned_delta_v_m_per_s,
+                        candidate.lead_time_hours,
+                        outcome.propellant_kg,
+                        describe_outcome(outcome)
+                    ));
+                    if let Some(burn) = &outcome.burn
+                        && ui.button("Add Burn to Schedule").clicked()
+                    {
+                        self.input_fields.burn_schedule.push(burn.clone());
+                        self.run_status = format!(
+                            "Added the avoidance burn at {:.5} days to the schedule.",
+                            burn.start_days_since_epoch
+                        );
+                    }
+                });
+            }
+            _ => {
+                ui.label(format!(
+                    "No candidate brings Pc down to {:.1e}.",
+                    result.acceptable_pc
+                ));
+            }
+        }
+
+        // One curve per lead time, against signed delta-v.
+        let mut lead_times = result
+            .candidates
+            .iter()
+            .map(|candidate| candidate.lead_time_hours)
+            .collect::<Vec<_>>();
+        lead_times.dedup();
+        let curve = |lead_time_hours: f64, value: &dyn Fn(&AvoidanceOutcome) -> f64| {
+            let points: PlotPoints = result
+                .candidates
+                .iter()
+                .filter(|candidate| candidate.lead_time_hours == lead_time_hours)
+                .filter_map(|candidate| {
+                    let outcome = candidate.outcome.as_ref().ok()?;
+                    Some([candidate.planned_delta_v_m_per_s, value(outcome)])
+                })
+                .collect();
+            Line::new(format!("{lead_time_hours:.1} h before TCA"), points)
+        };
+        let log_pc =
+            |outcome: &AvoidanceOutcome| outcome.probability.foster.log10().max(LOG_PC_FLOOR);
+        let miss_km = |outcome: &AvoidanceOutcome| outcome.probability.miss_distance_m / 1000.0;
+        ui.columns(2, |columns| {
+            Plot::new("avoidance_pc_plot")
+                .height(220.0)
+                .legend(Legend::default())
+                .x_axis_label("Along-track delta-v (m/s)")
+                .y_axis_label("log10 Pc")
+                .show(&mut columns[0], |plot_ui| {
+                    for lead_time_hours in &lead_times {
+                        plot_ui.line(curve(*lead_time_hours, &log_pc));
+                    }
+                });
+            Plot::new("avoidance_miss_plot")
+                .height(220.0)
+                .legend(Legend::default())
+                .x_axis_label("Along-track delta-v (m/s)")
+                .y_axis_label("Miss distance (km)")
+                .show(&mut columns[1], |plot_ui| {
+                    for lead_time_hours in &lead_times {
+                        plot_ui.line(curve(*lead_time_hours, &miss_km));
+                    }
+                });
+        });
+
+        egui::CollapsingHeader::new("Candidate burns").show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .id_salt("avoidance_scroll")
+                .max_height(250.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("avoidance_grid")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("Lead time (h)");
+                            ui.label("Planned delta-v (m/s)");
+                            ui.label("Simulated delta-v (m/s)");
+                            ui.label("Propellant (kg)");
+                            ui.label("New TCA (UTC)");
+                            ui.label("Radial / along / cross (km)");
+                            ui.label("Pc (Foster)");
+                            ui.end_row();
+                            for candidate in &result.candidates {
+                                ui.label(format!("{:.1}", candidate.lead_time_hours));
+                                ui.label(format!("{:+.3}", candidate.planned_delta_v_m_per_s));
+                                match &candidate.outcome {
+                                    Ok(outcome) => {
+                                        let [r, a, c] = outcome.miss_rtn_m.map(|m| m / 1000.0);
+                                        ui.label(format!("{:.3}", outcome.delta_v_m_per_s));
+                                        ui.label(format!("{:.4}", outcome.propellant_kg));
+                                        ui.label(outcome.tca.as_iso8601());
+                                        ui.label(format!("{r:.3} / {a:.3} / {c:.3}"));
+                                        ui.label(format!("{:.3e}", outcome.probability.foster));
+                                    }
+                                    Err(e) => {
+                                        ui.label(e);
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+    }
+}
@@ -0,0 +1,410 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+
+use eframe::egui;
+use strum::IntoEnumIterator;
+
+use crate::collision_probability::{CollisionProbability, conjunction_probability, parse_cdm};
+use crate::conjunction::screen_conjunctions;
+use crate::tle_history::parse_tle_sets;
+use crate::ui::actions::{MyApp, SIMULATION_MAX_UI_UPDATE_PERIOD_MS};
+use crate::ui::fields::ConjunctionField;
+
+fn format_pc(probability: &CollisionProbability) -> String {
+    format!(
+        "{:.3e} / {:.3e} / {:.3e}",
+        probability.foster, probability.chan, probability.alfano
+    )
+}
+
+impl MyApp {
+    fn on_screen_conjunctions(&mut self) {
+        let Some(primary) = self.tle_data.clone() else {
+            self.run_status = "No valid TLE available.".into();
+            return;
+        };
+        let settings = match self.read_screening_settings(primary.epoch) {
+            Ok(settings) => settings,
+            Err(e) => {
+                self.run_status = format!("Invalid screening settings: {e}");
+                return;
+            }
+        };
+        let path = self
+            .input_fields
+            .conjunction_inputs
+            .get(&ConjunctionField::CatalogFile)
+            .map(|p| p.trim().to_string())
+            .unwrap_or_default();
+        // The generated constellation is screened along with the catalog.
+        let mut catalog = self.constellation_tles.clone();
+        if !path.is_empty() {
+            match std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {path}: {e}"))
+                .and_then(|text| parse_tle_sets(&text))
+            {
+                Ok(objects) => catalog.extend(objects),
+                Err(e) => {
+                    self.run_status = format!("Invalid catalog: {e}");
+                    return;
+                }
+            }
+        }
+        if catalog.is_empty() {
+            self.run_status =
+                "Nothing to screen: load a catalog or generate a constellation.".into();
+            return;
+        }
+
+        let progress = Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = mpsc::channel();
+        self.conjunction_progress = progress.clone();
+        self.conjunction_objects = catalog
+            .iter()
+            .filter(|object| object.sat_num != primary.sat_num)
+            .count();
+        self.conjunction_rx = Some(rx);
+        self.conjunction_result = None;
+        self.conjunction_probabilities.clear();
+        self.avoidance_result = None;
+        self.avoidance_conjunction = 0;
+        self.run_status = format!(
+            "Screening {} objects over {} days...",
+            self.conjunction_objects, settings.duration_days
+        );
+
+        std::thread::spawn(move || {
+            let result = screen_conjunctions(&primary, &catalog, &settings, &progress)
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+    }
+
+    /// Pc of every screened conjunction, with covariances from the TLE-age uncertainty model.
+    fn on_compute_conjunction_pc(&mut self) {
+        let Some(result) = &self.conjunction_result else {
+            return;
+        };
+        let (growth, hard_body_radius_m) = match self.read_collision_probability_model() {
+            Ok(model) => model,
+            Err(e) => {
+                self.run_status = format!("Cannot compute Pc: {e}");
+                return;
+            }
+        };
+        self.conjunction_probabilities = result
+            .conjunctions
+            .iter()
+            .map(|conjunction| {
+                conjunction_probability(
+                    conjunction,
+                    &result.primary_epoch,
+                    &growth,
+                    hard_body_radius_m,
+                )
+                .map_err(|e| e.to_string())
+            })
+            .collect();
+        // The avoidance search defaults to the riskiest conjunction.
+        let (riskiest, highest) = self
+            .conjunction_probabilities
+            .iter()
+            .enumerate()
+            .filter_map(|(index, probability)| Some((index, probability.as_ref().ok()?.foster)))
+            .fold((0, 0.0), |best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            });
+        self.avoidance_conjunction = riskiest;
+        self.run_status = format!(
+            "Computed Pc of {} conjunctions; highest {highest:.3e}.",
+            self.conjunction_probabilities.len()
+        );
+    }
+
+    /// Recompute the Pc of a CDM, with the configured hard-body radius when there is one and
+    /// the message's otherwise.
+    fn on_load_cdm(&mut self) {
+        let path = self.input_fields.cdm_path.trim().to_string();
+        let cdm = match std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {path}: {e}"))
+            .and_then(|text| parse_cdm(&text))
+        {
+            Ok(cdm) => cdm,
+            Err(e) => {
+                self.run_status = format!("Invalid CDM: {e}");
+                return;
+            }
+        };
+        let hard_body_radius_m = match self.read_combined_hard_body_radius() {
+            Ok(Some(radius)) => radius,
+            Ok(None) => match cdm.hard_body_radius_m {
+                Some(radius) => radius,
+                None => {
+                    self.run_status = "The CDM has no hard-body radius: set the satellite's to \
+                                       recompute Pc."
+                        .into();
+                    return;
+                }
+            },
+            Err(e) => {
+                self.run_status = format!("Invalid hard-body radius: {e}");
+                return;
+            }
+        };
+        match cdm.recompute(hard_body_radius_m) {
+            Ok(probability) => {
+                self.run_status = format!(
+                    "Recomputed Pc {:.3e} (reported {}).",
+                    probability.foster,
+                    cdm.collision_probability
+                        .map_or("none".to_string(), |pc| format!("{pc:.3e}"))
+                );
+                self.cdm_comparison = Some((cdm, probability));
+            }
+            Err(e) => self.run_status = format!("Failed to recompute the CDM's Pc: {e}"),
+        }
+    }
+
+    pub fn poll_conjunctions(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.conjunction_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(result)) => {
+                self.run_status = format!(
+                    "Screening finished: {} conjunctions among {} objects ({} failed to propagate).",
+                    result.conjunctions.len(),
+                    result.screened_objects,
+                    result.failed_objects.len()
+                );
+                self.conjunction_result = Some(result);
+                self.conjunction_rx = None;
+            }
+            Ok(Err(e)) => {
+                self.run_status = format!("Conjunction screening failed: {e}");
+                self.conjunction_rx = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(
+                    SIMULATION_MAX_UI_UPDATE_PERIOD_MS as u64,
+                ));
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.run_status = "Conjunction screening worker stopped unexpectedly.".into();
+                self.conjunction_rx = None;
+            }
+        }
+    }
+
+    pub fn conjunction_section(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Conjunction Screening");
+        ui.label(
+            "Finds close approaches of the TLE above with every object of the catalog and of \
+             the generated constellation.",
+        );
+        for f in ConjunctionField::iter() {
+            let mut val = self
+                .input_fields
+                .conjunction_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .conjunction_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+
+        ui.horizontal(|ui| {
+            let running = self.conjunction_rx.is_some();
+            if ui
+                .add_enabled(!running, egui::Button::new("Screen Conjunctions"))
+                .clicked()
+            {
+                self.on_screen_conjunctions();
+            }
+            if ui
+                .add_enabled(
+                    !running && self.conjunction_result.is_some(),
+                    egui::Button::new("Compute Pc"),
+                )
+                .on_hover_text(
+                    "Covariances from the TLE-age uncertainty model; hard-body radius from the \
+                     satellite plus the other object's.",
+                )
+                .clicked()
+            {
+                self.on_compute_conjunction_pc();
+            }
+            if running {
+                ui.label(format!(
+                    "{} / {} objects done",
+                    self.conjunction_progress.load(Ordering::Relaxed),
+                    self.conjunction_objects
+                ));
+            }
+        });
+
+        egui::CollapsingHeader::new("Conjunction Data Message").show(ui, |ui| {
+            self.cdm_section(ui);
+        });
+
+        let Some(result) = &self.conjunction_result else {
+            return;
+        };
+        egui::Grid::new("conjunction_summary_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Objects screened");
+                ui.label(result.screened_objects.to_string());
+                ui.end_row();
+                ui.label("Passed the apogee/perigee filter");
+                ui.label(result.after_apsis_filter.to_string());
+                ui.end_row();
+                ui.label("Half-hour chunks searched after the orbit-path filter");
+                ui.label(result.path_filter_candidates.to_string());
+                ui.end_row();
+                if let Some((object, error)) = result.failed_objects.first() {
+                    ui.label(format!(
+                        "Failed to propagate ({})",
+                        result.failed_objects.len()
+                    ));
+                    ui.label(format!("{object}: {error}"));
+                    ui.end_row();
+                }
+            });
+
+        egui::ScrollArea::vertical()
+            .id_salt("conjunction_scroll")
+            .max_height(250.0)
+            .show(ui, |ui| {
+                egui::Grid::new("conjunction_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("Object");
+                        ui.label("NORAD id");
+                        ui.label("TCA (UTC)");
+                        ui.label("Miss distance (km)");
+                        ui.label("Radial / along / cross (km)");
+                        ui.label("Relative speed (km/s)");
+                        if !self.conjunction_probabilities.is_empty() {
+                            ui.label("Pc (Foster / Chan / Alfano)");
+                        }
+                        ui.end_row();
+                        for (index, conjunction) in result.conjunctions.iter().enumerate() {
+                            let [r, a, c] = conjunction.miss_rtn_m.map(|m| m / 1000.0);
+                            ui.label(&conjunction.object);
+                            ui.label(conjunction.sat_num.to_string());
+                            ui.label(conjunction.tca.as_iso8601());
+                            ui.label(format!("{:.3}", conjunction.miss_distance_m / 1000.0));
+                            ui.label(format!("{r:.3} / {a:.3} / {c:.3}"));
+                            ui.label(format!(
+                                "{:.3}",
+                                conjunction.relative_speed_m_per_s / 1000.0
+                            ));
+                            match self.conjunction_probabilities.get(index) {
+                                Some(Ok(probability)) => {
+                                    ui.label(format_pc(probability));
+                                }
+                                Some(Err(e)) => {
+                                    ui.label(e);
+                                }
+                                None => {}
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if !self.conjunction_probabilities.is_empty() {
+            egui::CollapsingHeader::new("Collision Avoidance").show(ui, |ui| {
+                self.collision_avoidance_section(ui);
+            });
+        }
+    }
+
+    fn cdm_section(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            "Recomputes Pc from the states and RTN covariances of a CCSDS CDM (KVN), using the \
+             hard-body radius above or the message's.",
+        );
+        ui.horizontal(|ui| {
+            ui.label("CDM file");
+            ui.text_edit_singleline(&mut self.input_fields.cdm_path);
+            if ui.button("Load CDM").clicked() {
+                self.on_load_cdm();
+            }
+        });
+
+        let Some((cdm, probability)) = &self.cdm_comparison else {
+            return;
+        };
+        egui::Grid::new("cdm_grid").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.label("Reported");
+            ui.label("Recomputed");
+            ui.end_row();
+            ui.label("Objects");
+            ui.label(format!(
+                "{} ({}) / {} ({})",
+                cdm.objects[0].name,
+                cdm.objects[0].designator,
+                cdm.objects[1].name,
+                cdm.objects[1].designator
+            ));
+            ui.end_row();
+            ui.label("TCA (UTC)");
+            ui.label(cdm.tca.as_iso8601());
+            ui.end_row();
+            ui.label("Miss distance (m)");
+            ui.label(format!("{:.1}", cdm.miss_distance_m));
+            ui.label(format!("{:.1}", probability.miss_distance_m));
+            ui.end_row();
+            ui.label("Relative speed (km/s)");
+            ui.label("");
+            ui.label(format!(
+                "{:.3}",
+                probability.relative_speed_m_per_s / 1000.0
+            ));
+            ui.end_row();
+            ui.label("Hard-body radius (m)");
+            ui.label(
+                cdm.hard_body_radius_m
+                    .map_or(String::new(), |radius| format!("{radius:.2}")),
+            );
+            ui.label(format!("{:.2}", probability.hard_body_radius_m));
+            ui.end_row();
+            ui.label("Encounter-plane 1σ major / minor (m)");
+            ui.label("");
+            ui.label(format!(
+                "{:.1} / {:.1}",
+                probability.sigma_major_m, probability.sigma_minor_m
+            ));
+            ui.end_row();
+            ui.label("Pc");
+            ui.label(
+                match (
+                    &cdm.collision_probability,
+                    &cdm.collision_probability_method,
+                ) {
+                    (Some(pc), Some(method)) => format!("{pc:.3e} ({method})"),
+                    (Some(pc), None) => format!("{pc:.3e}"),
+                    (None, _) => String::new(),
+                },
+            );
+            ui.label(format!(
+                "{} (Foster / Chan / Alfano)",
+                format_pc(probability)
+            ));
+            ui.end_row();
+        });
+    }
+}
@@ -0,0 +1,198 @@
+use std::sync::mpsc;
+
+use eframe::egui;
+use egui_plot::{Line, Plot, PlotPoints, Points};
+use strum::IntoEnumIterator;
+
+use crate::decay_trend::{ElementTrendPoint, TrendQuantity, calibrate_drag};
+use crate::ui::actions::{MyApp, SIMULATION_MAX_UI_UPDATE_PERIOD_MS};
+use crate::ui::fields::SimulationBoolField;
+
+impl MyApp {
+    fn on_calibrate_drag(&mut self) {
+        let enable_space_weather = *self
+            .input_fields
+            .simulation_bools
+            .get(&SimulationBoolField::DragPowerEnableSpaceWeather)
+            .unwrap_or(&false);
+        let sets = self.tle_history.clone();
+        let (tx, rx) = mpsc::channel();
+        self.decay_calibration_rx = Some(rx);
+        self.decay_calibration = None;
+        self.run_status = format!("Calibrating drag over {} element sets...", sets.len());
+
+        std::thread::spawn(move || {
+            let result = calibrate_drag(&sets, enable_space_weather).map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+    }
+
+    pub fn poll_decay_calibration(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.decay_calibration_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(calibration)) => {
+                self.run_status = format!(
+                    "Calibrated C_d·A/m = {:.5} m²/kg (B* {:.4e}) over {} intervals.",
+                    calibration.drag_area_per_mass_m2_per_kg,
+                    calibration.calibrated_bstar(),
+                    calibration.intervals.len()
+                );
+                self.decay_calibration = Some(calibration);
+                self.decay_calibration_rx = None;
+            }
+            Ok(Err(e)) => {
+                self.run_status = format!("Drag calibration failed: {e}");
+                self.decay_calibration_rx = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(
+                    SIMULATION_MAX_UI_UPDATE_PERIOD_MS as u64,
+                ));
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.run_status = "Drag calibration worker stopped unexpectedly.".into();
+                self.decay_calibration_rx = None;
+            }
+        }
+    }
+
+    /// Replace the TLE by the latest set of the history with the calibrated B*, and drop the
+    /// history so that a run propagates that set alone.
+    fn on_use_calibrated_bstar(&mut self) {
+        let (Some(calibration), Some(latest)) = (&self.decay_calibration, self.tle_history.last())
+        else {
+            return;
+        };
+        let mut tle = latest.clone();
+        tle.bstar = calibration.calibrated_bstar();
+        match tle.to_satkit_tle().to_2line() {
+            Ok([line1, line2]) => {
+                self.tle_line0 = tle.name.clone();
+                self.tle_line1 = line1;
+                self.tle_line2 = line2;
+                self.try_parse_tle();
+                self.tle_history.clear();
+                self.tle_history_discontinuities.clear();
+                self.tle_accuracy = None;
+                self.tle_accuracy_rx = None;
+                self.run_status = format!(
+                    "Using the set of {} with the calibrated B* {:.4e}.",
+                    tle.epoch.as_iso8601(),
+                    tle.bstar
+                );
+            }
+            Err(e) => self.run_status = format!("Failed to format the calibrated TLE: {e}"),
+        }
+    }
+
+    pub fn decay_trend_section(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_label("Trend")
+            .selected_text(self.trend_quantity.label())
+            .show_ui(ui, |ui| {
+                for quantity in TrendQuantity::iter() {
+                    ui.selectable_value(&mut self.trend_quantity, quantity, quantity.label());
+                }
+            });
+        let quantity = self.trend_quantity;
+        let start = self.tle_history[0].epoch;
+        let points: Vec<[f64; 2]> = self
+            .tle_history
+            .iter()
+            .map(|tle| {
+                let point = ElementTrendPoint::new(tle);
+                [(point.epoch - start).as_days(), quantity.value(&point)]
+            })
+            .collect();
+        Plot::new("element_trend_plot")
+            .height(200.0)
+            .x_axis_label("Days since first set")
+            .y_axis_label(quantity.label())
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(
+                    quantity.label(),
+                    PlotPoints::from(points.clone()),
+                ));
+                plot_ui.points(Points::new(quantity.label(), PlotPoints::from(points)).radius(2.0));
+            });
+
+        ui.label(
+            "Fits C_d·A/m so that NRLMSISE-00 reproduces the decay of the mean semi-major axis \
+             between sets. Manoeuvres between sets bias the fit.",
+        );
+        ui.horizontal(|ui| {
+            let running = self.decay_calibration_rx.is_some();
+            if ui
+                .add_enabled(!running, egui::Button::new("Calibrate Drag"))
+                .clicked()
+            {
+                self.on_calibrate_drag();
+            }
+            if ui
+                .add_enabled(
+                    self.decay_calibration.is_some(),
+                    egui::Button::new("Use Calibrated B* from Latest Set"),
+                )
+                .clicked()
+            {
+                self.on_use_calibrated_bstar();
+            }
+        });
+
+        let Some(calibration) = &self.decay_calibration else {
+            return;
+        };
+        egui::Grid::new("decay_calibration_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Fitted C_d·A/m (m²/kg)");
+                ui.label(match calibration.sigma_m2_per_kg {
+                    Some(sigma) => format!(
+                        "{:.5} ± {sigma:.5}",
+                        calibration.drag_area_per_mass_m2_per_kg
+                    ),
+                    None => format!("{:.5}", calibration.drag_area_per_mass_m2_per_kg),
+                });
+                ui.end_row();
+                ui.label("Calibrated B*");
+                ui.label(format!("{:.4e}", calibration.calibrated_bstar()));
+                ui.end_row();
+                ui.label("Mean C_d·A/m from the sets' B* (m²/kg)");
+                ui.label(format!(
+                    "{:.5}",
+                    calibration.bstar_drag_area_per_mass_m2_per_kg
+                ));
+                ui.end_row();
+                if let Ok(satellite) = self.read_satellite() {
+                    ui.label("Equivalent mass for the satellite's C_d·A (kg)");
+                    ui.label(format!("{:.2}", calibration.equivalent_mass_kg(&satellite)));
+                    ui.end_row();
+                }
+            });
+
+        egui::CollapsingHeader::new("Decay per interval").show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .id_salt("decay_interval_scroll")
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("decay_interval_grid")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("From (UTC)");
+                            ui.label("To (UTC)");
+                            ui.label("Observed decay (m)");
+                            ui.label("Fitted decay (m)");
+                            ui.end_row();
+                            for interval in &calibration.intervals {
+                                ui.label(interval.start.as_iso8601());
+                                ui.label(interval.end.as_iso8601());
+                                ui.label(format!("{:.1}", interval.observed_decay_m));
+                                ui.label(format!("{:.1}", interval.predicted_decay_m));
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+    }
+}
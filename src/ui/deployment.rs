@@ -0,0 +1,79 @@
+use eframe::egui;
+use strum::IntoEnumIterator;
+
+use crate::deployment::DeploymentTrigger;
+use crate::ui::actions::MyApp;
+use crate::ui::fields::DeploymentEventField;
+
+impl MyApp {
+    pub fn deployment_events_section(&mut self, ui: &mut egui::Ui) {
+        ui.label(egui::RichText::new("Deployment Events").strong());
+        ui.label(
+            "Drag sails, jettisons, ... Each replaces the drag Cd and area once triggered, and \
+             removes the jettisoned mass.",
+        );
+
+        let mut remove_index = None;
+        egui::Grid::new("deployment_event_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Name");
+                ui.label("Trigger");
+                ui.label("C_d after");
+                ui.label("Area after (m²)");
+                ui.label("Jettisoned (kg)");
+                ui.label("");
+                ui.end_row();
+                for (i, event) in self.input_fields.deployment_events.iter().enumerate() {
+                    ui.label(&event.name);
+                    ui.label(format!("{} {}", event.trigger.label(), event.threshold));
+                    ui.label(format!("{:.3}", event.drag_coefficient));
+                    ui.label(format!("{:.4}", event.drag_area_m2));
+                    ui.label(format!("{:.3}", event.jettisoned_mass_kg));
+                    if ui.button("Remove").clicked() {
+                        remove_index = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = remove_index {
+            self.input_fields.deployment_events.remove(i);
+        }
+
+        for f in DeploymentEventField::iter() {
+            let mut val = self
+                .input_fields
+                .deployment_event_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .deployment_event_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Trigger")
+                .selected_text(self.input_fields.deployment_trigger.label())
+                .show_ui(ui, |ui| {
+                    for trigger in DeploymentTrigger::iter() {
+                        ui.selectable_value(
+                            &mut self.input_fields.deployment_trigger,
+                            trigger,
+                            trigger.label(),
+                        );
+                    }
+                });
+            if ui.button("Add Event").clicked() {
+                match self.read_deployment_event() {
+                    Ok(event) => self.input_fields.deployment_events.push(event),
+                    Err(e) => self.run_status = format!("Invalid deployment event: {e}"),
+                }
+            }
+        });
+    }
+}
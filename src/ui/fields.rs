@@ -3,7 +3,17 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
-use crate::initial_state_model::TleData;
+use crate::attitude::AttitudeMode;
+use crate::constellation::{SeparationDirection, WalkerPatternKind};
+use crate::deployment::{DeploymentEvent, DeploymentTrigger};
+use crate::initial_state_model::{GroundTarget, TleData};
+use crate::maneuver_planner::TransferMethod;
+use crate::monte_carlo::{DispersedParameter, Dispersion, DistributionKind};
+use crate::parameter_sweep::{SweepAxis, SweepParameter};
+use crate::propulsion::{ScheduledBurn, ThrustDirection};
+use crate::solar_power::{SolarArray, SolarArrayMounting};
+use crate::spacecraft_geometry::{Panel, PanelKind};
+use crate::tle_history::TleSelection;
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
 pub enum TleParameterField {
@@ -107,6 +117,8 @@ pub enum SatelliteField {
     Name,
     DragCoefficient,
     DragAreaM2,
+    NoseRadiusM,
+    HardBodyRadiusM,
 }
 impl SatelliteField {
     pub fn label(&self) -> &'static str {
@@ -114,6 +126,396 @@ impl SatelliteField {
             SatelliteField::Name => "Name",
             SatelliteField::DragCoefficient => "Drag Coefficient (C_d)",
             SatelliteField::DragAreaM2 => "Drag Area (m²)",
+            SatelliteField::NoseRadiusM => "Nose Radius (m, for perigee-pass heating)",
+            SatelliteField::HardBodyRadiusM => "Hard-Body Radius (m, for collision probability)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum AttitudeField {
+    InertialRollDeg,
+    InertialPitchDeg,
+    InertialYawDeg,
+    TumbleRateDegPerS,
+}
+impl AttitudeField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AttitudeField::InertialRollDeg => "Inertial Roll (deg)",
+            AttitudeField::InertialPitchDeg => "Inertial Pitch (deg)",
+            AttitudeField::InertialYawDeg => "Inertial Yaw (deg)",
+            AttitudeField::TumbleRateDegPerS => "Tumble Rate (deg/s)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum PanelField {
+    Name,
+    AreaM2,
+    NormalX,
+    NormalY,
+    NormalZ,
+    SpecularReflectivity,
+    DiffuseReflectivity,
+}
+impl PanelField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PanelField::Name => "Panel Name",
+            PanelField::AreaM2 => "Panel Area (m²)",
+            PanelField::NormalX => "Normal X (body)",
+            PanelField::NormalY => "Normal Y (body)",
+            PanelField::NormalZ => "Normal Z (body)",
+            PanelField::SpecularReflectivity => "Specular Reflectivity",
+            PanelField::DiffuseReflectivity => "Diffuse Reflectivity",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum SolarArrayField {
+    Name,
+    AreaM2,
+    CellEfficiency,
+    TemperatureCoefficientPerC,
+    OperatingTemperatureC,
+    OrientationX,
+    OrientationY,
+    OrientationZ,
+}
+impl SolarArrayField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SolarArrayField::Name => "Array Name",
+            SolarArrayField::AreaM2 => "Cell Area (m²)",
+            SolarArrayField::CellEfficiency => "Cell Efficiency at 28 °C (0-1)",
+            SolarArrayField::TemperatureCoefficientPerC => "Temperature Coefficient (1/°C)",
+            SolarArrayField::OperatingTemperatureC => "Operating Temperature (°C)",
+            SolarArrayField::OrientationX => "Normal / Drive Axis X (body)",
+            SolarArrayField::OrientationY => "Normal / Drive Axis Y (body)",
+            SolarArrayField::OrientationZ => "Normal / Drive Axis Z (body)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum DeploymentEventField {
+    Name,
+    Threshold,
+    DragCoefficient,
+    DragAreaM2,
+    JettisonedMassKg,
+}
+impl DeploymentEventField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeploymentEventField::Name => "Event Name",
+            DeploymentEventField::Threshold => "Trigger Threshold (days or km)",
+            DeploymentEventField::DragCoefficient => "Drag Coefficient After (C_d)",
+            DeploymentEventField::DragAreaM2 => "Drag Area After (m²)",
+            DeploymentEventField::JettisonedMassKg => "Jettisoned Mass (kg, optional)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum GroundTargetField {
+    Name,
+    LatitudeDeg,
+    LongitudeDeg,
+    PolygonVertices,
+    MinSunElevationDeg,
+}
+impl GroundTargetField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GroundTargetField::Name => "Name",
+            GroundTargetField::LatitudeDeg => "Latitude (deg)",
+            GroundTargetField::LongitudeDeg => "Longitude (deg)",
+            GroundTargetField::PolygonVertices => {
+                "Polygon vertices (\"lat lon; lat lon; ...\", blank for a point)"
+            }
+            GroundTargetField::MinSunElevationDeg => "Min Sun Elevation (deg)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum ImagerField {
+    MaxOffNadirDeg,
+    FocalLengthMm,
+    PixelPitchUm,
+}
+impl ImagerField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImagerField::MaxOffNadirDeg => "Max Off-Nadir Angle (deg)",
+            ImagerField::FocalLengthMm => "Focal Length (mm)",
+            ImagerField::PixelPitchUm => "Pixel Pitch (µm)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum ThrusterField {
+    ThrustN,
+    SpecificImpulseS,
+    DryMassKg,
+    WetMassKg,
+    DutyCycle,
+}
+impl ThrusterField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThrusterField::ThrustN => "Thrust (N)",
+            ThrusterField::SpecificImpulseS => "Specific Impulse (s)",
+            ThrusterField::DryMassKg => "Dry Mass (kg)",
+            ThrusterField::WetMassKg => "Wet Mass (kg)",
+            ThrusterField::DutyCycle => "Duty Cycle (0-1)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum BurnField {
+    Name,
+    StartDaysSinceEpoch,
+    DurationMinutes,
+}
+impl BurnField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BurnField::Name => "Burn Name",
+            BurnField::StartDaysSinceEpoch => "Start (days since epoch)",
+            BurnField::DurationMinutes => "Duration (minutes)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum StationKeepingField {
+    TargetAltitudeKm,
+    DeadbandKm,
+    StartDaysSinceEpoch,
+    DurationDays,
+}
+impl StationKeepingField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StationKeepingField::TargetAltitudeKm => "Target Mean Altitude (km)",
+            StationKeepingField::DeadbandKm => "Deadband (± km)",
+            StationKeepingField::StartDaysSinceEpoch => "Start (days since epoch)",
+            StationKeepingField::DurationDays => "Duration (days)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum ManeuverField {
+    TargetPerigeeAltitudeKm,
+    TargetApogeeAltitudeKm,
+    StartDaysSinceEpoch,
+    BiEllipticApogeeAltitudeKm,
+    MaxBurnMinutes,
+}
+impl ManeuverField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ManeuverField::TargetPerigeeAltitudeKm => "Target Perigee Altitude (km)",
+            ManeuverField::TargetApogeeAltitudeKm => "Target Apogee Altitude (km)",
+            ManeuverField::StartDaysSinceEpoch => "Earliest Start (days since epoch)",
+            ManeuverField::BiEllipticApogeeAltitudeKm => "Bi-elliptic Apogee Altitude (km)",
+            ManeuverField::MaxBurnMinutes => "Max Burn Duration (min, multi-burn)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum MonteCarloField {
+    Samples,
+    Seed,
+    Threads,
+}
+impl MonteCarloField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MonteCarloField::Samples => "Samples",
+            MonteCarloField::Seed => "Seed",
+            MonteCarloField::Threads => "Threads (blank for all cores)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum ConjunctionField {
+    CatalogFile,
+    StartUtc,
+    DurationDays,
+    RadialKm,
+    AlongTrackKm,
+    CrossTrackKm,
+    Threads,
+    ObjectHardBodyRadiusM,
+}
+impl ConjunctionField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConjunctionField::CatalogFile => "Catalog file (TLEs)",
+            ConjunctionField::StartUtc => "Start (UTC, blank for the TLE epoch)",
+            ConjunctionField::DurationDays => "Duration (days)",
+            ConjunctionField::RadialKm => "Screening volume radial (km)",
+            ConjunctionField::AlongTrackKm => "Screening volume along-track (km)",
+            ConjunctionField::CrossTrackKm => "Screening volume cross-track (km)",
+            ConjunctionField::Threads => "Threads (blank for all cores)",
+            ConjunctionField::ObjectHardBodyRadiusM => "Other object hard-body radius (m, for Pc)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum AvoidanceField {
+    MaxLeadTimeHours,
+    LeadTimeSteps,
+    MaxDeltaVMPerS,
+    DeltaVSteps,
+    AcceptablePc,
+    Threads,
+}
+impl AvoidanceField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AvoidanceField::MaxLeadTimeHours => "Max lead time before TCA (hours)",
+            AvoidanceField::LeadTimeSteps => "Lead time steps",
+            AvoidanceField::MaxDeltaVMPerS => "Max along-track delta-v (m/s)",
+            AvoidanceField::DeltaVSteps => "Delta-v steps (each way)",
+            AvoidanceField::AcceptablePc => "Acceptable Pc",
+            AvoidanceField::Threads => "Threads (blank for all cores)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum OrbitDeterminationField {
+    ObservationFile,
+    PositionSigmaM,
+    VelocitySigmaMPerS,
+    RangeSigmaM,
+    AngleSigmaDeg,
+    RangeRateSigmaMPerS,
+    MaxIterations,
+}
+impl OrbitDeterminationField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OrbitDeterminationField::ObservationFile => "Observation File (CSV)",
+            OrbitDeterminationField::PositionSigmaM => "State Position 1σ (m)",
+            OrbitDeterminationField::VelocitySigmaMPerS => "State Velocity 1σ (m/s)",
+            OrbitDeterminationField::RangeSigmaM => "Range 1σ (m)",
+            OrbitDeterminationField::AngleSigmaDeg => "Azimuth/Elevation 1σ (deg)",
+            OrbitDeterminationField::RangeRateSigmaMPerS => "Range Rate 1σ (m/s)",
+            OrbitDeterminationField::MaxIterations => "Max Iterations",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum UncertaintyKind {
+    #[default]
+    None,
+    Covariance,
+    TleAge,
+}
+impl UncertaintyKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            UncertaintyKind::None => "None",
+            UncertaintyKind::Covariance => "Initial covariance",
+            UncertaintyKind::TleAge => "TLE age error growth",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum UncertaintyField {
+    RadialSigmaM,
+    AlongTrackSigmaM,
+    CrossTrackSigmaM,
+    RadialVelocitySigmaMPerS,
+    AlongTrackVelocitySigmaMPerS,
+    CrossTrackVelocitySigmaMPerS,
+    BstarRelativeSigma,
+    RadialGrowthMPerDay,
+    AlongTrackGrowthMPerDay,
+    CrossTrackGrowthMPerDay,
+    AlongTrackGrowthMPerDay2,
+}
+impl UncertaintyField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            UncertaintyField::RadialSigmaM => "Radial 1σ at epoch (m)",
+            UncertaintyField::AlongTrackSigmaM => "Along-track 1σ at epoch (m)",
+            UncertaintyField::CrossTrackSigmaM => "Cross-track 1σ at epoch (m)",
+            UncertaintyField::RadialVelocitySigmaMPerS => "Radial velocity 1σ (m/s)",
+            UncertaintyField::AlongTrackVelocitySigmaMPerS => "Along-track velocity 1σ (m/s)",
+            UncertaintyField::CrossTrackVelocitySigmaMPerS => "Cross-track velocity 1σ (m/s)",
+            UncertaintyField::BstarRelativeSigma => "B* 1σ (fraction)",
+            UncertaintyField::RadialGrowthMPerDay => "Radial growth (m/day)",
+            UncertaintyField::AlongTrackGrowthMPerDay => "Along-track growth (m/day)",
+            UncertaintyField::CrossTrackGrowthMPerDay => "Cross-track growth (m/day)",
+            UncertaintyField::AlongTrackGrowthMPerDay2 => "Along-track growth (m/day²)",
+        }
+    }
+
+    /// Whether the field is an input of the `kind` model.
+    pub fn applies_to(&self, kind: UncertaintyKind) -> bool {
+        match self {
+            UncertaintyField::RadialSigmaM
+            | UncertaintyField::AlongTrackSigmaM
+            | UncertaintyField::CrossTrackSigmaM => kind != UncertaintyKind::None,
+            UncertaintyField::RadialVelocitySigmaMPerS
+            | UncertaintyField::AlongTrackVelocitySigmaMPerS
+            | UncertaintyField::CrossTrackVelocitySigmaMPerS
+            | UncertaintyField::BstarRelativeSigma => kind == UncertaintyKind::Covariance,
+            UncertaintyField::RadialGrowthMPerDay
+            | UncertaintyField::AlongTrackGrowthMPerDay
+            | UncertaintyField::CrossTrackGrowthMPerDay
+            | UncertaintyField::AlongTrackGrowthMPerDay2 => kind == UncertaintyKind::TleAge,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum SweepField {
+    AxisStart,
+    AxisStop,
+    AxisSteps,
+    Threads,
+}
+impl SweepField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SweepField::AxisStart => "Start",
+            SweepField::AxisStop => "Stop",
+            SweepField::AxisSteps => "Steps",
+            SweepField::Threads => "Threads (blank for all cores)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum TleAccuracyField {
+    MaxAgeDays,
+    BinDays,
+    ToleranceKm,
+}
+impl TleAccuracyField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TleAccuracyField::MaxAgeDays => "Maximum prediction age (days)",
+            TleAccuracyField::BinDays => "Age bin width (days)",
+            TleAccuracyField::ToleranceKm => "Tolerable RMS error (km)",
         }
     }
 }
@@ -146,6 +548,28 @@ impl SimulationBoolField {
     }
 }
 
+#[derive(Debug, Clone, Eq, Hash, PartialEq, EnumIter, Serialize, Deserialize)]
+pub enum ConstellationField {
+    WalkerTotalSatellites,
+    WalkerPlanes,
+    WalkerPhasing,
+    RideshareChildCount,
+    RideshareSeparationSpeedMPerS,
+    RideshareConeHalfAngleDeg,
+}
+impl ConstellationField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConstellationField::WalkerTotalSatellites => "Walker Total Satellites (T)",
+            ConstellationField::WalkerPlanes => "Walker Planes (P)",
+            ConstellationField::WalkerPhasing => "Walker Phasing (F)",
+            ConstellationField::RideshareChildCount => "Rideshare Child Count",
+            ConstellationField::RideshareSeparationSpeedMPerS => "Rideshare Separation Speed (m/s)",
+            ConstellationField::RideshareConeHalfAngleDeg => "Rideshare Cone Half-Angle (deg)",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MyAppInputFields {
     pub ground_station_inputs: HashMap<GroundStationField, String>,
@@ -153,5 +577,116 @@ pub struct MyAppInputFields {
     pub simulation_inputs: HashMap<SimulationField, String>,
     pub simulation_bools: HashMap<SimulationBoolField, bool>,
 
+    #[serde(default)]
+    pub ground_target_inputs: HashMap<GroundTargetField, String>,
+    #[serde(default)]
+    pub ground_target_require_sunlit: bool,
+    #[serde(default)]
+    pub ground_targets: Vec<GroundTarget>,
+
     pub tle_parameter_inputs: HashMap<TleParameterField, String>,
+
+    #[serde(default)]
+    pub tle_history_path: String,
+    #[serde(default)]
+    pub tle_selection: TleSelection,
+    #[serde(default)]
+    pub tle_accuracy_inputs: HashMap<TleAccuracyField, String>,
+
+    #[serde(default)]
+    pub constellation_inputs: HashMap<ConstellationField, String>,
+    #[serde(default)]
+    pub walker_pattern_kind: WalkerPatternKind,
+    #[serde(default)]
+    pub rideshare_separation_direction: SeparationDirection,
+
+    #[serde(default)]
+    pub attitude_inputs: HashMap<AttitudeField, String>,
+    #[serde(default)]
+    pub attitude_mode: AttitudeMode,
+
+    #[serde(default)]
+    pub panel_inputs: HashMap<PanelField, String>,
+    #[serde(default)]
+    pub panel_kind: PanelKind,
+    /// Panels added so far; empty means the constant drag area is used.
+    #[serde(default)]
+    pub panels: Vec<Panel>,
+
+    #[serde(default)]
+    pub solar_array_inputs: HashMap<SolarArrayField, String>,
+    #[serde(default)]
+    pub solar_array_mounting: SolarArrayMounting,
+    #[serde(default)]
+    pub solar_arrays: Vec<SolarArray>,
+
+    #[serde(default)]
+    pub deployment_event_inputs: HashMap<DeploymentEventField, String>,
+    #[serde(default)]
+    pub deployment_trigger: DeploymentTrigger,
+    #[serde(default)]
+    pub deployment_events: Vec<DeploymentEvent>,
+
+    /// Leave the maximum off-nadir angle empty for no imager.
+    #[serde(default)]
+    pub imager_inputs: HashMap<ImagerField, String>,
+
+    /// Leave the thrust empty for no thruster.
+    #[serde(default)]
+    pub thruster_inputs: HashMap<ThrusterField, String>,
+    #[serde(default)]
+    pub burn_inputs: HashMap<BurnField, String>,
+    #[serde(default)]
+    pub burn_direction: ThrustDirection,
+    #[serde(default)]
+    pub burn_schedule: Vec<ScheduledBurn>,
+    /// Leave the target altitude empty for no station keeping.
+    #[serde(default)]
+    pub station_keeping_inputs: HashMap<StationKeepingField, String>,
+
+    #[serde(default)]
+    pub maneuver_inputs: HashMap<ManeuverField, String>,
+    #[serde(default)]
+    pub transfer_method: TransferMethod,
+
+    #[serde(default)]
+    pub monte_carlo_inputs: HashMap<MonteCarloField, String>,
+    #[serde(default)]
+    pub dispersion_spread_input: String,
+    #[serde(default)]
+    pub dispersed_parameter: DispersedParameter,
+    #[serde(default)]
+    pub dispersion_distribution: DistributionKind,
+    #[serde(default)]
+    pub dispersions: Vec<Dispersion>,
+
+    #[serde(default)]
+    pub conjunction_inputs: HashMap<ConjunctionField, String>,
+    #[serde(default)]
+    pub cdm_path: String,
+    #[serde(default)]
+    pub avoidance_inputs: HashMap<AvoidanceField, String>,
+
+    #[serde(default)]
+    pub orbit_determination_inputs: HashMap<OrbitDeterminationField, String>,
+    #[serde(default)]
+    pub od_solve_for_bstar: bool,
+    /// Start the fit from the current TLE (also the source of the catalog fields).
+    #[serde(default)]
+    pub od_use_current_tle: bool,
+
+    #[serde(default)]
+    pub uncertainty_kind: UncertaintyKind,
+    #[serde(default)]
+    pub uncertainty_inputs: HashMap<UncertaintyField, String>,
+    /// Standard deviations of timing error added to either side of every pass window.
+    #[serde(default)]
+    pub pass_window_sigmas_input: String,
+
+    #[serde(default)]
+    pub sweep_inputs: HashMap<SweepField, String>,
+    #[serde(default)]
+    pub sweep_parameter: SweepParameter,
+    #[serde(default)]
+    pub sweep_axes: Vec<SweepAxis>,
 }
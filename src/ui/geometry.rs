@@ -0,0 +1,86 @@
+use eframe::egui;
+use strum::IntoEnumIterator;
+
+use crate::spacecraft_geometry::{PanelKind, PanelPreset};
+use crate::ui::actions::MyApp;
+use crate::ui::fields::PanelField;
+
+impl MyApp {
+    pub fn panel_geometry_section(&mut self, ui: &mut egui::Ui) {
+        ui.label(egui::RichText::new("Panel Geometry").strong());
+        ui.label("Leave empty to use the constant drag area.");
+        ui.horizontal(|ui| {
+            for preset in PanelPreset::iter() {
+                if ui.button(preset.label()).clicked() {
+                    self.input_fields.panels = preset.panels();
+                }
+            }
+            if ui.button("Clear Panels").clicked() {
+                self.input_fields.panels.clear();
+            }
+        });
+
+        let mut remove_index = None;
+        egui::Grid::new("panel_grid").striped(true).show(ui, |ui| {
+            ui.label("Name");
+            ui.label("Kind");
+            ui.label("Area (m²)");
+            ui.label("Normal (body)");
+            ui.label("ρ spec / ρ diff");
+            ui.label("");
+            ui.end_row();
+            for (i, panel) in self.input_fields.panels.iter().enumerate() {
+                ui.label(&panel.name);
+                ui.label(panel.kind.label());
+                ui.label(format!("{:.4}", panel.area_m2));
+                ui.label(format!(
+                    "[{:+.3}, {:+.3}, {:+.3}]",
+                    panel.normal_body[0], panel.normal_body[1], panel.normal_body[2]
+                ));
+                ui.label(format!(
+                    "{:.2} / {:.2}",
+                    panel.specular_reflectivity, panel.diffuse_reflectivity
+                ));
+                if ui.button("Remove").clicked() {
+                    remove_index = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = remove_index {
+            self.input_fields.panels.remove(i);
+        }
+
+        for f in PanelField::iter() {
+            let mut val = self
+                .input_fields
+                .panel_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .panel_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Panel Kind")
+                .selected_text(self.input_fields.panel_kind.label())
+                .show_ui(ui, |ui| {
+                    for kind in PanelKind::iter() {
+                        ui.selectable_value(&mut self.input_fields.panel_kind, kind, kind.label());
+                    }
+                });
+            if ui.button("Add Panel").clicked() {
+                match self.read_panel() {
+                    Ok(panel) => self.input_fields.panels.push(panel),
+                    Err(e) => self.run_status = format!("Invalid panel: {e}"),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,232 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+
+use eframe::egui;
+use strum::IntoEnumIterator;
+
+use crate::imaging::find_imaging_opportunities;
+use crate::initial_state_model::TargetShape;
+use crate::ui::actions::{MyApp, SIMULATION_MAX_UI_UPDATE_PERIOD_MS};
+use crate::ui::fields::{GroundTargetField, ImagerField};
+
+impl MyApp {
+    pub fn ground_targets_section(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Ground Targets");
+        let mut remove_index = None;
+        egui::Grid::new("ground_target_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Name");
+                ui.label("Shape");
+                ui.label("Min Sun elevation (deg)");
+                ui.label("Sunlit satellite");
+                ui.label("");
+                ui.end_row();
+                for (i, target) in self.input_fields.ground_targets.iter().enumerate() {
+                    ui.label(&target.name);
+                    ui.label(match &target.shape {
+                        TargetShape::Point {
+                            latitude_deg,
+                            longitude_deg,
+                        } => format!("Point {latitude_deg:.4}, {longitude_deg:.4}"),
+                        TargetShape::Polygon { vertices_deg } => {
+                            format!("Polygon of {} vertices", vertices_deg.len())
+                        }
+                    });
+                    ui.label(format!("{:.1}", target.min_sun_elevation_deg));
+                    ui.label(if target.require_sunlit_satellite {
+                        "Yes"
+                    } else {
+                        "No"
+                    });
+                    if ui.button("Remove").clicked() {
+                        remove_index = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = remove_index {
+            self.input_fields.ground_targets.remove(i);
+        }
+
+        for f in GroundTargetField::iter() {
+            let mut val = self
+                .input_fields
+                .ground_target_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .ground_target_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut self.input_fields.ground_target_require_sunlit,
+                "Satellite out of eclipse",
+            );
+            if ui.button("Add Target").clicked() {
+                match self.read_ground_target() {
+                    Ok(target) => self.input_fields.ground_targets.push(target),
+                    Err(e) => self.run_status = format!("Invalid ground target: {e}"),
+                }
+            }
+        });
+    }
+
+    pub fn imager_section(&mut self, ui: &mut egui::Ui) {
+        ui.label(egui::RichText::new("Imager").strong());
+        ui.label("Leave the off-nadir angle empty for no imager.");
+        for f in ImagerField::iter() {
+            let mut val = self
+                .input_fields
+                .imager_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .imager_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+    }
+
+    fn on_find_imaging_opportunities(&mut self) {
+        let initial = match self.read_initial_simulation_state() {
+            Ok(initial) => initial,
+            Err(e) => {
+                self.run_status = format!("Error initializing imaging search: {e}");
+                return;
+            }
+        };
+
+        let progress = Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = mpsc::channel();
+        self.imaging_progress = progress.clone();
+        self.imaging_targets = initial.ground_targets.len();
+        self.imaging_rx = Some(rx);
+        self.imaging_opportunities = None;
+        self.run_status = format!(
+            "Searching {} targets over {} days...",
+            self.imaging_targets, initial.simulation_settings.max_days
+        );
+
+        std::thread::spawn(move || {
+            let result = find_imaging_opportunities(&initial, &progress).map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+    }
+
+    pub fn poll_imaging(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.imaging_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(opportunities)) => {
+                self.run_status = format!("Found {} imaging opportunities.", opportunities.len());
+                self.imaging_opportunities = Some(opportunities);
+                self.imaging_rx = None;
+            }
+            Ok(Err(e)) => {
+                self.run_status = format!("Imaging search failed: {e}");
+                self.imaging_rx = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(
+                    SIMULATION_MAX_UI_UPDATE_PERIOD_MS as u64,
+                ));
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.run_status = "Imaging search worker stopped unexpectedly.".into();
+                self.imaging_rx = None;
+            }
+        }
+    }
+
+    pub fn imaging_section(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Imaging Opportunities");
+        ui.label(
+            "Finds when each ground target is within the imager's off-nadir limit and lit by \
+             the Sun, over the simulation's max days (SGP4 from the TLE or TLE history, without \
+             burns).",
+        );
+        ui.horizontal(|ui| {
+            let running = self.imaging_rx.is_some();
+            if ui
+                .add_enabled(!running, egui::Button::new("Find Imaging Opportunities"))
+                .clicked()
+            {
+                self.on_find_imaging_opportunities();
+            }
+            if running {
+                ui.label(format!(
+                    "{} / {} targets done",
+                    self.imaging_progress.load(Ordering::Relaxed),
+                    self.imaging_targets
+                ));
+            }
+        });
+
+        let Some(opportunities) = &self.imaging_opportunities else {
+            return;
+        };
+        if opportunities.is_empty() {
+            ui.label("No imaging opportunities.");
+            return;
+        }
+        egui::ScrollArea::vertical()
+            .id_salt("imaging_scroll")
+            .max_height(250.0)
+            .show(ui, |ui| {
+                egui::Grid::new("imaging_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("Target");
+                        ui.label("Start (UTC)");
+                        ui.label("End (UTC)");
+                        ui.label("Best (UTC)");
+                        ui.label("Aim lat / lon (deg)");
+                        ui.label("Off-nadir (deg)");
+                        ui.label("Azimuth / elevation (deg)");
+                        ui.label("Slant range (km)");
+                        ui.label("GSD (m)");
+                        ui.label("Sun azimuth / elevation (deg)");
+                        ui.label("Phase angle (deg)");
+                        ui.end_row();
+                        for opportunity in opportunities {
+                            let best = &opportunity.best;
+                            ui.label(&opportunity.target);
+                            ui.label(opportunity.start.as_iso8601());
+                            ui.label(opportunity.end.as_iso8601());
+                            ui.label(best.time.as_iso8601());
+                            ui.label(format!(
+                                "{:.4} / {:.4}",
+                                best.aim_latitude_deg, best.aim_longitude_deg
+                            ));
+                            ui.label(format!("{:.2}", best.off_nadir_deg));
+                            ui.label(format!(
+                                "{:.1} / {:.1}",
+                                best.azimuth_deg, best.elevation_deg
+                            ));
+                            ui.label(format!("{:.1}", best.slant_range_m / 1000.0));
+                            ui.label(format!("{:.2}", best.ground_sample_distance_m));
+                            ui.label(format!(
+                                "{:.1} / {:.1}",
+                                best.sun_azimuth_deg, best.sun_elevation_deg
+                            ));
+                            ui.label(format!("{:.1}", best.phase_angle_deg));
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
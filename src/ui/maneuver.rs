@@ -0,0 +1,151 @@
+use eframe::egui;
+use strum::IntoEnumIterator;
+
+use crate::maneuver_planner::{TransferMethod, plan_maneuver};
+use crate::ui::actions::MyApp;
+use crate::ui::fields::ManeuverField;
+
+impl MyApp {
+    fn on_plan_maneuver(&mut self) {
+        let Some(tle) = &self.tle_data else {
+            self.run_status = "No valid TLE available.".into();
+            return;
+        };
+        let thruster = match self.read_satellite() {
+            Ok(satellite) => satellite.thruster,
+            Err(e) => {
+                self.run_status = format!("Invalid satellite: {e}");
+                return;
+            }
+        };
+        let Some(thruster) = thruster else {
+            self.run_status = "Maneuver planning needs a thruster.".into();
+            return;
+        };
+        let request = match self.read_maneuver_request() {
+            Ok(request) => request,
+            Err(e) => {
+                self.run_status = format!("Invalid maneuver: {e}");
+                return;
+            }
+        };
+
+        match plan_maneuver(tle, &thruster, &request) {
+            Ok(plan) => {
+                self.run_status = format!(
+                    "Planned {} burns, {:.3} m/s.",
+                    plan.burns.len(),
+                    plan.total_delta_v_m_per_s
+                );
+                self.maneuver_plan = Some(plan);
+            }
+            Err(e) => {
+                self.run_status = format!("Maneuver planning failed: {e}");
+                self.maneuver_plan = None;
+            }
+        }
+    }
+
+    pub fn maneuver_planner_section(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Maneuver Planner");
+        ui.label("Plans a coplanar transfer with the satellite's thruster, starting at wet mass.");
+        for f in ManeuverField::iter() {
+            let mut val = self
+                .input_fields
+                .maneuver_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .maneuver_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Transfer Method")
+                .selected_text(self.input_fields.transfer_method.label())
+                .show_ui(ui, |ui| {
+                    for method in TransferMethod::iter() {
+                        ui.selectable_value(
+                            &mut self.input_fields.transfer_method,
+                            method,
+                            method.label(),
+                        );
+                    }
+                });
+            if ui.button("Plan Maneuver").clicked() {
+                self.on_plan_maneuver();
+            }
+        });
+
+        let Some(plan) = &self.maneuver_plan else {
+            return;
+        };
+        egui::Grid::new("maneuver_summary_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Total Δv (m/s)");
+                ui.label(format!(
+                    "{:.3} (impulsive {:.3})",
+                    plan.total_delta_v_m_per_s, plan.impulsive_delta_v_m_per_s
+                ));
+                ui.end_row();
+                ui.label("Propellant (kg)");
+                ui.label(format!("{:.4}", plan.total_propellant_kg));
+                ui.end_row();
+                ui.label("Perigee achieved / target (km)");
+                ui.label(format!(
+                    "{:.2} / {:.2}",
+                    plan.achieved_perigee_altitude_km, plan.request.target_perigee_altitude_km
+                ));
+                ui.end_row();
+                ui.label("Apogee achieved / target (km)");
+                ui.label(format!(
+                    "{:.2} / {:.2}",
+                    plan.achieved_apogee_altitude_km, plan.request.target_apogee_altitude_km
+                ));
+                ui.end_row();
+            });
+
+        egui::ScrollArea::vertical()
+            .id_salt("maneuver_burns_scroll")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                egui::Grid::new("maneuver_burn_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("Burn");
+                        ui.label("Start (days)");
+                        ui.label("Duration (min)");
+                        ui.label("Direction");
+                        ui.label("Δv (m/s)");
+                        ui.label("Propellant (kg)");
+                        ui.label("Perigee × apogee after (km)");
+                        ui.end_row();
+                        for planned in &plan.burns {
+                            ui.label(&planned.burn.name);
+                            ui.label(format!("{:.5}", planned.burn.start_days_since_epoch));
+                            ui.label(format!("{:.2}", planned.burn.duration_minutes));
+                            ui.label(planned.burn.direction.label());
+                            ui.label(format!("{:.3}", planned.delta_v_m_per_s));
+                            ui.label(format!("{:.4}", planned.propellant_kg));
+                            ui.label(format!(
+                                "{:.2} × {:.2}",
+                                planned.perigee_altitude_km, planned.apogee_altitude_km
+                            ));
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if ui.button("Add Burns to Schedule").clicked() {
+            let burns = plan.burns.iter().map(|planned| planned.burn.clone());
+            self.input_fields.burn_schedule.extend(burns);
+            self.run_status = format!("Added {} burns to the schedule.", plan.burns.len());
+        }
+    }
+}
@@ -1,7 +1,24 @@
 mod actions;
+mod aerobraking;
+mod budget;
+mod collision_avoidance;
+mod conjunction;
+mod decay_trend;
+mod deployment;
 mod fields;
+mod geometry;
+mod imaging;
+mod maneuver;
+mod monte_carlo;
+mod orbit_determination;
+mod parameter_sweep;
+mod plots;
+mod power;
+mod propulsion;
 mod read_fields;
 mod sim_background_worker;
+mod tle_history;
+mod uncertainty;
 // mod view;
 
 pub use actions::main;
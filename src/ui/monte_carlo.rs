@@ -0,0 +1,281 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+
+use eframe::egui;
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints};
+use strum::IntoEnumIterator;
+
+use crate::monte_carlo::{DispersedParameter, DistributionKind, run_monte_carlo};
+use crate::ui::actions::{MyApp, SIMULATION_MAX_UI_UPDATE_PERIOD_MS};
+use crate::ui::fields::MonteCarloField;
+
+impl MyApp {
+    fn on_run_monte_carlo(&mut self) {
+        let nominal = match self.read_initial_simulation_state() {
+            Ok(nominal) => nominal,
+            Err(e) => {
+                self.run_status = format!("Error initializing Monte Carlo: {e}");
+                return;
+            }
+        };
+        let settings = match self.read_monte_carlo_settings() {
+            Ok(settings) => settings,
+            Err(e) => {
+                self.run_status = format!("Invalid Monte Carlo settings: {e}");
+                return;
+            }
+        };
+
+        let progress = Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = mpsc::channel();
+        self.monte_carlo_progress = progress.clone();
+        self.monte_carlo_samples = settings.samples;
+        self.monte_carlo_rx = Some(rx);
+        self.monte_carlo_result = None;
+        self.run_status = format!(
+            "Running {} Monte Carlo samples on {} threads...",
+            settings.samples, settings.threads
+        );
+
+        std::thread::spawn(move || {
+            let result = run_monte_carlo(&nominal, &settings, &progress).map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+    }
+
+    pub fn poll_monte_carlo(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.monte_carlo_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(result)) => {
+                let failed = result.samples.iter().filter(|s| s.error.is_some()).count();
+                self.run_status = format!(
+                    "Monte Carlo finished: {} samples, {} reentered, {} failed.",
+                    result.samples.len(),
+                    result.reentered_samples,
+                    failed
+                );
+                self.monte_carlo_result = Some(result);
+                self.monte_carlo_rx = None;
+            }
+            Ok(Err(e)) => {
+                self.run_status = format!("Monte Carlo failed: {e}");
+                self.monte_carlo_rx = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(
+                    SIMULATION_MAX_UI_UPDATE_PERIOD_MS as u64,
+                ));
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.run_status = "Monte Carlo worker stopped unexpectedly.".into();
+                self.monte_carlo_rx = None;
+            }
+        }
+    }
+
+    pub fn monte_carlo_section(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Monte Carlo");
+        ui.label("Runs the current inputs many times with the dispersions below applied.");
+        for f in MonteCarloField::iter() {
+            let mut val = self
+                .input_fields
+                .monte_carlo_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .monte_carlo_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+
+        let mut remove_index = None;
+        egui::Grid::new("dispersion_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Parameter");
+                ui.label("Distribution");
+                ui.label("Spread");
+                ui.label("");
+                ui.end_row();
+                for (i, dispersion) in self.input_fields.dispersions.iter().enumerate() {
+                    ui.label(dispersion.parameter.label());
+                    ui.label(dispersion.distribution.label());
+                    ui.label(format!("{}", dispersion.spread));
+                    if ui.button("Remove").clicked() {
+                        remove_index = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = remove_index {
+            self.input_fields.dispersions.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Parameter")
+                .selected_text(self.input_fields.dispersed_parameter.label())
+                .show_ui(ui, |ui| {
+                    for parameter in DispersedParameter::iter() {
+                        ui.selectable_value(
+                            &mut self.input_fields.dispersed_parameter,
+                            parameter,
+                            parameter.label(),
+                        );
+                    }
+                });
+            egui::ComboBox::from_label("Distribution")
+                .selected_text(self.input_fields.dispersion_distribution.label())
+                .show_ui(ui, |ui| {
+                    for distribution in DistributionKind::iter() {
+                        ui.selectable_value(
+                            &mut self.input_fields.dispersion_distribution,
+                            distribution,
+                            distribution.label(),
+                        );
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label("Spread");
+            ui.text_edit_singleline(&mut self.input_fields.dispersion_spread_input);
+            if ui.button("Add Dispersion").clicked() {
+                match self.read_dispersion() {
+                    Ok(dispersion) => self.input_fields.dispersions.push(dispersion),
+                    Err(e) => self.run_status = format!("Invalid dispersion: {e}"),
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let running = self.monte_carlo_rx.is_some();
+            if ui
+                .add_enabled(!running, egui::Button::new("Run Monte Carlo"))
+                .clicked()
+            {
+                self.on_run_monte_carlo();
+            }
+            if running {
+                ui.label(format!(
+                    "{} / {} samples done",
+                    self.monte_carlo_progress.load(Ordering::Relaxed),
+                    self.monte_carlo_samples
+                ));
+            }
+        });
+
+        let Some(result) = &self.monte_carlo_result else {
+            return;
+        };
+        egui::Grid::new("monte_carlo_summary_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Reentered");
+                ui.label(format!(
+                    "{} of {} samples",
+                    result.reentered_samples,
+                    result.samples.len()
+                ));
+                ui.end_row();
+                if let Some(reentry) = &result.reentry_days {
+                    ui.label("Reentry (days since epoch, 5% / 50% / 95%)");
+                    ui.label(format!(
+                        "{:.2} / {:.2} / {:.2}",
+                        reentry.p05, reentry.p50, reentry.p95
+                    ));
+                    ui.end_row();
+                }
+                if let Some(error) = result.samples.iter().find_map(|s| s.error.as_ref()) {
+                    ui.label("First failure");
+                    ui.label(error);
+                    ui.end_row();
+                }
+            });
+
+        let reentry_days = result
+            .samples
+            .iter()
+            .filter_map(|s| s.reentry_days_since_epoch)
+            .collect::<Vec<_>>();
+        if !reentry_days.is_empty() {
+            const BINS: usize = 20;
+            let min = reentry_days.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = reentry_days
+                .iter()
+                .copied()
+                .fold(f64::NEG_INFINITY, f64::max);
+            let width = ((max - min) / BINS as f64).max(1e-3);
+            let mut counts = [0usize; BINS];
+            for days in &reentry_days {
+                counts[(((days - min) / width) as usize).min(BINS - 1)] += 1;
+            }
+            let bars = counts
+                .iter()
+                .enumerate()
+                .map(|(i, count)| {
+                    Bar::new(min + (i as f64 + 0.5) * width, *count as f64).width(width)
+                })
+                .collect();
+            Plot::new("monte_carlo_reentry_plot")
+                .height(150.0)
+                .x_axis_label("Reentry (days since epoch)")
+                .y_axis_label("Samples")
+                .show(ui, |plot_ui| {
+                    plot_ui.bar_chart(BarChart::new("Reentry", bars));
+                });
+        }
+
+        let band_line = |name: &str, value: fn(&crate::monte_carlo::PercentileBand) -> f64| {
+            let points: PlotPoints = result
+                .altitude_bands_km
+                .iter()
+                .map(|(days, band)| [*days, value(band)])
+                .collect();
+            Line::new(name.to_string(), points)
+        };
+        Plot::new("monte_carlo_altitude_plot")
+            .height(250.0)
+            .legend(Legend::default())
+            .x_axis_label("Days since epoch")
+            .y_axis_label("Elevation (km)")
+            .show(ui, |plot_ui| {
+                plot_ui.line(band_line("5%", |band| band.p05));
+                plot_ui.line(band_line("50%", |band| band.p50));
+                plot_ui.line(band_line("95%", |band| band.p95));
+            });
+
+        if result.pass_timing.is_empty() {
+            return;
+        }
+        ui.label(egui::RichText::new("Pass Timing Spread").strong());
+        egui::ScrollArea::vertical()
+            .id_salt("monte_carlo_pass_scroll")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                egui::Grid::new("monte_carlo_pass_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("Station");
+                        ui.label("Nominal AOS (UTC)");
+                        ui.label("Samples");
+                        ui.label("1σ (s)");
+                        ui.label("Max offset (s)");
+                        ui.end_row();
+                        for pass in &result.pass_timing {
+                            ui.label(&pass.station);
+                            ui.label(pass.nominal_aos.as_iso8601());
+                            ui.label(pass.samples.to_string());
+                            ui.label(format!("{:.1}", pass.std_dev_seconds));
+                            ui.label(format!("{:.1}", pass.max_abs_offset_seconds));
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
@@ -0,0 +1,281 @@
+use std::sync::mpsc;
+
+use eframe::egui;
+use strum::IntoEnumIterator;
+
+use crate::orbit_determination::{
+    Measurement, Residual, fit_tle_to_observations, parse_observations,
+};
+use crate::ui::actions::{MyApp, SIMULATION_MAX_UI_UPDATE_PERIOD_MS};
+use crate::ui::fields::OrbitDeterminationField;
+
+fn optional(value: Option<f64>, decimals: usize, unit: &str) -> String {
+    value.map_or_else(String::new, |v| format!("{v:.decimals$}{unit}"))
+}
+
+impl MyApp {
+    fn on_fit_observations(&mut self) {
+        let path = self
+            .input_fields
+            .orbit_determination_inputs
+            .get(&OrbitDeterminationField::ObservationFile)
+            .map(|p| p.trim().to_string())
+            .unwrap_or_default();
+        let settings = match self.read_orbit_determination_settings() {
+            Ok(settings) => settings,
+            Err(e) => {
+                self.run_status = format!("Invalid orbit determination settings: {e}");
+                return;
+            }
+        };
+        let observations = match std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {path}: {e}"))
+            .and_then(|text| parse_observations(&text))
+        {
+            Ok(observations) => observations,
+            Err(e) => {
+                self.run_status = format!("Invalid observations: {e}");
+                return;
+            }
+        };
+        let template = if self.input_fields.od_use_current_tle {
+            match &self.tle_data {
+                Some(tle) => Some(tle.clone()),
+                None => {
+                    self.run_status = "No valid TLE to start the fit from.".into();
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+        // Tracking observations refer to the ground station by name.
+        let has_tracks = observations
+            .iter()
+            .any(|observation| matches!(observation.measurement, Measurement::Track { .. }));
+        let stations = match self.read_ground_station() {
+            Ok(station) => vec![station],
+            Err(e) if has_tracks => {
+                self.run_status = format!("Invalid ground station for the tracks: {e}");
+                return;
+            }
+            Err(_) => Vec::new(),
+        };
+
+        let (tx, rx) = mpsc::channel();
+        self.orbit_determination_rx = Some(rx);
+        self.run_status = format!("Fitting {} observations...", observations.len());
+
+        std::thread::spawn(move || {
+            let result =
+                fit_tle_to_observations(&observations, &stations, template.as_ref(), &settings)
+                    .map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+    }
+
+    pub fn poll_orbit_determination(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.orbit_determination_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(result)) => {
+                self.run_status = format!(
+                    "Fitted {} observations in {} iterations ({}), weighted RMS {:.3}.",
+                    result.residuals.len(),
+                    result.iterations.len(),
+                    if result.converged {
+                        "converged"
+                    } else if result.stalled {
+                        "stalled, not converged"
+                    } else {
+                        "not converged"
+                    },
+                    result
+                        .iterations
+                        .last()
+                        .map_or(f64::NAN, |i| i.weighted_rms)
+                );
+                self.orbit_determination_result = Some(result);
+                self.orbit_determination_rx = None;
+            }
+            Ok(Err(e)) => {
+                self.run_status = format!("Orbit determination failed: {e}");
+                self.orbit_determination_rx = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(
+                    SIMULATION_MAX_UI_UPDATE_PERIOD_MS as u64,
+                ));
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.run_status = "Orbit determination worker stopped unexpectedly.".into();
+                self.orbit_determination_rx = None;
+            }
+        }
+    }
+
+    fn on_use_fitted_tle(&mut self) {
+        let Some(result) = &self.orbit_determination_result else {
+            return;
+        };
+        match result.tle.to_satkit_tle().to_2line() {
+            Ok([line1, line2]) => {
+                self.tle_line0 = result.tle.name.clone();
+                self.tle_line1 = line1;
+                self.tle_line2 = line2;
+                self.try_parse_tle();
+            }
+            Err(e) => self.run_status = format!("Failed to format the fitted TLE: {e}"),
+        }
+    }
+
+    pub fn orbit_determination_section(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Orbit Determination");
+        ui.label(
+            "Fits a TLE to a CSV of observations, one per line: \
+             'state,<UTC>,<ITRF|GCRF|TEME>,x,y,z,vx,vy,vz' (m, m/s) or \
+             'track,<UTC>,<station>,range_m,azimuth_deg,elevation_deg,range_rate_m_per_s' \
+             (blank for unmeasured). Tracks use the ground station above.",
+        );
+        for f in OrbitDeterminationField::iter() {
+            let mut val = self
+                .input_fields
+                .orbit_determination_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .orbit_determination_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+        ui.checkbox(&mut self.input_fields.od_solve_for_bstar, "Solve for B*");
+        ui.checkbox(
+            &mut self.input_fields.od_use_current_tle,
+            "Start from the current TLE",
+        );
+        ui.horizontal(|ui| {
+            let running = self.orbit_determination_rx.is_some();
+            if ui
+                .add_enabled(!running, egui::Button::new("Fit Observations"))
+                .clicked()
+            {
+                self.on_fit_observations();
+            }
+            if running {
+                ui.label("Fitting...");
+            }
+            if ui
+                .add_enabled(
+                    self.orbit_determination_result.is_some(),
+                    egui::Button::new("Use Fitted TLE"),
+                )
+                .clicked()
+            {
+                self.on_use_fitted_tle();
+            }
+        });
+
+        let Some(result) = &self.orbit_determination_result else {
+            return;
+        };
+        if let Ok([line1, line2]) = result.tle.to_satkit_tle().to_2line() {
+            let mut lines = format!("{line1}\n{line2}");
+            ui.add(
+                egui::TextEdit::multiline(&mut lines)
+                    .font(egui::TextStyle::Monospace)
+                    .desired_rows(2)
+                    .interactive(true),
+            );
+        }
+
+        egui::CollapsingHeader::new("Convergence").show(ui, |ui| {
+            egui::Grid::new("od_iteration_grid")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("Iteration");
+                    ui.label("Weighted RMS");
+                    ui.label("Damping");
+                    ui.end_row();
+                    for (i, iteration) in result.iterations.iter().enumerate() {
+                        ui.label((i + 1).to_string());
+                        ui.label(format!("{:.4}", iteration.weighted_rms));
+                        ui.label(format!("{:.1e}", iteration.damping));
+                        ui.end_row();
+                    }
+                });
+            egui::Grid::new("od_sigma_grid")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("Parameter");
+                    ui.label("Formal 1σ");
+                    ui.end_row();
+                    for (label, sigma) in &result.parameter_sigmas {
+                        ui.label(*label);
+                        ui.label(format!("{sigma:.3e}"));
+                        ui.end_row();
+                    }
+                });
+        });
+
+        egui::CollapsingHeader::new("Residuals (observed − computed)").show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .id_salt("od_residual_scroll")
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("od_residual_grid")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for header in [
+                                "Time (UTC)",
+                                "Source",
+                                "Radial / range (m)",
+                                "Along / azimuth",
+                                "Cross / elevation",
+                                "Velocity / range rate (m/s)",
+                            ] {
+                                ui.label(header);
+                            }
+                            ui.end_row();
+                            for (time, residual) in &result.residuals {
+                                ui.label(time.as_iso8601());
+                                match residual {
+                                    Residual::State {
+                                        position_rtn_m,
+                                        velocity_m_per_s,
+                                    } => {
+                                        ui.label("State");
+                                        ui.label(format!("{:.2}", position_rtn_m[0]));
+                                        ui.label(format!("{:.2} m", position_rtn_m[1]));
+                                        ui.label(format!("{:.2} m", position_rtn_m[2]));
+                                        ui.label(format!("{velocity_m_per_s:.4}"));
+                                    }
+                                    Residual::Track {
+                                        station,
+                                        range_m,
+                                        azimuth_deg,
+                                        elevation_deg,
+                                        range_rate_m_per_s,
+                                    } => {
+                                        ui.label(station);
+                                        ui.label(optional(*range_m, 2, ""));
+                                        let arcsec = |deg: Option<f64>| {
+                                            optional(deg.map(|d| d * 3600.0), 1, " arcsec")
+                                        };
+                                        ui.label(arcsec(*azimuth_deg));
+                                        ui.label(arcsec(*elevation_deg));
+                                        ui.label(optional(*range_rate_m_per_s, 4, ""));
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+    }
+}
@@ -0,0 +1,333 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+
+use eframe::egui::{self, Color32};
+use egui_plot::{Plot, PlotPoints, Polygon};
+use strum::IntoEnumIterator;
+
+use crate::parameter_sweep::{SweepMetric, SweepParameter, SweepResult, run_sweep};
+use crate::ui::actions::{MyApp, SIMULATION_MAX_UI_UPDATE_PERIOD_MS};
+use crate::ui::fields::SweepField;
+
+/// Colour of `fraction` (0 to 1) on a dark blue → teal → yellow scale.
+fn heatmap_color(fraction: f64) -> Color32 {
+    const STOPS: [[f64; 3]; 3] = [
+        [30.0, 40.0, 120.0],
+        [30.0, 160.0, 140.0],
+        [250.0, 230.0, 60.0],
+    ];
+    let scaled = fraction.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+    let low = (scaled.floor() as usize).min(STOPS.len() - 2);
+    let t = scaled - low as f64;
+    let channel = |c: usize| (STOPS[low][c] + (STOPS[low + 1][c] - STOPS[low][c]) * t) as u8;
+    Color32::from_rgb(channel(0), channel(1), channel(2))
+}
+
+/// Spacing of an axis' values, or 1 for a single-value axis.
+fn cell_size(values: &[f64]) -> f64 {
+    if values.len() > 1 {
+        (values[1] - values[0]).abs()
+    } else {
+        1.0
+    }
+}
+
+impl MyApp {
+    fn on_run_sweep(&mut self) {
+        let nominal = match self.read_initial_simulation_state() {
+            Ok(nominal) => nominal,
+            Err(e) => {
+                self.run_status = format!("Error initializing sweep: {e}");
+                return;
+            }
+        };
+        let definition = match self.read_sweep_definition() {
+            Ok(definition) => definition,
+            Err(e) => {
+                self.run_status = format!("Invalid sweep: {e}");
+                return;
+            }
+        };
+
+        let progress = Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = mpsc::channel();
+        self.sweep_progress = progress.clone();
+        self.sweep_runs = definition.run_count();
+        self.sweep_rx = Some(rx);
+        self.sweep_result = None;
+        self.run_status = format!(
+            "Running {} sweep combinations on {} threads...",
+            self.sweep_runs, definition.threads
+        );
+
+        std::thread::spawn(move || {
+            let result = run_sweep(&nominal, &definition, &progress).map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+    }
+
+    pub fn poll_sweep(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.sweep_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(result)) => {
+                let failed = result.rows.iter().filter(|r| r.metrics.is_err()).count();
+                self.run_status = format!(
+                    "Sweep finished: {} combinations, {} failed.",
+                    result.rows.len(),
+                    failed
+                );
+                self.sweep_result = Some(result);
+                self.sweep_rx = None;
+            }
+            Ok(Err(e)) => {
+                self.run_status = format!("Sweep failed: {e}");
+                self.sweep_rx = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(
+                    SIMULATION_MAX_UI_UPDATE_PERIOD_MS as u64,
+                ));
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.run_status = "Sweep worker stopped unexpectedly.".into();
+                self.sweep_rx = None;
+            }
+        }
+    }
+
+    pub fn parameter_sweep_section(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Parameter Sweep");
+        ui.label(
+            "Runs the current inputs once for every combination of the axes below. Only the \
+             listed parameters can be swept; orbit parameters need a plain TLE (no TLE history) \
+             and the drag area needs no panel model.",
+        );
+
+        let mut remove_index = None;
+        egui::Grid::new("sweep_axis_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Parameter");
+                ui.label("Start");
+                ui.label("Stop");
+                ui.label("Steps");
+                ui.label("");
+                ui.end_row();
+                for (i, axis) in self.input_fields.sweep_axes.iter().enumerate() {
+                    ui.label(axis.parameter.label());
+                    ui.label(format!("{}", axis.start));
+                    ui.label(format!("{}", axis.stop));
+                    ui.label(axis.steps.to_string());
+                    if ui.button("Remove").clicked() {
+                        remove_index = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = remove_index {
+            self.input_fields.sweep_axes.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Parameter")
+                .selected_text(self.input_fields.sweep_parameter.label())
+                .show_ui(ui, |ui| {
+                    for parameter in SweepParameter::iter() {
+                        ui.selectable_value(
+                            &mut self.input_fields.sweep_parameter,
+                            parameter,
+                            parameter.label(),
+                        );
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            for f in [
+                SweepField::AxisStart,
+                SweepField::AxisStop,
+                SweepField::AxisSteps,
+            ] {
+                let mut val = self
+                    .input_fields
+                    .sweep_inputs
+                    .get(&f)
+                    .cloned()
+                    .unwrap_or_default();
+                ui.label(f.label());
+                if ui
+                    .add(egui::TextEdit::singleline(&mut val).desired_width(80.0))
+                    .changed()
+                {
+                    self.input_fields.sweep_inputs.insert(f, val);
+                }
+            }
+            if ui
+                .add_enabled(
+                    self.input_fields.sweep_axes.len() < 2,
+                    egui::Button::new("Add Axis"),
+                )
+                .clicked()
+            {
+                match self.read_sweep_axis() {
+                    Ok(axis) => self.input_fields.sweep_axes.push(axis),
+                    Err(e) => self.run_status = format!("Invalid sweep axis: {e}"),
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            let f = SweepField::Threads;
+            let mut val = self
+                .input_fields
+                .sweep_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.label(f.label());
+            if ui.text_edit_singleline(&mut val).changed() {
+                self.input_fields.sweep_inputs.insert(f, val);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let running = self.sweep_rx.is_some();
+            if ui
+                .add_enabled(!running, egui::Button::new("Run Sweep"))
+                .clicked()
+            {
+                self.on_run_sweep();
+            }
+            if running {
+                ui.label(format!(
+                    "{} / {} runs done",
+                    self.sweep_progress.load(Ordering::Relaxed),
+                    self.sweep_runs
+                ));
+            }
+        });
+
+        let Some(result) = &self.sweep_result else {
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("CSV path");
+            ui.add(egui::TextEdit::singleline(&mut self.sweep_export_path).hint_text("sweep.csv"));
+            if ui.button("Export CSV").clicked() {
+                let path = if self.sweep_export_path.trim().is_empty() {
+                    "sweep.csv".to_string()
+                } else {
+                    self.sweep_export_path.trim().to_string()
+                };
+                self.run_status = match result.write_csv(&path) {
+                    Ok(()) => format!("Exported {} sweep rows to {path}.", result.rows.len()),
+                    Err(e) => format!("Failed to export sweep: {e}"),
+                };
+            }
+        });
+
+        egui::ScrollArea::vertical()
+            .id_salt("sweep_result_scroll")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                egui::Grid::new("sweep_result_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for axis in &result.axes {
+                            ui.label(axis.parameter.label());
+                        }
+                        for metric in SweepMetric::iter() {
+                            ui.label(metric.label());
+                        }
+                        ui.end_row();
+                        for row in &result.rows {
+                            for value in &row.values {
+                                ui.label(format!("{value:.6}"));
+                            }
+                            match &row.metrics {
+                                Ok(metrics) => {
+                                    let lifetime = SweepMetric::LifetimeDays.value(metrics);
+                                    ui.label(if metrics.lifetime_days.is_some() {
+                                        format!("{lifetime:.2}")
+                                    } else {
+                                        format!("> {lifetime:.2}")
+                                    });
+                                    ui.label(format!("{:.1}", metrics.contact_minutes_per_day));
+                                    ui.label(format!("{:.3}", metrics.eclipse_fraction));
+                                }
+                                Err(e) => {
+                                    ui.label(format!("Failed: {e}"));
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        Self::sweep_heatmap(ui, result, &mut self.sweep_metric);
+    }
+
+    fn sweep_heatmap(ui: &mut egui::Ui, result: &SweepResult, metric: &mut SweepMetric) {
+        egui::ComboBox::from_label("Heatmap metric")
+            .selected_text(metric.label())
+            .show_ui(ui, |ui| {
+                for m in SweepMetric::iter() {
+                    ui.selectable_value(metric, m, m.label());
+                }
+            });
+
+        let values = result
+            .rows
+            .iter()
+            .map(|row| row.metrics.as_ref().ok().map(|m| metric.value(m)))
+            .collect::<Vec<_>>();
+        let (min, max) = values
+            .iter()
+            .flatten()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+                (min.min(*v), max.max(*v))
+            });
+        if !min.is_finite() {
+            return;
+        }
+        ui.label(format!(
+            "{}: {:.3} (blue) to {:.3} (yellow); failed runs are grey.",
+            metric.label(),
+            min,
+            max
+        ));
+
+        let x_values = result.axes[0].values();
+        let y_values = result.axes.get(1).map_or(vec![0.0], |axis| axis.values());
+        let (width, height) = (cell_size(&x_values), cell_size(&y_values));
+        let mut plot = Plot::new("sweep_heatmap")
+            .height(300.0)
+            .x_axis_label(result.axes[0].parameter.label());
+        if let Some(axis) = result.axes.get(1) {
+            plot = plot.y_axis_label(axis.parameter.label());
+        }
+        plot.show(ui, |plot_ui| {
+            for (row, value) in result.rows.iter().zip(&values) {
+                let (x, y) = (row.values[0], row.values.get(1).copied().unwrap_or(0.0));
+                let color = match value {
+                    Some(v) if max > min => heatmap_color((v - min) / (max - min)),
+                    Some(_) => heatmap_color(0.5),
+                    None => Color32::GRAY,
+                };
+                let corners: PlotPoints = vec![
+                    [x - width / 2.0, y - height / 2.0],
+                    [x + width / 2.0, y - height / 2.0],
+                    [x + width / 2.0, y + height / 2.0],
+                    [x - width / 2.0, y + height / 2.0],
+                ]
+                .into();
+                plot_ui.polygon(
+                    Polygon::new("", corners)
+                        .fill_color(color)
+                        .stroke(egui::Stroke::NONE),
+                );
+            }
+        });
+    }
+}
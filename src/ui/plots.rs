@@ -0,0 +1,226 @@
+use eframe::egui;
+use egui_plot::{Line, Plot, PlotPoints};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::output_frames::OutputFrame;
+use crate::satellite_state::SimulationStateAtStep;
+use crate::telemetry_export::write_telemetry_csv;
+use crate::ui::actions::MyApp;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter)]
+pub enum PlotQuantity {
+    #[default]
+    ElevationKm,
+    SemiMajorAxisKm,
+    MeanSemiMajorAxisKm,
+    Eccentricity,
+    InclinationDeg,
+    RaanDeg,
+    ArgOfPerigeeDeg,
+    ApogeeAltitudeKm,
+    PerigeeAltitudeKm,
+    UndeployedElevationKm,
+    PeriodMinutes,
+    SpeedMPerS,
+    DragPowerWatts,
+    PeakDynamicPressurePa,
+    PeakHeatFluxWPerM2,
+    RadialSigmaM,
+    AlongTrackSigmaM,
+    CrossTrackSigmaM,
+    IrradianceWPerM2,
+    ApparentSolarTimeHours,
+    EquationOfTimeMinutes,
+    LtanHours,
+    LtanDriftMinutes,
+    SunAngleFromBodyZDeg,
+    DragAreaM2,
+    SolarPowerWatts,
+    OrbitAveragePowerWatts,
+    PropellantRemainingKg,
+    TotalBurnDeltaVMPerS,
+}
+
+impl PlotQuantity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlotQuantity::ElevationKm => "Elevation (km)",
+            PlotQuantity::SemiMajorAxisKm => "Semi-major axis (km)",
+            PlotQuantity::MeanSemiMajorAxisKm => "Orbit-average semi-major axis (km)",
+            PlotQuantity::Eccentricity => "Eccentricity",
+            PlotQuantity::InclinationDeg => "Inclination (deg)",
+            PlotQuantity::RaanDeg => "RAAN (deg)",
+            PlotQuantity::ArgOfPerigeeDeg => "Argument of perigee (deg)",
+            PlotQuantity::ApogeeAltitudeKm => "Apogee altitude (km)",
+            PlotQuantity::PerigeeAltitudeKm => "Perigee altitude (km)",
+            PlotQuantity::UndeployedElevationKm => "Elevation without deployment (km)",
+            PlotQuantity::PeriodMinutes => "Period (min)",
+            PlotQuantity::SpeedMPerS => "Speed (m/s)",
+            PlotQuantity::DragPowerWatts => "Drag power (W)",
+            PlotQuantity::PeakDynamicPressurePa => "Perigee pass peak dynamic pressure (Pa)",
+            PlotQuantity::PeakHeatFluxWPerM2 => "Perigee pass peak heat flux (W/m²)",
+            PlotQuantity::RadialSigmaM => "Radial 1σ (m)",
+            PlotQuantity::AlongTrackSigmaM => "Along-track 1σ (m)",
+            PlotQuantity::CrossTrackSigmaM => "Cross-track 1σ (m)",
+            PlotQuantity::IrradianceWPerM2 => "Irradiance (W/m²)",
+            PlotQuantity::ApparentSolarTimeHours => "Apparent solar time (h)",
+            PlotQuantity::EquationOfTimeMinutes => "Equation of time (min)",
+            PlotQuantity::LtanHours => "LTAN (h)",
+            PlotQuantity::LtanDriftMinutes => "LTAN drift (min)",
+            PlotQuantity::SunAngleFromBodyZDeg => "Sun angle from body +Z (deg)",
+            PlotQuantity::DragAreaM2 => "Drag area (m²)",
+            PlotQuantity::SolarPowerWatts => "Solar power (W)",
+            PlotQuantity::OrbitAveragePowerWatts => "Orbit-average power (W)",
+            PlotQuantity::PropellantRemainingKg => "Propellant remaining (kg)",
+            PlotQuantity::TotalBurnDeltaVMPerS => "Total burn Δv (m/s)",
+        }
+    }
+
+    pub fn value(&self, t: &SimulationStateAtStep) -> f64 {
+        match self {
+            PlotQuantity::ElevationKm => t.elevation_km,
+            PlotQuantity::SemiMajorAxisKm => t.orbital_elements.semi_major_axis_m / 1000.0,
+            PlotQuantity::MeanSemiMajorAxisKm => t.mean_semi_major_axis_m / 1000.0,
+            PlotQuantity::Eccentricity => t.orbital_elements.eccentricity,
+            PlotQuantity::InclinationDeg => t.orbital_elements.inclination_deg,
+            PlotQuantity::RaanDeg => t.orbital_elements.raan_deg,
+            PlotQuantity::ArgOfPerigeeDeg => t.orbital_elements.arg_of_perigee_deg,
+            PlotQuantity::ApogeeAltitudeKm => t.orbital_elements.apogee_altitude_km(),
+            PlotQuantity::PerigeeAltitudeKm => t.orbital_elements.perigee_altitude_km(),
+            PlotQuantity::UndeployedElevationKm => t
+                .undeployed
+                .as_ref()
+                .map(|u| u.elevation_km)
+                .unwrap_or(f64::NAN),
+            PlotQuantity::PeriodMinutes => t.orbital_elements.period_minutes(),
+            PlotQuantity::SpeedMPerS => t.speed_m_per_s,
+            PlotQuantity::DragPowerWatts => t.drag_power_watts,
+            PlotQuantity::PeakDynamicPressurePa => t
+                .perigee_passes
+                .iter()
+                .map(|p| p.peak_dynamic_pressure_pa)
+                .reduce(f64::max)
+                .unwrap_or(f64::NAN),
+            PlotQuantity::PeakHeatFluxWPerM2 => t
+                .perigee_passes
+                .iter()
+                .map(|p| p.peak_heat_flux_w_per_m2)
+                .reduce(f64::max)
+                .unwrap_or(f64::NAN),
+            PlotQuantity::RadialSigmaM => t.uncertainty.map_or(f64::NAN, |u| u.radial_sigma_m),
+            PlotQuantity::AlongTrackSigmaM => {
+                t.uncertainty.map_or(f64::NAN, |u| u.along_track_sigma_m)
+            }
+            PlotQuantity::CrossTrackSigmaM => {
+                t.uncertainty.map_or(f64::NAN, |u| u.cross_track_sigma_m)
+            }
+            PlotQuantity::IrradianceWPerM2 => t.irradiance_w_per_m2,
+            PlotQuantity::ApparentSolarTimeHours => t.apparent_solar_time_hours,
+            PlotQuantity::EquationOfTimeMinutes => t.equation_of_time_minutes,
+            PlotQuantity::LtanHours => t.ltan_hours,
+            PlotQuantity::LtanDriftMinutes => t.ltan_drift_minutes,
+            PlotQuantity::DragAreaM2 => t.drag_area_m2,
+            PlotQuantity::SolarPowerWatts => t.solar_array_power_watts.iter().sum(),
+            PlotQuantity::OrbitAveragePowerWatts => t
+                .orbit_average_power
+                .as_ref()
+                .map(|avg| avg.average_power_watts)
+                .unwrap_or(f64::NAN),
+            PlotQuantity::PropellantRemainingKg => t
+                .propulsion
+                .as_ref()
+                .map(|p| p.propellant_remaining_kg)
+                .unwrap_or(f64::NAN),
+            PlotQuantity::TotalBurnDeltaVMPerS => t
+                .propulsion
+                .as_ref()
+                .map(|p| p.total_delta_v_m_per_s)
+                .unwrap_or(f64::NAN),
+            PlotQuantity::SunAngleFromBodyZDeg => {
+                t.attitude.sun_body[2].clamp(-1.0, 1.0).acos().to_degrees()
+            }
+        }
+    }
+}
+
+impl MyApp {
+    pub fn telemetry_plot_section(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Telemetry Plots");
+        egui::ComboBox::from_label("Quantity")
+            .selected_text(self.plot_quantity.label())
+            .show_ui(ui, |ui| {
+                for quantity in PlotQuantity::iter() {
+                    ui.selectable_value(&mut self.plot_quantity, quantity, quantity.label());
+                }
+            });
+
+        let Some(first) = self.telemetry_history.first() else {
+            ui.label("No telemetry yet. Press Run to start.");
+            return;
+        };
+        let start_time = first.time;
+        let quantity = self.plot_quantity;
+        let points: PlotPoints = self
+            .telemetry_history
+            .iter()
+            .map(|t| [(t.time - start_time).as_days(), quantity.value(t)])
+            .collect();
+
+        Plot::new("telemetry_plot")
+            .height(250.0)
+            .x_axis_label("Days since start")
+            .y_axis_label(quantity.label())
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(quantity.label(), points))
+            });
+    }
+
+    pub fn telemetry_export_section(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Export Telemetry");
+        ui.horizontal(|ui| {
+            ui.label("Frames");
+            for frame in OutputFrame::iter() {
+                let mut selected = self.export_frames.contains(&frame);
+                if ui.checkbox(&mut selected, frame.label()).changed() {
+                    if selected {
+                        self.export_frames.push(frame);
+                    } else {
+                        self.export_frames.retain(|f| *f != frame);
+                    }
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("CSV path");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.telemetry_export_path)
+                    .hint_text("telemetry.csv"),
+            );
+            if ui
+                .add_enabled(
+                    !self.telemetry_history.is_empty(),
+                    egui::Button::new("Export CSV"),
+                )
+                .clicked()
+            {
+                let path = if self.telemetry_export_path.trim().is_empty() {
+                    "telemetry.csv".to_string()
+                } else {
+                    self.telemetry_export_path.trim().to_string()
+                };
+                self.run_status = match write_telemetry_csv(
+                    &path,
+                    &self.telemetry_history,
+                    &self.export_frames,
+                ) {
+                    Ok(()) => format!(
+                        "Exported {} telemetry rows to {path}.",
+                        self.telemetry_history.len()
+                    ),
+                    Err(e) => format!("Failed to export telemetry: {e}"),
+                };
+            }
+        });
+    }
+}
@@ -0,0 +1,88 @@
+use eframe::egui;
+use strum::IntoEnumIterator;
+
+use crate::solar_power::SolarArrayMounting;
+use crate::ui::actions::MyApp;
+use crate::ui::fields::SolarArrayField;
+
+impl MyApp {
+    pub fn solar_array_section(&mut self, ui: &mut egui::Ui) {
+        ui.label(egui::RichText::new("Solar Arrays").strong());
+
+        let mut remove_index = None;
+        egui::Grid::new("solar_array_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Name");
+                ui.label("Mounting");
+                ui.label("Area (m²)");
+                ui.label("Efficiency (at temp.)");
+                ui.label("Normal / axis (body)");
+                ui.label("");
+                ui.end_row();
+                for (i, array) in self.input_fields.solar_arrays.iter().enumerate() {
+                    ui.label(&array.name);
+                    ui.label(array.mounting.label());
+                    ui.label(format!("{:.4}", array.area_m2));
+                    ui.label(format!(
+                        "{:.3} ({:.3} at {:.0} °C)",
+                        array.cell_efficiency,
+                        array.efficiency(),
+                        array.operating_temperature_c
+                    ));
+                    ui.label(format!(
+                        "[{:+.3}, {:+.3}, {:+.3}]",
+                        array.orientation_body[0],
+                        array.orientation_body[1],
+                        array.orientation_body[2]
+                    ));
+                    if ui.button("Remove").clicked() {
+                        remove_index = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = remove_index {
+            self.input_fields.solar_arrays.remove(i);
+        }
+
+        for f in SolarArrayField::iter() {
+            let mut val = self
+                .input_fields
+                .solar_array_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .solar_array_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Array Mounting")
+                .selected_text(self.input_fields.solar_array_mounting.label())
+                .show_ui(ui, |ui| {
+                    for mounting in SolarArrayMounting::iter() {
+                        ui.selectable_value(
+                            &mut self.input_fields.solar_array_mounting,
+                            mounting,
+                            mounting.label(),
+                        );
+                    }
+                });
+            if ui.button("Add Array").clicked() {
+                match self.read_solar_array() {
+                    Ok(array) => self.input_fields.solar_arrays.push(array),
+                    Err(e) => self.run_status = format!("Invalid solar array: {e}"),
+                }
+            }
+            if ui.button("Clear Arrays").clicked() {
+                self.input_fields.solar_arrays.clear();
+            }
+        });
+    }
+}
@@ -0,0 +1,110 @@
+use eframe::egui;
+use strum::IntoEnumIterator;
+
+use crate::propulsion::ThrustDirection;
+use crate::ui::actions::MyApp;
+use crate::ui::fields::{BurnField, StationKeepingField, ThrusterField};
+
+impl MyApp {
+    pub fn propulsion_section(&mut self, ui: &mut egui::Ui) {
+        ui.label(egui::RichText::new("Thruster").strong());
+        ui.label("Leave the thrust empty for no thruster.");
+        for f in ThrusterField::iter() {
+            let mut val = self
+                .input_fields
+                .thruster_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .thruster_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+
+        ui.add_space(4.0);
+        ui.label(egui::RichText::new("Burn Schedule").strong());
+        let mut remove_index = None;
+        egui::Grid::new("burn_schedule_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Name");
+                ui.label("Start (days)");
+                ui.label("Duration (min)");
+                ui.label("Direction");
+                ui.label("");
+                ui.end_row();
+                for (i, burn) in self.input_fields.burn_schedule.iter().enumerate() {
+                    ui.label(&burn.name);
+                    ui.label(format!("{:.4}", burn.start_days_since_epoch));
+                    ui.label(format!("{:.2}", burn.duration_minutes));
+                    ui.label(burn.direction.label());
+                    if ui.button("Remove").clicked() {
+                        remove_index = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = remove_index {
+            self.input_fields.burn_schedule.remove(i);
+        }
+
+        for f in BurnField::iter() {
+            let mut val = self
+                .input_fields
+                .burn_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields.burn_inputs.insert(f.clone(), val.clone());
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Thrust Direction")
+                .selected_text(self.input_fields.burn_direction.label())
+                .show_ui(ui, |ui| {
+                    for direction in ThrustDirection::iter() {
+                        ui.selectable_value(
+                            &mut self.input_fields.burn_direction,
+                            direction,
+                            direction.label(),
+                        );
+                    }
+                });
+            if ui.button("Add Burn").clicked() {
+                match self.read_burn() {
+                    Ok(burn) => self.input_fields.burn_schedule.push(burn),
+                    Err(e) => self.run_status = format!("Invalid burn: {e}"),
+                }
+            }
+        });
+
+        ui.add_space(4.0);
+        ui.label(egui::RichText::new("Station Keeping").strong());
+        ui.label("Reboosts along-velocity whenever the mean altitude drops below the deadband.");
+        for f in StationKeepingField::iter() {
+            let mut val = self
+                .input_fields
+                .station_keeping_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .station_keeping_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+    }
+}
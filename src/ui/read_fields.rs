@@ -1,5 +1,28 @@
+use crate::attitude::AttitudeSettings;
+use crate::collision_avoidance::AvoidanceSettings;
+use crate::conjunction::{ScreeningSettings, ScreeningVolume};
+use crate::constellation::{RideshareDeployment, WalkerPattern};
+use crate::deployment::DeploymentEvent;
+use crate::imaging::Imager;
+use crate::initial_state_model::{GroundTarget, TargetShape};
+use crate::maneuver_planner::ManeuverRequest;
+use crate::monte_carlo::{Dispersion, MonteCarloSettings};
+use crate::orbit_determination::OrbitDeterminationSettings;
+use crate::parameter_sweep::{SweepAxis, SweepDefinition};
+use crate::propulsion::{ScheduledBurn, Thruster};
+use crate::solar_power::SolarArray;
+use crate::spacecraft_geometry::Panel;
+use crate::station_keeping::StationKeeping;
+use crate::tle_history::AccuracyValidationSettings;
 use crate::ui::actions::MyApp;
-use crate::ui::fields::{GroundStationField, SatelliteField, SimulationBoolField, SimulationField};
+use crate::ui::fields::{
+    AttitudeField, AvoidanceField, BurnField, ConjunctionField, ConstellationField,
+    DeploymentEventField, GroundStationField, GroundTargetField, ImagerField, ManeuverField,
+    MonteCarloField, OrbitDeterminationField, PanelField, SatelliteField, SimulationBoolField,
+    SimulationField, SolarArrayField, StationKeepingField, SweepField, ThrusterField,
+    TleAccuracyField, UncertaintyField, UncertaintyKind,
+};
+use crate::uncertainty::{InitialCovariance, TleAgeErrorGrowth, UncertaintyModel};
 
 fn parse_required_f64(label: &str, s: &str) -> Result<f64, String> {
     let trimmed = s.trim();
@@ -11,6 +34,16 @@ fn parse_required_f64(label: &str, s: &str) -> Result<f64, String> {
         .map_err(|_| format!("Invalid number for '{}'", label))
 }
 
+fn parse_required_u32(label: &str, s: &str) -> Result<u32, String> {
+    let trimmed = s.trim();
+    if trimmed.is_empty() {
+        return Err(format!("'{}' is required", label));
+    }
+    trimmed
+        .parse::<u32>()
+        .map_err(|_| format!("Invalid whole number for '{}'", label))
+}
+
 fn parse_optional_f64(s: &str) -> Option<f64> {
     let t = s.trim();
     if t.is_empty() {
@@ -72,6 +105,54 @@ impl MyApp {
         crate::initial_state_model::GroundStation::new(name, lat, lon, elev_opt, alt, min_el)
     }
 
+    /// A point target unless polygon vertices are given. A blank minimum Sun elevation means
+    /// daylight (0 degrees).
+    pub fn read_ground_target(&self) -> Result<GroundTarget, String> {
+        let input = |field: &GroundTargetField| {
+            self.input_fields
+                .ground_target_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        let required = |field: GroundTargetField| parse_required_f64(field.label(), input(&field));
+
+        let vertices = input(&GroundTargetField::PolygonVertices).trim();
+        let shape = if vertices.is_empty() {
+            TargetShape::Point {
+                latitude_deg: required(GroundTargetField::LatitudeDeg)?,
+                longitude_deg: required(GroundTargetField::LongitudeDeg)?,
+            }
+        } else {
+            let vertices_deg = vertices
+                .split(';')
+                .map(str::trim)
+                .filter(|vertex| !vertex.is_empty())
+                .map(|vertex| {
+                    let values = vertex
+                        .split_whitespace()
+                        .map(str::parse::<f64>)
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| format!("Invalid polygon vertex '{vertex}'"))?;
+                    match values[..] {
+                        [latitude_deg, longitude_deg] => Ok([latitude_deg, longitude_deg]),
+                        _ => Err(format!(
+                            "Polygon vertex '{vertex}' must be a latitude and a longitude"
+                        )),
+                    }
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            TargetShape::Polygon { vertices_deg }
+        };
+
+        GroundTarget::new(
+            input(&GroundTargetField::Name).trim().to_string(),
+            shape,
+            parse_optional_f64(input(&GroundTargetField::MinSunElevationDeg)).unwrap_or(0.0),
+            self.input_fields.ground_target_require_sunlit,
+        )
+    }
+
     pub fn read_satellite(&self) -> Result<crate::initial_state_model::Satellite, String> {
         let name = self
             .input_fields
@@ -96,11 +177,514 @@ impl MyApp {
                 .map(String::as_str)
                 .unwrap_or(""),
         )?;
+        let nose_radius_m = parse_optional_f64(
+            self.input_fields
+                .satellite_inputs
+                .get(&SatelliteField::NoseRadiusM)
+                .map(String::as_str)
+                .unwrap_or(""),
+        );
+        if nose_radius_m.is_some_and(|radius| radius <= 0.0) {
+            return Err("Nose radius must be > 0".into());
+        }
+        let hard_body_radius_m = parse_optional_f64(
+            self.input_fields
+                .satellite_inputs
+                .get(&SatelliteField::HardBodyRadiusM)
+                .map(String::as_str)
+                .unwrap_or(""),
+        );
+        if hard_body_radius_m.is_some_and(|radius| radius <= 0.0) {
+            return Err("Hard-body radius must be > 0".into());
+        }
 
         Ok(crate::initial_state_model::Satellite {
             name,
             drag_coefficient: cd,
             drag_area_m2: area,
+            attitude: self.read_attitude_settings()?,
+            panels: self.input_fields.panels.clone(),
+            solar_arrays: self.input_fields.solar_arrays.clone(),
+            deployment_events: self.input_fields.deployment_events.clone(),
+            thruster: self.read_thruster()?,
+            burn_schedule: self.input_fields.burn_schedule.clone(),
+            station_keeping: self.read_station_keeping()?,
+            nose_radius_m,
+            hard_body_radius_m,
+            imager: self.read_imager()?,
+        })
+    }
+
+    /// No thruster if the thrust is left blank. A blank duty cycle means continuous firing.
+    fn read_thruster(&self) -> Result<Option<Thruster>, String> {
+        let input = |field: &ThrusterField| {
+            self.input_fields
+                .thruster_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        if input(&ThrusterField::ThrustN).trim().is_empty() {
+            return Ok(None);
+        }
+        let required = |field: ThrusterField| parse_required_f64(field.label(), input(&field));
+
+        Thruster::new(
+            required(ThrusterField::ThrustN)?,
+            required(ThrusterField::SpecificImpulseS)?,
+            required(ThrusterField::DryMassKg)?,
+            required(ThrusterField::WetMassKg)?,
+            parse_optional_f64(input(&ThrusterField::DutyCycle)).unwrap_or(1.0),
+        )
+        .map(Some)
+    }
+
+    /// No imager if the maximum off-nadir angle is left blank.
+    fn read_imager(&self) -> Result<Option<Imager>, String> {
+        let input = |field: &ImagerField| {
+            self.input_fields
+                .imager_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        if input(&ImagerField::MaxOffNadirDeg).trim().is_empty() {
+            return Ok(None);
+        }
+        let required = |field: ImagerField| parse_required_f64(field.label(), input(&field));
+
+        Imager::new(
+            required(ImagerField::MaxOffNadirDeg)?,
+            required(ImagerField::FocalLengthMm)?,
+            required(ImagerField::PixelPitchUm)?,
+        )
+        .map(Some)
+    }
+
+    /// No station keeping if the target altitude is left blank. A blank start means from the
+    /// epoch.
+    fn read_station_keeping(&self) -> Result<Option<StationKeeping>, String> {
+        let input = |field: &StationKeepingField| {
+            self.input_fields
+                .station_keeping_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        if input(&StationKeepingField::TargetAltitudeKm)
+            .trim()
+            .is_empty()
+        {
+            return Ok(None);
+        }
+        let required =
+            |field: StationKeepingField| parse_required_f64(field.label(), input(&field));
+
+        StationKeeping::new(
+            required(StationKeepingField::TargetAltitudeKm)?,
+            required(StationKeepingField::DeadbandKm)?,
+            parse_optional_f64(input(&StationKeepingField::StartDaysSinceEpoch)).unwrap_or(0.0),
+            required(StationKeepingField::DurationDays)?,
+        )
+        .map(Some)
+    }
+
+    /// The bi-elliptic apogee and the max burn duration are only needed by their methods, and
+    /// are read as 0 when blank.
+    pub fn read_maneuver_request(&self) -> Result<ManeuverRequest, String> {
+        let input = |field: &ManeuverField| {
+            self.input_fields
+                .maneuver_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        let required = |field: ManeuverField| parse_required_f64(field.label(), input(&field));
+
+        ManeuverRequest::new(
+            required(ManeuverField::TargetPerigeeAltitudeKm)?,
+            required(ManeuverField::TargetApogeeAltitudeKm)?,
+            self.input_fields.transfer_method,
+            parse_optional_f64(input(&ManeuverField::StartDaysSinceEpoch)).unwrap_or(0.0),
+            parse_optional_f64(input(&ManeuverField::BiEllipticApogeeAltitudeKm)).unwrap_or(0.0),
+            parse_optional_f64(input(&ManeuverField::MaxBurnMinutes)).unwrap_or(0.0),
+        )
+    }
+
+    /// Threads default to the number of cores when left blank.
+    pub fn read_monte_carlo_settings(&self) -> Result<MonteCarloSettings, String> {
+        let input = |field: &MonteCarloField| {
+            self.input_fields
+                .monte_carlo_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        let required = |field: MonteCarloField| parse_required_u32(field.label(), input(&field));
+
+        let threads = if input(&MonteCarloField::Threads).trim().is_empty() {
+            std::thread::available_parallelism().map_or(1, usize::from)
+        } else {
+            required(MonteCarloField::Threads)? as usize
+        };
+        MonteCarloSettings::new(
+            required(MonteCarloField::Samples)? as usize,
+            required(MonteCarloField::Seed)? as u64,
+            threads,
+            self.input_fields.dispersions.clone(),
+        )
+    }
+
+    pub fn read_dispersion(&self) -> Result<Dispersion, String> {
+        Dispersion::new(
+            self.input_fields.dispersed_parameter,
+            self.input_fields.dispersion_distribution,
+            parse_required_f64("Spread", &self.input_fields.dispersion_spread_input)?,
+        )
+    }
+
+    /// Screening settings; a blank start means `default_start`.
+    pub fn read_screening_settings(
+        &self,
+        default_start: satkit::Instant,
+    ) -> Result<ScreeningSettings, String> {
+        let input = |field: &ConjunctionField| {
+            self.input_fields
+                .conjunction_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        let required = |field: ConjunctionField| parse_required_f64(field.label(), input(&field));
+
+        let start = match input(&ConjunctionField::StartUtc).trim() {
+            "" => default_start,
+            start => satkit::Instant::from_rfc3339(start)
+                .map_err(|e| format!("Invalid start time '{start}': {e}"))?,
+        };
+        let threads = if input(&ConjunctionField::Threads).trim().is_empty() {
+            std::thread::available_parallelism().map_or(1, usize::from)
+        } else {
+            parse_required_u32(
+                ConjunctionField::Threads.label(),
+                input(&ConjunctionField::Threads),
+            )? as usize
+        };
+        ScreeningSettings::new(
+            ScreeningVolume::new(
+                required(ConjunctionField::RadialKm)? * 1000.0,
+                required(ConjunctionField::AlongTrackKm)? * 1000.0,
+                required(ConjunctionField::CrossTrackKm)? * 1000.0,
+            )?,
+            start,
+            required(ConjunctionField::DurationDays)?,
+            threads,
+        )
+    }
+
+    /// Sum of the satellite's hard-body radius and the other object's (blank for 0), or
+    /// `None` when the satellite has no hard-body radius.
+    pub fn read_combined_hard_body_radius(&self) -> Result<Option<f64>, String> {
+        let Some(satellite_m) = self.read_satellite()?.hard_body_radius_m else {
+            return Ok(None);
+        };
+        let object_m = parse_optional_f64(
+            self.input_fields
+                .conjunction_inputs
+                .get(&ConjunctionField::ObjectHardBodyRadiusM)
+                .map(String::as_str)
+                .unwrap_or(""),
+        )
+        .unwrap_or(0.0);
+        if object_m < 0.0 {
+            return Err("Hard-body radius of the other object must be >= 0".into());
+        }
+        Ok(Some(satellite_m + object_m))
+    }
+
+    /// Covariance growth and combined hard-body radius for the probability of collision of
+    /// screened conjunctions.
+    pub fn read_collision_probability_model(&self) -> Result<(TleAgeErrorGrowth, f64), String> {
+        let Some(UncertaintyModel::TleAge(growth)) = self.read_uncertainty_model()? else {
+            return Err("Pc needs the TLE-age uncertainty model for the covariances".into());
+        };
+        let hard_body_radius_m = self
+            .read_combined_hard_body_radius()?
+            .ok_or("Set the satellite's hard-body radius to compute Pc")?;
+        Ok((growth, hard_body_radius_m))
+    }
+
+    pub fn read_avoidance_settings(&self) -> Result<AvoidanceSettings, String> {
+        let input = |field: &AvoidanceField| {
+            self.input_fields
+                .avoidance_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        let required = |field: AvoidanceField| parse_required_f64(field.label(), input(&field));
+        let required_u32 = |field: AvoidanceField| parse_required_u32(field.label(), input(&field));
+
+        let threads = if input(&AvoidanceField::Threads).trim().is_empty() {
+            std::thread::available_parallelism().map_or(1, usize::from)
+        } else {
+            required_u32(AvoidanceField::Threads)? as usize
+        };
+        AvoidanceSettings::new(
+            required(AvoidanceField::MaxLeadTimeHours)?,
+            required_u32(AvoidanceField::LeadTimeSteps)?,
+            required(AvoidanceField::MaxDeltaVMPerS)?,
+            required_u32(AvoidanceField::DeltaVSteps)?,
+            required(AvoidanceField::AcceptablePc)?,
+            threads,
+        )
+    }
+
+    pub fn read_orbit_determination_settings(&self) -> Result<OrbitDeterminationSettings, String> {
+        let input = |field: &OrbitDeterminationField| {
+            self.input_fields
+                .orbit_determination_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        let required =
+            |field: OrbitDeterminationField| parse_required_f64(field.label(), input(&field));
+
+        OrbitDeterminationSettings::new(
+            required(OrbitDeterminationField::PositionSigmaM)?,
+            required(OrbitDeterminationField::VelocitySigmaMPerS)?,
+            required(OrbitDeterminationField::RangeSigmaM)?,
+            required(OrbitDeterminationField::AngleSigmaDeg)?,
+            required(OrbitDeterminationField::RangeRateSigmaMPerS)?,
+            self.input_fields.od_solve_for_bstar,
+            parse_required_u32(
+                OrbitDeterminationField::MaxIterations.label(),
+                input(&OrbitDeterminationField::MaxIterations),
+            )? as usize,
+        )
+    }
+
+    pub fn read_accuracy_validation_settings(&self) -> Result<AccuracyValidationSettings, String> {
+        let input = |field: &TleAccuracyField| {
+            self.input_fields
+                .tle_accuracy_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        let required = |field: TleAccuracyField| parse_required_f64(field.label(), input(&field));
+
+        AccuracyValidationSettings::new(
+            required(TleAccuracyField::MaxAgeDays)?,
+            required(TleAccuracyField::BinDays)?,
+            required(TleAccuracyField::ToleranceKm)? * 1000.0,
+        )
+    }
+
+    pub fn read_uncertainty_model(&self) -> Result<Option<UncertaintyModel>, String> {
+        let input = |field: &UncertaintyField| {
+            self.input_fields
+                .uncertainty_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        let required = |field: UncertaintyField| parse_required_f64(field.label(), input(&field));
+        let required_rtn = |fields: [UncertaintyField; 3]| -> Result<[f64; 3], String> {
+            let [r, a, c] = fields;
+            Ok([required(r)?, required(a)?, required(c)?])
+        };
+
+        let position_sigma_m = || {
+            required_rtn([
+                UncertaintyField::RadialSigmaM,
+                UncertaintyField::AlongTrackSigmaM,
+                UncertaintyField::CrossTrackSigmaM,
+            ])
+        };
+        match self.input_fields.uncertainty_kind {
+            UncertaintyKind::None => Ok(None),
+            UncertaintyKind::Covariance => {
+                Ok(Some(UncertaintyModel::Covariance(InitialCovariance::new(
+                    position_sigma_m()?,
+                    required_rtn([
+                        UncertaintyField::RadialVelocitySigmaMPerS,
+                        UncertaintyField::AlongTrackVelocitySigmaMPerS,
+                        UncertaintyField::CrossTrackVelocitySigmaMPerS,
+                    ])?,
+                    required(UncertaintyField::BstarRelativeSigma)?,
+                )?)))
+            }
+            UncertaintyKind::TleAge => Ok(Some(UncertaintyModel::TleAge(TleAgeErrorGrowth::new(
+                position_sigma_m()?,
+                required_rtn([
+                    UncertaintyField::RadialGrowthMPerDay,
+                    UncertaintyField::AlongTrackGrowthMPerDay,
+                    UncertaintyField::CrossTrackGrowthMPerDay,
+                ])?,
+                required(UncertaintyField::AlongTrackGrowthMPerDay2)?,
+            )?))),
+        }
+    }
+
+    /// Pass window margin in standard deviations; 3 when left blank.
+    pub fn read_pass_window_sigmas(&self) -> Result<f64, String> {
+        let sigmas = parse_optional_f64(&self.input_fields.pass_window_sigmas_input).unwrap_or(3.0);
+        if sigmas < 0.0 {
+            return Err("Pass window margin must be >= 0 sigma".into());
+        }
+        Ok(sigmas)
+    }
+
+    pub fn read_sweep_definition(&self) -> Result<SweepDefinition, String> {
+        let threads_input = self
+            .input_fields
+            .sweep_inputs
+            .get(&SweepField::Threads)
+            .map(String::as_str)
+            .unwrap_or("");
+        let threads = if threads_input.trim().is_empty() {
+            std::thread::available_parallelism().map_or(1, usize::from)
+        } else {
+            parse_required_u32(SweepField::Threads.label(), threads_input)? as usize
+        };
+        SweepDefinition::new(self.input_fields.sweep_axes.clone(), threads)
+    }
+
+    pub fn read_sweep_axis(&self) -> Result<SweepAxis, String> {
+        let input = |field: &SweepField| {
+            self.input_fields
+                .sweep_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        let required = |field: SweepField| parse_required_f64(field.label(), input(&field));
+
+        SweepAxis::new(
+            self.input_fields.sweep_parameter,
+            required(SweepField::AxisStart)?,
+            required(SweepField::AxisStop)?,
+            parse_required_u32(SweepField::AxisSteps.label(), input(&SweepField::AxisSteps))?
+                as usize,
+        )
+    }
+
+    pub fn read_burn(&self) -> Result<ScheduledBurn, String> {
+        let input = |field: &BurnField| {
+            self.input_fields
+                .burn_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        let required = |field: BurnField| parse_required_f64(field.label(), input(&field));
+
+        ScheduledBurn::new(
+            input(&BurnField::Name).trim().to_string(),
+            required(BurnField::StartDaysSinceEpoch)?,
+            required(BurnField::DurationMinutes)?,
+            self.input_fields.burn_direction,
+        )
+    }
+
+    pub fn read_deployment_event(&self) -> Result<DeploymentEvent, String> {
+        let input = |field: &DeploymentEventField| {
+            self.input_fields
+                .deployment_event_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        let required =
+            |field: DeploymentEventField| parse_required_f64(field.label(), input(&field));
+
+        DeploymentEvent::new(
+            input(&DeploymentEventField::Name).trim().to_string(),
+            self.input_fields.deployment_trigger,
+            required(DeploymentEventField::Threshold)?,
+            required(DeploymentEventField::DragCoefficient)?,
+            required(DeploymentEventField::DragAreaM2)?,
+            parse_optional_f64(input(&DeploymentEventField::JettisonedMassKg)).unwrap_or(0.0),
+        )
+    }
+
+    /// The temperature coefficient and operating temperature default to 0 and 28 °C (no
+    /// derating) when left blank.
+    pub fn read_solar_array(&self) -> Result<SolarArray, String> {
+        let input = |field: &SolarArrayField| {
+            self.input_fields
+                .solar_array_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        let required = |field: SolarArrayField| parse_required_f64(field.label(), input(&field));
+
+        SolarArray::new(
+            input(&SolarArrayField::Name).trim().to_string(),
+            self.input_fields.solar_array_mounting,
+            required(SolarArrayField::AreaM2)?,
+            required(SolarArrayField::CellEfficiency)?,
+            parse_optional_f64(input(&SolarArrayField::TemperatureCoefficientPerC)).unwrap_or(0.0),
+            parse_optional_f64(input(&SolarArrayField::OperatingTemperatureC)).unwrap_or(28.0),
+            [
+                required(SolarArrayField::OrientationX)?,
+                required(SolarArrayField::OrientationY)?,
+                required(SolarArrayField::OrientationZ)?,
+            ],
+        )
+    }
+
+    pub fn read_panel(&self) -> Result<Panel, String> {
+        let input = |field: &PanelField| {
+            self.input_fields
+                .panel_inputs
+                .get(field)
+                .map(String::as_str)
+                .unwrap_or("")
+        };
+        let required = |field: PanelField| parse_required_f64(field.label(), input(&field));
+
+        Panel::new(
+            input(&PanelField::Name).trim().to_string(),
+            self.input_fields.panel_kind,
+            required(PanelField::AreaM2)?,
+            [
+                required(PanelField::NormalX)?,
+                required(PanelField::NormalY)?,
+                required(PanelField::NormalZ)?,
+            ],
+            parse_optional_f64(input(&PanelField::SpecularReflectivity)).unwrap_or(0.0),
+            parse_optional_f64(input(&PanelField::DiffuseReflectivity)).unwrap_or(0.0),
+        )
+    }
+
+    /// Blank attitude angles and rates are treated as zero.
+    fn read_attitude_settings(&self) -> Result<AttitudeSettings, String> {
+        let optional_value = |field: AttitudeField| -> Result<f64, String> {
+            let s = self
+                .input_fields
+                .attitude_inputs
+                .get(&field)
+                .map(String::as_str)
+                .unwrap_or("");
+            if s.trim().is_empty() {
+                Ok(0.0)
+            } else {
+                parse_required_f64(field.label(), s)
+            }
+        };
+
+        Ok(AttitudeSettings {
+            mode: self.input_fields.attitude_mode,
+            inertial_roll_pitch_yaw_deg: [
+                optional_value(AttitudeField::InertialRollDeg)?,
+                optional_value(AttitudeField::InertialPitchDeg)?,
+                optional_value(AttitudeField::InertialYawDeg)?,
+            ],
+            tumble_rate_deg_per_s: optional_value(AttitudeField::TumbleRateDegPerS)?,
         })
     }
 
@@ -142,4 +726,56 @@ impl MyApp {
             drag_power_enable_space_weather: enable_sw,
         })
     }
+
+    fn constellation_input(&self, field: &ConstellationField) -> &str {
+        self.input_fields
+            .constellation_inputs
+            .get(field)
+            .map(String::as_str)
+            .unwrap_or("")
+    }
+
+    pub fn read_walker_pattern(&self) -> Result<WalkerPattern, String> {
+        let total = parse_required_u32(
+            ConstellationField::WalkerTotalSatellites.label(),
+            self.constellation_input(&ConstellationField::WalkerTotalSatellites),
+        )?;
+        let planes = parse_required_u32(
+            ConstellationField::WalkerPlanes.label(),
+            self.constellation_input(&ConstellationField::WalkerPlanes),
+        )?;
+        let phasing = parse_required_u32(
+            ConstellationField::WalkerPhasing.label(),
+            self.constellation_input(&ConstellationField::WalkerPhasing),
+        )?;
+
+        WalkerPattern::new(
+            self.input_fields.walker_pattern_kind,
+            total,
+            planes,
+            phasing,
+        )
+    }
+
+    pub fn read_rideshare_deployment(&self) -> Result<RideshareDeployment, String> {
+        let child_count = parse_required_u32(
+            ConstellationField::RideshareChildCount.label(),
+            self.constellation_input(&ConstellationField::RideshareChildCount),
+        )?;
+        let speed = parse_required_f64(
+            ConstellationField::RideshareSeparationSpeedMPerS.label(),
+            self.constellation_input(&ConstellationField::RideshareSeparationSpeedMPerS),
+        )?;
+        let cone = parse_required_f64(
+            ConstellationField::RideshareConeHalfAngleDeg.label(),
+            self.constellation_input(&ConstellationField::RideshareConeHalfAngleDeg),
+        )?;
+
+        RideshareDeployment::new(
+            child_count,
+            speed,
+            self.input_fields.rideshare_separation_direction,
+            cone,
+        )
+    }
 }
@@ -26,55 +26,79 @@ pub fn spawn_stepper_loop(run: Arc<Mutex<SimulationRun>>, tx: StepTx) {
             let step_interval_h = sim_run.initial.simulation_settings.step_interval_hours;
 
             // Inner loop: do work for up to SIMULATION_MAX_UI_UPDATE_PERIOD_MS, then send update
-            let outcome = loop {
-                if sim_run.hours_since_epoch() >= max_hours {
-                    break Ok(StepOutcome {
-                        done: true,
-                        status_line: format!(
-                            "Reached max time: {:.2} hours ({:.2} days).",
-                            max_hours,
-                            max_hours / 24.0
-                        ),
-                        latest_telemetry: sim_run.latest_telemetry.clone(),
-                    });
-                }
+            let outcome =
+                loop {
+                    if sim_run.hours_since_epoch() >= max_hours {
+                        break Ok(StepOutcome {
+                            done: true,
+                            status_line: format!(
+                                "Reached max time: {:.2} hours ({:.2} days).",
+                                max_hours,
+                                max_hours / 24.0
+                            ),
+                            latest_telemetry: sim_run.latest_telemetry.clone(),
+                            new_history: Vec::new(),
+                        });
+                    }
 
-                match sim_run.step().map_err(|e| format!("{e}")) {
-                    Ok(telemetry) => {
-                        if telemetry.is_deorbited {
-                            let deorbit_h =
-                                (telemetry.hours_since_epoch - step_interval_h).max(0.0);
-                            break Ok(StepOutcome {
-                                done: true,
-                                status_line: format!(
-                                    "Satellite deorbited at {:.2} hours ({:.2} days).",
-                                    deorbit_h,
-                                    deorbit_h / 24.0
-                                ),
-                                latest_telemetry: sim_run.latest_telemetry.clone(),
-                            });
+                    match sim_run.step().map_err(|e| format!("{e}")) {
+                        Ok(telemetry) => {
+                            if telemetry.is_deorbited {
+                                let deorbit_h =
+                                    (telemetry.hours_since_epoch - step_interval_h).max(0.0);
+                                break Ok(StepOutcome {
+                                    done: true,
+                                    status_line: format!(
+                                        "Satellite deorbited at {:.2} hours ({:.2} days).",
+                                        deorbit_h,
+                                        deorbit_h / 24.0
+                                    ),
+                                    latest_telemetry: sim_run.latest_telemetry.clone(),
+                                    new_history: Vec::new(),
+                                });
+                            }
+                            if let Some(segment) = telemetry.propulsion.as_ref().and_then(|p| {
+                                p.burn_segments.iter().find(|s| s.propellant_depleted)
+                            }) {
+                                break Ok(StepOutcome {
+                                    done: true,
+                                    status_line: format!(
+                                        "Propellant exhausted during burn \"{}\" at {}.",
+                                        segment.name,
+                                        segment.end.as_iso8601()
+                                    ),
+                                    latest_telemetry: sim_run.latest_telemetry.clone(),
+                                    new_history: Vec::new(),
+                                });
+                            }
                         }
+                        Err(e) => break Err(e),
                     }
-                    Err(e) => break Err(e),
-                }
 
-                if real_time_start.elapsed().as_millis()
-                    >= SIMULATION_MAX_UI_UPDATE_PERIOD_MS as u128
-                {
-                    let latest_telemetry = sim_run.latest_telemetry.as_ref().cloned();
-                    let status = latest_telemetry.as_ref().map(|tt| {
-                        format!("Sim running... t = {:.2} days", tt.hours_since_epoch / 24.0)
-                    });
-                    break Ok(StepOutcome {
-                        done: latest_telemetry
-                            .as_ref()
-                            .map(|x| x.hours_since_epoch >= max_hours)
-                            .unwrap_or(false),
-                        status_line: status.unwrap_or_else(|| "Sim running...".to_string()),
-                        latest_telemetry,
-                    });
-                }
-            };
+                    if real_time_start.elapsed().as_millis()
+                        >= SIMULATION_MAX_UI_UPDATE_PERIOD_MS as u128
+                    {
+                        let latest_telemetry = sim_run.latest_telemetry.as_ref().cloned();
+                        let status = latest_telemetry.as_ref().map(|tt| {
+                            format!("Sim running... t = {:.2} days", tt.hours_since_epoch / 24.0)
+                        });
+                        break Ok(StepOutcome {
+                            done: latest_telemetry
+                                .as_ref()
+                                .map(|x| x.hours_since_epoch >= max_hours)
+                                .unwrap_or(false),
+                            status_line: status.unwrap_or_else(|| "Sim running...".to_string()),
+                            latest_telemetry,
+                            new_history: Vec::new(),
+                        });
+                    }
+                };
+
+            // Forward the steps completed since the last update.
+            let outcome = outcome.map(|mut o| {
+                o.new_history = sim_run.take_history();
+                o
+            });
 
             drop(guard);
 
@@ -0,0 +1,289 @@
+use std::sync::mpsc;
+
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+use strum::IntoEnumIterator;
+
+use crate::tle_history::{TleHistory, TleSelection, parse_tle_sets};
+use crate::ui::actions::{MyApp, SIMULATION_MAX_UI_UPDATE_PERIOD_MS};
+use crate::ui::fields::TleAccuracyField;
+
+impl MyApp {
+    fn on_load_tle_history(&mut self) {
+        let path = self.input_fields.tle_history_path.trim().to_string();
+        let history = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {path}: {e}"))
+            .and_then(|text| parse_tle_sets(&text))
+            .and_then(|sets| TleHistory::new(sets, self.input_fields.tle_selection));
+        let history = match history {
+            Ok(history) => history,
+            Err(e) => {
+                self.run_status = format!("Invalid TLE history: {e}");
+                return;
+            }
+        };
+        let discontinuities = match history.discontinuities() {
+            Ok(discontinuities) => discontinuities,
+            Err(e) => {
+                self.run_status = format!("Failed to propagate the TLE history: {e}");
+                return;
+            }
+        };
+
+        // The run starts at the earliest set.
+        let first = &history.sets[0];
+        match first.to_satkit_tle().to_2line() {
+            Ok([line1, line2]) => {
+                self.tle_line0 = first.name.clone();
+                self.tle_line1 = line1;
+                self.tle_line2 = line2;
+                self.try_parse_tle();
+            }
+            Err(e) => {
+                self.run_status = format!("Failed to format the first element set: {e}");
+                return;
+            }
+        }
+        self.run_status = format!(
+            "Loaded {} element sets from {} to {}.",
+            history.sets.len(),
+            first.epoch.as_iso8601(),
+            history.sets[history.sets.len() - 1].epoch.as_iso8601()
+        );
+        self.tle_history = history.sets;
+        self.tle_history_discontinuities = discontinuities;
+        self.tle_accuracy = None;
+        self.tle_accuracy_rx = None;
+        self.decay_calibration = None;
+        self.decay_calibration_rx = None;
+    }
+
+    fn on_validate_tle_accuracy(&mut self) {
+        let settings = match self.read_accuracy_validation_settings() {
+            Ok(settings) => settings,
+            Err(e) => {
+                self.run_status = format!("Invalid accuracy validation settings: {e}");
+                return;
+            }
+        };
+        let history =
+            match TleHistory::new(self.tle_history.clone(), self.input_fields.tle_selection) {
+                Ok(history) => history,
+                Err(e) => {
+                    self.run_status = format!("Accuracy validation failed: {e}");
+                    return;
+                }
+            };
+
+        let (tx, rx) = mpsc::channel();
+        self.tle_accuracy_rx = Some(rx);
+        self.tle_accuracy = None;
+        self.run_status = format!(
+            "Validating accuracy over {} element sets...",
+            history.sets.len()
+        );
+
+        std::thread::spawn(move || {
+            let result = history
+                .validate_accuracy(&settings)
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+    }
+
+    pub fn poll_tle_accuracy(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.tle_accuracy_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(validation)) => {
+                self.run_status = match validation.max_age_within_tolerance_days {
+                    Some(days) => format!(
+                        "Predictions stay within {:.3} km RMS up to {days:.2} days old ({} comparisons, {} failed).",
+                        validation.tolerance_m / 1000.0,
+                        validation.errors.len(),
+                        validation.failed_pairs.len()
+                    ),
+                    None => format!(
+                        "Predictions exceed {:.3} km RMS at every age ({} comparisons, {} failed).",
+                        validation.tolerance_m / 1000.0,
+                        validation.errors.len(),
+                        validation.failed_pairs.len()
+                    ),
+                };
+                self.tle_accuracy = Some(validation);
+                self.tle_accuracy_rx = None;
+            }
+            Ok(Err(e)) => {
+                self.run_status = format!("Accuracy validation failed: {e}");
+                self.tle_accuracy_rx = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(
+                    SIMULATION_MAX_UI_UPDATE_PERIOD_MS as u64,
+                ));
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.run_status = "Accuracy validation worker stopped unexpectedly.".into();
+                self.tle_accuracy_rx = None;
+            }
+        }
+    }
+
+    pub fn tle_history_section(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("TLE history file");
+            ui.text_edit_singleline(&mut self.input_fields.tle_history_path);
+            if ui.button("Load TLE History").clicked() {
+                self.on_load_tle_history();
+            }
+            if !self.tle_history.is_empty() && ui.button("Clear").clicked() {
+                self.tle_history.clear();
+                self.tle_history_discontinuities.clear();
+                self.tle_accuracy = None;
+                self.tle_accuracy_rx = None;
+                self.decay_calibration = None;
+                self.decay_calibration_rx = None;
+            }
+        });
+        if self.tle_history.is_empty() {
+            return;
+        }
+
+        egui::ComboBox::from_label("Element set used at each step")
+            .selected_text(self.input_fields.tle_selection.label())
+            .show_ui(ui, |ui| {
+                for selection in TleSelection::iter() {
+                    ui.selectable_value(
+                        &mut self.input_fields.tle_selection,
+                        selection,
+                        selection.label(),
+                    );
+                }
+            });
+
+        let jumps_km = self
+            .tle_history_discontinuities
+            .iter()
+            .map(|d| d.position_m / 1000.0)
+            .collect::<Vec<_>>();
+        let mut summary = format!("{} element sets", self.tle_history.len());
+        if !jumps_km.is_empty() {
+            summary += &format!(
+                "; position jump between sets: mean {:.3} km, max {:.3} km",
+                jumps_km.iter().sum::<f64>() / jumps_km.len() as f64,
+                jumps_km.iter().copied().fold(0.0, f64::max)
+            );
+        }
+        ui.label(summary);
+
+        egui::CollapsingHeader::new("Discontinuities between element sets").show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .id_salt("tle_discontinuity_scroll")
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("tle_discontinuity_grid")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("Epoch (UTC)");
+                            ui.label("Gap (days)");
+                            ui.label("Jump (km)");
+                            ui.label("Radial / transverse / normal (km)");
+                            ui.end_row();
+                            for d in &self.tle_history_discontinuities {
+                                let [r, t, n] = d.position_rtn_m.map(|m| m / 1000.0);
+                                ui.label(d.epoch.as_iso8601());
+                                ui.label(format!("{:.3}", (d.epoch - d.previous_epoch).as_days()));
+                                ui.label(format!("{:.3}", d.position_m / 1000.0));
+                                ui.label(format!("{r:.3} / {t:.3} / {n:.3}"));
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+
+        egui::CollapsingHeader::new("Prediction Accuracy").show(ui, |ui| {
+            self.tle_accuracy_section(ui);
+        });
+        egui::CollapsingHeader::new("Element Trends and Drag Calibration").show(ui, |ui| {
+            self.decay_trend_section(ui);
+        });
+    }
+
+    fn tle_accuracy_section(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            "Propagates every set forward and compares it with the later sets at their epochs.",
+        );
+        for f in TleAccuracyField::iter() {
+            let mut val = self
+                .input_fields
+                .tle_accuracy_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .tle_accuracy_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+        let running = self.tle_accuracy_rx.is_some();
+        if ui
+            .add_enabled(!running, egui::Button::new("Validate Accuracy"))
+            .clicked()
+        {
+            self.on_validate_tle_accuracy();
+        }
+
+        let Some(validation) = &self.tle_accuracy else {
+            return;
+        };
+        if let Some((predictor_epoch, truth_epoch, error)) = validation.failed_pairs.first() {
+            ui.label(format!(
+                "{} pairs failed to propagate, e.g. {} to {}: {error}",
+                validation.failed_pairs.len(),
+                predictor_epoch.as_iso8601(),
+                truth_epoch.as_iso8601()
+            ));
+        }
+        let curve = |name: &str, axis: usize| {
+            let points: PlotPoints = validation
+                .bins
+                .iter()
+                .map(|bin| [bin.max_age_days, bin.rms_rtn_m[axis] / 1000.0])
+                .collect();
+            Line::new(name.to_string(), points)
+        };
+        Plot::new("tle_accuracy_plot")
+            .height(250.0)
+            .legend(Legend::default())
+            .x_axis_label("Prediction age (days)")
+            .y_axis_label("RMS error (km)")
+            .show(ui, |plot_ui| {
+                plot_ui.line(curve("Radial", 0));
+                plot_ui.line(curve("Along-track", 1));
+                plot_ui.line(curve("Cross-track", 2));
+            });
+
+        egui::Grid::new("tle_accuracy_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Age up to (days)");
+                ui.label("Comparisons");
+                ui.label("RMS radial / along / cross (km)");
+                ui.label("RMS total (km)");
+                ui.end_row();
+                for bin in &validation.bins {
+                    let [r, a, c] = bin.rms_rtn_m.map(|m| m / 1000.0);
+                    ui.label(format!("{:.2}", bin.max_age_days));
+                    ui.label(bin.samples.to_string());
+                    ui.label(format!("{r:.3} / {a:.3} / {c:.3}"));
+                    ui.label(format!("{:.3}", bin.rms_m() / 1000.0));
+                    ui.end_row();
+                }
+            });
+    }
+}
@@ -0,0 +1,103 @@
+use eframe::egui;
+use strum::IntoEnumIterator;
+
+use crate::passes::find_pass_windows;
+use crate::ui::actions::MyApp;
+use crate::ui::fields::{UncertaintyField, UncertaintyKind};
+
+impl MyApp {
+    pub fn uncertainty_section(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Position Uncertainty");
+        egui::ComboBox::from_label("Uncertainty model")
+            .selected_text(self.input_fields.uncertainty_kind.label())
+            .show_ui(ui, |ui| {
+                for kind in UncertaintyKind::iter() {
+                    ui.selectable_value(
+                        &mut self.input_fields.uncertainty_kind,
+                        kind,
+                        kind.label(),
+                    );
+                }
+            });
+
+        let kind = self.input_fields.uncertainty_kind;
+        for f in UncertaintyField::iter().filter(|f| f.applies_to(kind)) {
+            let mut val = self
+                .input_fields
+                .uncertainty_inputs
+                .get(&f)
+                .cloned()
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label(f.label());
+                if ui.text_edit_singleline(&mut val).changed() {
+                    self.input_fields
+                        .uncertainty_inputs
+                        .insert(f.clone(), val.clone());
+                }
+            });
+        }
+        if kind != UncertaintyKind::None {
+            ui.horizontal(|ui| {
+                ui.label("Pass window margin (σ)");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.input_fields.pass_window_sigmas_input)
+                        .hint_text("3"),
+                );
+            });
+        }
+    }
+
+    pub fn pass_window_section(&mut self, ui: &mut egui::Ui) {
+        let passes = find_pass_windows(&self.telemetry_history, &self.run_ground_stations);
+        if passes.is_empty() {
+            return;
+        }
+
+        ui.heading("Pass Windows");
+        let sigmas = match self.read_pass_window_sigmas() {
+            Ok(sigmas) => sigmas,
+            Err(e) => {
+                ui.label(format!("Invalid pass window margin: {e}"));
+                return;
+            }
+        };
+        let has_uncertainty = passes.iter().any(|p| p.aos_sigma_seconds.is_some());
+        egui::ScrollArea::vertical()
+            .id_salt("pass_window_scroll")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                egui::Grid::new("pass_window_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("Station");
+                        ui.label("AOS (UTC)");
+                        ui.label("LOS (UTC)");
+                        ui.label("Max elevation (deg)");
+                        if has_uncertainty {
+                            ui.label("AOS / LOS 1σ (s)");
+                            ui.label(format!("Widened AOS (−{sigmas}σ)"));
+                            ui.label(format!("Widened LOS (+{sigmas}σ)"));
+                        }
+                        ui.end_row();
+                        for pass in &passes {
+                            ui.label(&pass.station);
+                            ui.label(pass.aos.as_iso8601());
+                            ui.label(pass.los.as_iso8601());
+                            ui.label(format!("{:.1}", pass.max_elevation_deg));
+                            if has_uncertainty {
+                                let (aos, los) = pass.widened(sigmas);
+                                ui.label(format!(
+                                    "{:.1} / {:.1}",
+                                    pass.aos_sigma_seconds.unwrap_or(0.0),
+                                    pass.los_sigma_seconds.unwrap_or(0.0)
+                                ));
+                                ui.label(aos.as_iso8601());
+                                ui.label(los.as_iso8601());
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
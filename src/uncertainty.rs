@@ -0,0 +1,216 @@
+use nalgebra::SMatrix;
+use satkit::Instant;
+use serde::{Deserialize, Serialize};
+
+/// Radial/along-track/cross-track position and velocity, then the relative error of B*.
+type Covariance = SMatrix<f64, 7, 7>;
+
+/// Where the position uncertainty of a run comes from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum UncertaintyModel {
+    /// A covariance at the TLE epoch, propagated alongside the state.
+    Covariance(InitialCovariance),
+    /// Empirical growth of the error with the age of the TLE.
+    TleAge(TleAgeErrorGrowth),
+}
+
+/// Diagonal covariance at the TLE epoch, in the radial/along-track/cross-track frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitialCovariance {
+    pub position_sigma_m: [f64; 3],
+    pub velocity_sigma_m_per_s: [f64; 3],
+    /// Relative error of B* (0.2 for 20 %). Mis-modelled drag is what makes TLE errors grow
+    /// along-track over days.
+    pub bstar_relative_sigma: f64,
+}
+
+impl InitialCovariance {
+    pub fn new(
+        position_sigma_m: [f64; 3],
+        velocity_sigma_m_per_s: [f64; 3],
+        bstar_relative_sigma: f64,
+    ) -> Result<Self, String> {
+        if position_sigma_m
+            .iter()
+            .chain(&velocity_sigma_m_per_s)
+            .chain(std::iter::once(&bstar_relative_sigma))
+            .any(|sigma| *sigma < 0.0)
+        {
+            return Err("Uncertainty sigmas must be >= 0".into());
+        }
+        Ok(Self {
+            position_sigma_m,
+            velocity_sigma_m_per_s,
+            bstar_relative_sigma,
+        })
+    }
+}
+
+/// 1-sigma error of a TLE as a function of its age, per radial/along-track/cross-track axis:
+/// `sigma_at_epoch + growth * age`, plus a quadratic along-track term for the drift that
+/// mis-modelled decay causes. Typical LEO element sets are good to a few hundred metres
+/// radially and a kilometre or so along-track at epoch, and lose a few kilometres along-track
+/// per day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TleAgeErrorGrowth {
+    pub sigma_at_epoch_m: [f64; 3],
+    pub growth_m_per_day: [f64; 3],
+    pub along_track_growth_m_per_day2: f64,
+}
+
+impl TleAgeErrorGrowth {
+    pub fn new(
+        sigma_at_epoch_m: [f64; 3],
+        growth_m_per_day: [f64; 3],
+        along_track_growth_m_per_day2: f64,
+    ) -> Result<Self, String> {
+        if sigma_at_epoch_m
+            .iter()
+            .chain(&growth_m_per_day)
+            .chain(std::iter::once(&along_track_growth_m_per_day2))
+            .any(|value| *value < 0.0)
+        {
+            return Err("TLE age error growth terms must be >= 0".into());
+        }
+        Ok(Self {
+            sigma_at_epoch_m,
+            growth_m_per_day,
+            along_track_growth_m_per_day2,
+        })
+    }
+
+    pub fn uncertainty(&self, age_days: f64) -> PositionUncertainty {
+        let age_days = age_days.abs();
+        let sigma =
+            |axis: usize| self.sigma_at_epoch_m[axis] + self.growth_m_per_day[axis] * age_days;
+        PositionUncertainty {
+            radial_sigma_m: sigma(0),
+            along_track_sigma_m: sigma(1) + self.along_track_growth_m_per_day2 * age_days.powi(2),
+            cross_track_sigma_m: sigma(2),
+        }
+    }
+}
+
+/// 1-sigma position error at one step.
+#[derive(Debug, Clone, Copy)]
+pub struct PositionUncertainty {
+    pub radial_sigma_m: f64,
+    pub along_track_sigma_m: f64,
+    pub cross_track_sigma_m: f64,
+}
+
+/// Clohessy-Wiltshire state transition over `dt_seconds` on a circular orbit of mean motion
+/// `n_rad_per_s`, with a constant along-track drag deceleration of `drag_m_per_s2` scaled by
+/// the relative B* error.
+fn state_transition(n_rad_per_s: f64, dt_seconds: f64, drag_m_per_s2: f64) -> Covariance {
+    let n = n_rad_per_s;
+    let nt = n * dt_seconds;
+    let (s, c) = nt.sin_cos();
+    let mut phi = Covariance::zeros();
+
+    // Radial.
+    phi[(0, 0)] = 4.0 - 3.0 * c;
+    phi[(0, 3)] = s / n;
+    phi[(0, 4)] = 2.0 * (1.0 - c) / n;
+    // Along-track.
+    phi[(1, 0)] = 6.0 * (s - nt);
+    phi[(1, 1)] = 1.0;
+    phi[(1, 3)] = -2.0 * (1.0 - c) / n;
+    phi[(1, 4)] = (4.0 * s - 3.0 * nt) / n;
+    // Cross-track.
+    phi[(2, 2)] = c;
+    phi[(2, 5)] = s / n;
+    // Radial velocity.
+    phi[(3, 0)] = 3.0 * n * s;
+    phi[(3, 3)] = c;
+    phi[(3, 4)] = 2.0 * s;
+    // Along-track velocity.
+    phi[(4, 0)] = -6.0 * n * (1.0 - c);
+    phi[(4, 3)] = -2.0 * s;
+    phi[(4, 4)] = 4.0 * c - 3.0;
+    // Cross-track velocity.
+    phi[(5, 2)] = -n * s;
+    phi[(5, 5)] = c;
+
+    // Response to a constant along-track acceleration f = -drag * (B* error).
+    let f = -drag_m_per_s2 / n.powi(2);
+    phi[(0, 6)] = 2.0 * f * (nt - s);
+    phi[(1, 6)] = f * (4.0 * (1.0 - c) - 1.5 * nt.powi(2));
+    phi[(3, 6)] = 2.0 * f * n * (1.0 - c);
+    phi[(4, 6)] = f * n * (4.0 * s - 3.0 * nt);
+    phi[(6, 6)] = 1.0;
+    phi
+}
+
+fn initial_covariance(model: &UncertaintyModel) -> Covariance {
+    match model {
+        UncertaintyModel::Covariance(initial) => {
+            let [r, a, c] = initial.position_sigma_m;
+            let [vr, va, vc] = initial.velocity_sigma_m_per_s;
+            Covariance::from_diagonal(
+                &[r, a, c, vr, va, vc, initial.bstar_relative_sigma]
+                    .map(|sigma| sigma * sigma)
+                    .into(),
+            )
+        }
+        UncertaintyModel::TleAge(_) => Covariance::zeros(),
+    }
+}
+
+/// Carries the uncertainty of a run from step to step.
+#[derive(Debug, Clone)]
+pub struct UncertaintyPropagator {
+    model: UncertaintyModel,
+    covariance: Covariance,
+    last_time: Option<Instant>,
+}
+
+impl UncertaintyPropagator {
+    pub fn new(model: UncertaintyModel) -> Self {
+        Self {
+            covariance: initial_covariance(&model),
+            model,
+            last_time: None,
+        }
+    }
+
+    /// Start again from the initial covariance, for a freshly fitted element set.
+    pub fn restart(&mut self) {
+        self.covariance = initial_covariance(&self.model);
+    }
+
+    /// Uncertainty at `time`, the next step of the run.
+    ///
+    /// The covariance is propagated linearly about a circular orbit at the current mean
+    /// semi-major axis, with the drag deceleration held constant over the step; this is
+    /// adequate for near-circular LEO orbits and steps short compared to a day.
+    pub fn step(
+        &mut self,
+        time: &Instant,
+        epoch: &Instant,
+        mean_semi_major_axis_m: f64,
+        drag_deceleration_m_per_s2: f64,
+    ) -> PositionUncertainty {
+        let last_time = self.last_time.replace(*time);
+        match &self.model {
+            UncertaintyModel::TleAge(growth) => growth.uncertainty((*time - *epoch).as_days()),
+            UncertaintyModel::Covariance(_) => {
+                if let Some(last_time) = last_time {
+                    let n_rad_per_s =
+                        (satkit::consts::MU_EARTH / mean_semi_major_axis_m.powi(3)).sqrt();
+                    let phi = state_transition(
+                        n_rad_per_s,
+                        (*time - last_time).as_seconds(),
+                        drag_deceleration_m_per_s2,
+                    );
+                    self.covariance = phi * self.covariance * phi.transpose();
+                }
+                PositionUncertainty {
+                    radial_sigma_m: self.covariance[(0, 0)].sqrt(),
+                    along_track_sigma_m: self.covariance[(1, 1)].sqrt(),
+                    cross_track_sigma_m: self.covariance[(2, 2)].sqrt(),
+                }
+            }
+        }
+    }
+}